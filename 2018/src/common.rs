@@ -1,5 +1,7 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+pub use anyhow::{bail, ensure, Context as _, Error};
+
+pub type Result<T = (), E = Error> = std::result::Result<T, E>;
+pub type Lines<'a> = &'a [&'a str];
 
 #[macro_export]
 macro_rules! iff {
@@ -11,11 +13,3 @@ macro_rules! iff {
         }
     };
 }
-
-pub fn read_file_lines(filename: &str) -> Vec<String> {
-    let f = File::open(filename).unwrap();
-    BufReader::new(f)
-        .lines()
-        .map(|l| l.unwrap())
-        .collect::<Vec<_>>()
-}
@@ -1,11 +1,14 @@
-use crate::common::read_file_lines;
+use crate::common::*;
 use std::collections::HashSet;
 
-pub fn run(_: &[&str]) {
-    let lines = read_file_lines("inputs/day1")
-        .into_iter()
-        .map(|x| x.parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+pub fn run(lines: Lines) -> Result {
+    let lines = lines
+        .iter()
+        .map(|x| {
+            x.parse::<i64>()
+                .with_context(|| format!("invalid line: {:?}", x))
+        })
+        .collect::<Result<Vec<_>>>()?;
 
     // iterate over list once
     let mut value = 0i64;
@@ -28,4 +31,6 @@ pub fn run(_: &[&str]) {
     }
 
     println!("answer B: {}", value);
+
+    Ok(())
 }
@@ -1,17 +1,16 @@
-use crate::common::read_file_lines;
+use crate::common::*;
 use std::collections::HashMap;
 
 pub fn chars_difference(a: &str, b: &str) -> usize {
     a.chars().zip(b.chars()).filter(|(x, y)| x != y).count()
 }
 
-pub fn run(_: &[&str]) {
-    let codes = read_file_lines("inputs/day2");
+pub fn run(codes: Lines) -> Result {
     let mut counts = HashMap::<char, i32>::new();
     let mut two_count = 0;
     let mut three_count = 0;
 
-    for code in &codes {
+    for code in codes {
         counts.clear();
 
         for c in code.chars() {
@@ -29,8 +28,8 @@ pub fn run(_: &[&str]) {
 
     println!("answer A: {}", two_count * three_count);
 
-    for a in &codes {
-        for b in &codes {
+    for a in codes {
+        for b in codes {
             let diff = chars_difference(a, b);
 
             if diff == 1 {
@@ -38,4 +37,6 @@ pub fn run(_: &[&str]) {
             }
         }
     }
+
+    Ok(())
 }
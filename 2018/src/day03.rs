@@ -1,24 +1,34 @@
-use crate::common::read_file_lines;
-use regex::Regex;
+use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use std::collections::HashSet;
 
-pub fn run(_: &[&str]) {
-    const SIZE: usize = 1000;
-    let mut fabric = vec![vec![(0, -1); SIZE]; SIZE];
-    let mut nonoverlapping = HashSet::new();
+fn parse_claim(c: &mut Cursor) -> ParseResult<[usize; 5]> {
+    c.tag("#")?;
+    let id = c.int()?;
+    c.tag(" @ ")?;
+    let x = c.int()?;
+    c.tag(",")?;
+    let y = c.int()?;
+    c.tag(": ")?;
+    let width = c.int()?;
+    c.tag("x")?;
+    let height = c.int()?;
 
-    let re = Regex::new(r"#(\d+) @ (\d+),(\d+): (\d+)x(\d+)").unwrap();
+    Ok([id, x, y, width, height])
+}
 
-    for line in read_file_lines("inputs/day3") {
-        let cap = re.captures(&line).unwrap();
+pub fn run(lines: Lines) -> Result {
+    let claims = Block::new(lines).parse_lines(parse_claim)?;
+    let size = claims
+        .iter()
+        .map(|c| (c[1] + c[3]).max(c[2] + c[4]))
+        .max()
+        .unwrap_or(0);
 
-        let numbers = cap
-            .iter()
-            .skip(1)
-            .map(|m| m.unwrap().as_str())
-            .map(|x| x.parse::<usize>().unwrap())
-            .collect::<Vec<_>>();
+    let mut fabric = vec![vec![(0, -1); size]; size];
+    let mut nonoverlapping = HashSet::new();
 
+    for numbers in claims {
         let claim = numbers[0] as i32;
         let x0 = numbers[1];
         let y0 = numbers[2];
@@ -53,4 +63,6 @@ pub fn run(_: &[&str]) {
 
     println!("answer A: {}", total);
     println!("answer B: {:?}", nonoverlapping);
+
+    Ok(())
 }
@@ -1,5 +1,5 @@
-use crate::common::read_file_lines;
-use regex::Regex;
+use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use std::collections::HashMap;
 
 type Date = (i32, i32, i32, i32, i32);
@@ -17,38 +17,38 @@ struct Record {
     event: Event,
 }
 
-pub fn run(_: &[&str]) {
-    let re_line = Regex::new(r"\[(\d\d\d\d)[-](\d\d)[-](\d\d) (\d\d):(\d\d)\] (.*)").unwrap();
-    let re_guard = Regex::new(r"Guard #(\d+) begins shift").unwrap();
-
-    let mut records = vec![];
-
-    for line in read_file_lines("inputs/day4") {
-        let cap = re_line.captures(&line).unwrap();
-        let mut matches = cap.iter().map(|x| x.unwrap()).map(|x| x.as_str()).skip(1);
-
-        let date = matches
-            .by_ref()
-            .take(5)
-            .map(|x| x.parse::<i32>().unwrap())
-            .collect::<Vec<_>>();
-
-        let event = match matches.next().unwrap() {
-            "falls asleep" => Event::FallAsleep,
-            "wakes up" => Event::WakeUp,
-            msg => {
-                let matches = re_guard.captures(&msg).unwrap();
-                let id = matches.get(1).unwrap().as_str().parse().unwrap();
-                Event::BeginShift(id)
-            }
-        };
-
-        records.push(Record {
-            date: (date[0], date[1], date[2], date[3], date[4]),
-            event: event,
-        })
-    }
+fn parse_record(c: &mut Cursor) -> ParseResult<Record> {
+    c.tag("[")?;
+    let year = c.int()?;
+    c.tag("-")?;
+    let month = c.int()?;
+    c.tag("-")?;
+    let day = c.int()?;
+    c.tag(" ")?;
+    let hour = c.int()?;
+    c.tag(":")?;
+    let minute = c.int()?;
+    c.tag("] ")?;
+
+    let event = if c.eat("falls asleep") {
+        Event::FallAsleep
+    } else if c.eat("wakes up") {
+        Event::WakeUp
+    } else {
+        c.tag("Guard #")?;
+        let id = c.int()?;
+        c.tag(" begins shift")?;
+        Event::BeginShift(id)
+    };
+
+    Ok(Record {
+        date: (year, month, day, hour, minute),
+        event,
+    })
+}
 
+pub fn run(lines: Lines) -> Result {
+    let mut records = Block::new(lines).parse_lines(parse_record)?;
     records.sort_by_key(|r| r.date);
 
     let mut active_id = 0;
@@ -79,7 +79,11 @@ pub fn run(_: &[&str]) {
         }
     }
 
-    let best_guard = *sleep_total.iter().max_by_key(|p| p.1).map(|p| p.0).unwrap();
+    let best_guard = *sleep_total
+        .iter()
+        .max_by_key(|p| p.1)
+        .map(|p| p.0)
+        .context("no guard ever falls asleep")?;
 
     let minute = sleep_minute
         .iter()
@@ -111,4 +115,6 @@ pub fn run(_: &[&str]) {
         answer.1,
         answer.0 * answer.1
     );
+
+    Ok(())
 }
@@ -1,4 +1,4 @@
-use crate::common::read_file_lines;
+use crate::common::*;
 
 fn invert_case(c: char) -> char {
     if c.is_ascii_lowercase() {
@@ -34,10 +34,9 @@ pub fn shrink_polymere(mut vec: Vec<char>) -> Vec<char> {
     }
 }
 
-pub fn run(_: &[&str]) {
-    let mut vec = read_file_lines("inputs/day5")[0]
-        .chars()
-        .collect::<Vec<_>>();
+pub fn run(lines: Lines) -> Result {
+    ensure!(lines.len() == 1, "expected a single line");
+    let mut vec = lines[0].chars().collect::<Vec<_>>();
     vec = shrink_polymere(vec);
 
     println!("answer A: {:?}", vec.len());
@@ -64,4 +63,6 @@ pub fn run(_: &[&str]) {
     }
 
     println!("answer B: {:?}", best);
+
+    Ok(())
 }
@@ -1,4 +1,5 @@
-use crate::common::read_file_lines;
+use crate::common::*;
+use advent_common::parse::Block;
 
 pub fn area_per_center(
     centers: &[(i32, i32)],
@@ -53,23 +54,42 @@ pub fn total_distance_less_than_1000(
     output
 }
 
-pub fn run(_: &[&str]) {
-    let coords = read_file_lines("inputs/day6")
-        .into_iter()
-        .map(|line| {
-            let index = line.find(',').unwrap();
-            (line[..index].to_string(), line[index + 2..].to_string())
-        })
-        .map(|(x, y)| (x.parse::<i32>().unwrap(), y.parse::<i32>().unwrap()))
-        .collect::<Vec<_>>();
+pub fn run(lines: Lines) -> Result {
+    let coords = Block::new(lines).parse_lines(|c| {
+        let x = c.int()?;
+        c.tag(", ")?;
+        let y = c.int()?;
+        Ok((x, y))
+    })?;
 
-    let a = area_per_center(&coords, (-1500, 2500), (-1500, 2500));
-    let b = area_per_center(&coords, (-2500, 3500), (-2500, 3500));
+    ensure!(!coords.is_empty(), "no coordinates given");
+    let xmin = coords.iter().map(|p| p.0).min().unwrap();
+    let xmax = coords.iter().map(|p| p.0).max().unwrap();
+    let ymin = coords.iter().map(|p| p.1).min().unwrap();
+    let ymax = coords.iter().map(|p| p.1).max().unwrap();
 
-    let result = a.iter().zip(b.iter()).filter(|v| v.0 == v.1).max().unwrap();
+    // Any area that reaches the edge of the bounding box is infinite, so it
+    // keeps growing if the box is made larger.
+    let a = area_per_center(&coords, (xmin, xmax), (ymin, ymax));
+    let b = area_per_center(&coords, (xmin - 1, xmax + 1), (ymin - 1, ymax + 1));
+
+    let result = a
+        .iter()
+        .zip(b.iter())
+        .filter(|v| v.0 == v.1)
+        .max()
+        .context("all areas are infinite")?;
 
     println!("answer A: {:?}", result);
 
-    let result = total_distance_less_than_1000(&coords, (-2500, 3500), (-2500, 3500));
+    // Beyond this margin the distances to all centers add up to at least 10000.
+    let margin = 10000 / coords.len() as i32 + 1;
+    let result = total_distance_less_than_1000(
+        &coords,
+        (xmin - margin, xmax + margin),
+        (ymin - margin, ymax + margin),
+    );
     println!("answer B: {:?}", result);
+
+    Ok(())
 }
@@ -1,8 +1,8 @@
-use crate::common::read_file_lines;
-use regex::Regex;
+use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use std::collections::HashSet;
 
-fn part_a(edges: &[(char, char)]) {
+fn part_a(edges: &[(char, char)]) -> Result {
     let mut pending = HashSet::new();
     for (a, b) in edges.iter().cloned() {
         pending.insert(a);
@@ -20,13 +20,18 @@ fn part_a(edges: &[(char, char)]) {
             }
         }
 
-        let candidate = candidates.iter().min().unwrap();
+        let candidate = candidates
+            .iter()
+            .min()
+            .context("steps depend on each other in a cycle")?;
 
         order.push(*candidate);
         pending.remove(candidate);
     }
 
     println!("answer A: {}", order);
+
+    Ok(())
 }
 
 fn part_b(edges: &[(char, char)]) {
@@ -76,23 +81,26 @@ fn part_b(edges: &[(char, char)]) {
         }
     }
 
-    let total_time = workers.iter().map(|w| w.0).max().unwrap();
+    let total_time = workers.iter().map(|w| w.0).max().unwrap_or(0);
 
     println!("answer B: {:?}", total_time);
 }
 
-pub fn run(_: &[&str]) {
-    let re = Regex::new(r"Step (.) must be finished before step (.) can begin.").unwrap();
-    let mut edges = vec![];
+fn parse_edge(c: &mut Cursor) -> ParseResult<(char, char)> {
+    c.tag("Step ")?;
+    let src = c.next_char()?;
+    c.tag(" must be finished before step ")?;
+    let dst = c.next_char()?;
+    c.tag(" can begin.")?;
 
-    for line in read_file_lines("inputs/day7") {
-        let matches = re.captures(&line).unwrap();
-        let src = matches.get(1).unwrap().as_str().chars().next().unwrap();
-        let dst = matches.get(2).unwrap().as_str().chars().next().unwrap();
+    Ok((src, dst))
+}
 
-        edges.push((src, dst));
-    }
+pub fn run(lines: Lines) -> Result {
+    let edges = Block::new(lines).parse_lines(parse_edge)?;
 
-    part_a(&edges);
+    part_a(&edges)?;
     part_b(&edges);
+
+    Ok(())
 }
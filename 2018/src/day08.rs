@@ -1,25 +1,26 @@
-use crate::common::read_file_lines;
+use crate::common::*;
 
 struct Node {
     children: Vec<Node>,
     entries: Vec<i32>,
 }
 
-fn parse_entry(data: &mut impl Iterator<Item = i32>) -> Node {
-    let n = data.next().unwrap();
-    let m = data.next().unwrap();
+fn parse_entry(data: &mut impl Iterator<Item = i32>) -> Result<Node> {
+    let mut next = || data.next().context("unexpected end of input");
+    let n = next()?;
+    let m = next()?;
     let mut children = vec![];
     let mut entries = vec![];
 
     for _ in 0..n {
-        children.push(parse_entry(data));
+        children.push(parse_entry(data)?);
     }
 
     for _ in 0..m {
-        entries.push(data.next().unwrap());
+        entries.push(data.next().context("unexpected end of input")?);
     }
 
-    Node { children, entries }
+    Ok(Node { children, entries })
 }
 
 fn sum_part_a(node: &Node) -> i32 {
@@ -33,7 +34,10 @@ fn sum_part_b(node: &Node) -> i32 {
         node.entries
             .iter()
             .map(|i| {
-                if let Some(child) = node.children.get(*i as usize - 1) {
+                if let Some(child) = (*i as usize)
+                    .checked_sub(1)
+                    .and_then(|i| node.children.get(i))
+                {
                     sum_part_b(&child)
                 } else {
                     0
@@ -43,12 +47,25 @@ fn sum_part_b(node: &Node) -> i32 {
     }
 }
 
-pub fn run(_: &[&str]) {
-    let line = read_file_lines("inputs/day8")[0].to_string();
-    let mut iter = line.split(" ").map(|x| x.parse::<i32>().unwrap());
+pub fn run(lines: Lines) -> Result {
+    ensure!(lines.len() == 1, "expected a single line");
+    let numbers = lines[0]
+        .split(' ')
+        .map(|x| {
+            x.parse::<i32>()
+                .with_context(|| format!("invalid number: {:?}", x))
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-    let root = parse_entry(&mut iter);
+    let mut iter = numbers.into_iter();
+    let root = parse_entry(&mut iter)?;
+    ensure!(
+        iter.next().is_none(),
+        "trailing numbers after the root node"
+    );
 
     println!("answer A: {}", sum_part_a(&root));
     println!("answer B: {}", sum_part_b(&root));
+
+    Ok(())
 }
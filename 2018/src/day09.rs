@@ -1,3 +1,5 @@
+use crate::common::*;
+use advent_common::parse::parse_line;
 use std::collections::VecDeque;
 
 fn rotate<T>(vec: &mut VecDeque<T>, n: i32) {
@@ -38,7 +40,20 @@ fn play_game(num_players: usize, num_marbles: usize) -> usize {
     *scores.iter().max().unwrap()
 }
 
-pub fn run(_: &[&str]) {
-    println!("answer A: {}", play_game(455, 71223));
-    println!("answer B: {}", play_game(455, 7122300));
+pub fn run(lines: Lines) -> Result {
+    ensure!(lines.len() == 1, "expected a single line");
+    let (num_players, last_marble) = parse_line(1, lines[0], |c| {
+        let num_players = c.int()?;
+        c.tag(" players; last marble is worth ")?;
+        let last_marble = c.int()?;
+        c.tag(" points")?;
+        Ok((num_players, last_marble))
+    })?;
+
+    ensure!(num_players > 0, "there must be at least one player");
+
+    println!("answer A: {}", play_game(num_players, last_marble));
+    println!("answer B: {}", play_game(num_players, last_marble * 100));
+
+    Ok(())
 }
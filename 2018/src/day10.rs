@@ -1,56 +1,56 @@
-use crate::common::read_file_lines;
-use regex::Regex;
+use crate::common::*;
+use advent_common::parse::{Block, Cursor};
 
-pub fn run(_: &[&str]) {
+type Bounds = (i64, i64, i64, i64);
+
+fn bounds_at(points: &[(i64, i64, i64, i64)], time: i64) -> Bounds {
+    let mut bounds = (i64::MAX, i64::MAX, i64::MIN, i64::MIN);
+
+    for (cx, cy, dx, dy) in points {
+        let x = cx + time * dx;
+        let y = cy + time * dy;
+
+        bounds.0 = i64::min(bounds.0, x);
+        bounds.1 = i64::min(bounds.1, y);
+        bounds.2 = i64::max(bounds.2, x);
+        bounds.3 = i64::max(bounds.3, y);
+    }
+
+    bounds
+}
+
+fn area((ax, ay, bx, by): Bounds) -> i64 {
+    (bx - ax) * (by - ay)
+}
+
+pub fn run(lines: Lines) -> Result {
     let mut points = vec![];
-    let re =
-        Regex::new(r"position=<([-0-9 ]+),([-0-9 ]+)> velocity=<([-0-9 ]+),([-0-9 ]+)>").unwrap();
-
-    for line in read_file_lines("inputs/day10") {
-        let matches = re.captures(&line).unwrap();
-        let vec = matches
-            .iter()
-            .skip(1)
-            .map(|x| x.unwrap().as_str())
-            .map(|x| x.trim())
-            .map(|x| x.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
 
+    for (i, vec) in Block::new(lines)
+        .parse_lines(Cursor::ints)?
+        .into_iter()
+        .enumerate()
+    {
+        ensure!(vec.len() == 4, "line {}: expected four numbers", i + 1);
         points.push((vec[0], vec[1], vec[2], vec[3]));
     }
 
-    // Find time where bounds on stars is minimal
+    ensure!(!points.is_empty(), "no points given");
+
+    // The stars converge until they form the message and then drift apart
+    // again, so find the time where the bounds on the stars are minimal.
     let mut best_time = 0;
-    let mut best_area = 10000;
-    let mut best_bounds = (0, 0, 0, 0);
-
-    for time in 0..20000 {
-        let mut bounds = None;
-
-        for (cx, cy, dx, dy) in &points {
-            let x = cx + time * dx;
-            let y = cy + time * dy;
-
-            bounds = match bounds {
-                Some((ax, ay, bx, by)) => {
-                    let ax = i64::min(ax, x);
-                    let ay = i64::min(ay, y);
-                    let bx = i64::max(bx, x);
-                    let by = i64::max(by, y);
-                    Some((ax, ay, bx, by))
-                }
-                None => Some((x, y, x, y)),
-            };
-        }
+    let mut best_bounds = bounds_at(&points, 0);
 
-        let bounds = bounds.unwrap();
-        let area = (bounds.2 - bounds.0) * (bounds.3 - bounds.1);
+    loop {
+        let bounds = bounds_at(&points, best_time + 1);
 
-        if area < best_area {
-            best_time = time;
-            best_area = area;
-            best_bounds = bounds;
+        if area(bounds) >= area(best_bounds) {
+            break;
         }
+
+        best_time += 1;
+        best_bounds = bounds;
     }
 
     // Draw stars
@@ -71,4 +71,6 @@ pub fn run(_: &[&str]) {
     for line in screen {
         println!("{}", line.iter().collect::<String>());
     }
+
+    Ok(())
 }
@@ -1,21 +1,21 @@
-// Compute value of cell at coordinates (x, y)
-fn compute_cell(x: i64, y: i64) -> i64 {
-    const SERIAL_ID: usize = 3031;
+use crate::common::*;
 
+// Compute value of cell at coordinates (x, y)
+fn compute_cell(x: i64, y: i64, serial_id: i64) -> i64 {
     let rack_id = x + 10;
-    let level = ((rack_id * y) + SERIAL_ID as i64) * rack_id;
+    let level = ((rack_id * y) + serial_id) * rack_id;
     let power = (level / 100) % 10 - 5;
 
     power
 }
 
 // Compute cumulative summed grid where entry (x, y) holds the sum over the cells (0..x, 0..y)
-fn compute_cumsum_grid(size: usize) -> Vec<Vec<i64>> {
+fn compute_cumsum_grid(size: usize, serial_id: i64) -> Vec<Vec<i64>> {
     let mut grid = vec![vec![0; size + 1]; size + 1];
 
     for i in 0..size {
         for j in 0..size {
-            let cell = compute_cell(i as i64 + 1, j as i64 + 1);
+            let cell = compute_cell(i as i64 + 1, j as i64 + 1, serial_id);
 
             grid[i + 1][j + 1] = cell + grid[i][j + 1] + grid[i + 1][j] - grid[i][j];
         }
@@ -35,10 +35,15 @@ fn compute_area(grid: &[Vec<i64>], lower: (usize, usize), size: usize) -> i64 {
     result
 }
 
-pub fn run(_: &[&str]) {
+pub fn run(lines: Lines) -> Result {
     const GRID_SIZE: usize = 300;
 
-    let sum_grid = compute_cumsum_grid(GRID_SIZE);
+    ensure!(lines.len() == 1, "expected a single line");
+    let serial_id = lines[0]
+        .parse()
+        .with_context(|| format!("invalid serial number: {:?}", lines[0]))?;
+
+    let sum_grid = compute_cumsum_grid(GRID_SIZE, serial_id);
 
     let mut best = (0, 0, 0);
 
@@ -68,4 +73,6 @@ pub fn run(_: &[&str]) {
     }
 
     println!("answer B: {:?}", best);
+
+    Ok(())
 }
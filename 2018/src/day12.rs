@@ -1,23 +1,34 @@
-use crate::common::read_file_lines;
+use crate::common::*;
+use advent_common::parse::{Cursor, ParseResult, Sections};
 use std::collections::HashMap;
-use std::convert::TryInto;
 
-pub fn run(_: &[&str]) {
-    let lines = read_file_lines("inputs/day12");
+fn parse_pots(c: &mut Cursor, n: usize) -> ParseResult<Vec<char>> {
+    let pots = c.take_while(|p| p == '#' || p == '.');
+
+    if n > 0 && pots.len() != n {
+        return Err(c.error(format_args!("expected {} pots", n)));
+    }
+
+    Ok(pots.chars().collect())
+}
+
+pub fn run(lines: Lines) -> Result {
+    let [state, rule_lines] = Sections::new(lines).blocks()?;
 
     // Load initial state
-    let header = "initial state: ";
-    let mut current = lines[0][header.len()..].chars().collect::<Vec<_>>();
+    let mut current = state.parse_single(|c| {
+        c.tag("initial state: ")?;
+        parse_pots(c, 0)
+    })?;
 
     // Load rules
     let mut rules = HashMap::new();
-    for line in &lines[2..] {
-        let chars = line.chars().collect::<Vec<_>>();
-
-        let v: &[char; 5] = (&chars[..5]).try_into().unwrap();
-        let a = chars[9];
-
-        rules.insert(*v, a);
+    for (pattern, result) in rule_lines.parse_lines(|c| {
+        let pattern = parse_pots(c, 5)?;
+        c.tag(" => ")?;
+        Ok((pattern, parse_pots(c, 1)?[0]))
+    })? {
+        rules.insert(pattern, result);
     }
 
     let mut first_plant = 0;
@@ -34,7 +45,10 @@ pub fn run(_: &[&str]) {
         next.extend(vec!['.'; 4]);
         first_plant += offset as i32 - 4;
 
-        current = next.windows(5).map(|m| rules[m]).collect::<Vec<_>>();
+        current = next
+            .windows(5)
+            .map(|m| rules.get(m).copied().unwrap_or('.'))
+            .collect::<Vec<_>>();
         first_plant += 2;
 
         let total = current
@@ -57,4 +71,6 @@ pub fn run(_: &[&str]) {
     let cons = scores[n - 1] - incr * (n as i64 - 1);
 
     println!("answer B: {}", cons + (50000000000i64 - 1) * incr);
+
+    Ok(())
 }
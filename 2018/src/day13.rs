@@ -1,14 +1,14 @@
-use crate::common::read_file_lines;
+use crate::common::*;
 use ndarray::prelude::*;
 
 type Cart = (char, i8, usize);
 
-fn parse_input() -> (Array2<char>, Array2<Option<Cart>>) {
+fn parse_input(lines: Lines) -> Result<(Array2<char>, Array2<Option<Cart>>)> {
     let mut width = 0;
     let mut tracks = vec![];
     let mut carts = vec![];
 
-    for (y, line) in read_file_lines("inputs/day13").iter().enumerate() {
+    for (y, line) in lines.iter().enumerate() {
         let mut line_width = 0;
 
         for c in line.chars() {
@@ -33,15 +33,17 @@ fn parse_input() -> (Array2<char>, Array2<Option<Cart>>) {
         if y == 0 {
             width = line_width;
         } else if line_width != width {
-            panic!("uneven line widths one line {}", y);
+            bail!("uneven line widths on line {}", y + 1);
         }
     }
 
+    ensure!(width > 0, "empty track layout");
+
     let shape = (tracks.len() / width, width);
-    (
-        Array2::from_shape_vec(shape, tracks).unwrap(),
-        Array2::from_shape_vec(shape, carts).unwrap(),
-    )
+    Ok((
+        Array2::from_shape_vec(shape, tracks)?,
+        Array2::from_shape_vec(shape, carts)?,
+    ))
 }
 
 pub fn process_cell(
@@ -50,24 +52,29 @@ pub fn process_cell(
     j: usize,
     tracks: &Array2<char>,
     carts: &mut Array2<Option<Cart>>,
-) -> Option<(usize, usize)> {
+) -> Result<Option<(usize, usize)>> {
     let (c, mut mem) = match carts[[i, j]] {
         Some((c, m, last_tick)) if last_tick < tick => {
             carts[[i, j]] = None;
             (c, m)
         }
-        _ => return None,
+        _ => return Ok(None),
     };
 
     let (ni, nj) = match c {
-        '^' => (i - 1, j),
-        '<' => (i, j - 1),
+        '^' => (i.wrapping_sub(1), j),
+        '<' => (i, j.wrapping_sub(1)),
         '>' => (i, j + 1),
         'v' => (i + 1, j),
-        _ => panic!("unknown cart symbol {}", c),
+        _ => bail!("unknown cart symbol {}", c),
+    };
+
+    let track = match tracks.get([ni, nj]) {
+        Some(&track) => track,
+        None => bail!("cart left the map at {:?}", (j, i)),
     };
 
-    let nc = if tracks[[ni, nj]] == '+' {
+    let nc = if track == '+' {
         let old_mem = mem;
         mem = (mem + 1) % 3;
 
@@ -84,10 +91,10 @@ pub fn process_cell(
             ('<', 0) => 'v',
             ('<', 1) => '<',
             ('<', 2) => '^',
-            x => panic!("unknown pair {:?}", x),
+            x => bail!("unknown pair {:?}", x),
         }
     } else {
-        match (c, tracks[[ni, nj]]) {
+        match (c, track) {
             (_, '|') | (_, '-') => c,
             ('^', '/') => '>',
             ('>', '/') => '^',
@@ -97,22 +104,23 @@ pub fn process_cell(
             ('>', '\\') => 'v',
             ('v', '\\') => '>',
             ('<', '\\') => '^',
-            x => panic!("unknown pair {:?}", x),
+            x => bail!("cart derailed at {:?}: {:?}", (nj, ni), x),
         }
     };
 
     if let Some(_) = carts[[ni, nj]].take() {
-        Some((ni, nj))
+        Ok(Some((ni, nj)))
     } else {
         carts[[ni, nj]] = Some((nc, mem, tick));
-        None
+        Ok(None)
     }
 }
 
-pub fn run(_: &[&str]) {
-    let (tracks, mut carts) = parse_input();
-    let (width, height) = (tracks.shape()[0], tracks.shape()[1]);
+pub fn run(lines: Lines) -> Result {
+    let (tracks, mut carts) = parse_input(lines)?;
+    let (height, width) = (tracks.shape()[0], tracks.shape()[1]);
     let mut num_carts = carts.iter().filter(|x| x.is_some()).count();
+    ensure!(num_carts % 2 == 1, "expected an odd number of carts");
     let mut collisions = vec![];
     let mut tick = 0;
 
@@ -122,7 +130,7 @@ pub fn run(_: &[&str]) {
 
         for i in 0..height {
             for j in 0..width {
-                if let Some(p) = process_cell(tick, i, j, &tracks, &mut carts) {
+                if let Some(p) = process_cell(tick, i, j, &tracks, &mut carts)? {
                     collisions.push(p);
                     num_carts -= 2;
                 }
@@ -130,7 +138,7 @@ pub fn run(_: &[&str]) {
         }
     }
 
-    let (y, x) = collisions[0];
+    let (y, x) = *collisions.first().context("carts never collide")?;
     println!("answer A: {:?}", (x, y));
 
     let (y, x) = carts
@@ -138,6 +146,8 @@ pub fn run(_: &[&str]) {
         .filter(|(_, c)| c.is_some())
         .map(|(p, _)| p)
         .next()
-        .unwrap();
+        .context("no carts left")?;
     println!("answer B: {:?}", (x, y));
+
+    Ok(())
 }
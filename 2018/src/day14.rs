@@ -1,8 +1,11 @@
-use crate::common::read_file_lines;
-
-pub fn run(_: &[&str]) {
-    let line = read_file_lines("inputs/day14")[0].clone();
-    let n = line.parse::<usize>().unwrap();
+use crate::common::*;
+
+pub fn run(lines: Lines) -> Result {
+    ensure!(lines.len() == 1, "expected a single line");
+    let line = lines[0];
+    let n = line
+        .parse::<usize>()
+        .with_context(|| format!("invalid number: {:?}", line))?;
     let pattern = line
         .chars()
         .map(|x| x as i8 - '0' as i8)
@@ -11,16 +14,20 @@ pub fn run(_: &[&str]) {
     let mut recipes = vec![3, 7];
     let mut index_a = 0;
     let mut index_b = 1;
+    let mut position = None;
 
-    // just run suffient number iterations
-    for _ in 0..50_000_000 {
+    // run until both the scores after n recipes and the pattern are known
+    while recipes.len() < n + 10 || position.is_none() {
         let sum = recipes[index_a] + recipes[index_b];
+        let (pair, single) = ([1, sum - 10], [sum]);
+        let digits = iff!(sum >= 10, &pair[..], &single[..]);
+
+        for &digit in digits {
+            recipes.push(digit);
 
-        if sum >= 10 {
-            recipes.push(1);
-            recipes.push(sum - 10);
-        } else {
-            recipes.push(sum);
+            if position.is_none() && recipes.ends_with(&pattern) {
+                position = Some(recipes.len() - pattern.len());
+            }
         }
 
         index_a = (index_a + recipes[index_a] as usize + 1) % recipes.len();
@@ -35,7 +42,7 @@ pub fn run(_: &[&str]) {
     println!("answer A: {}", answer);
 
     // find position of given pattern
-    let answer = recipes.windows(pattern.len()).position(|w| w == &*pattern);
+    println!("answer B: {:?}", position);
 
-    println!("answer B: {:?}", answer);
+    Ok(())
 }
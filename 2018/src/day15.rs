@@ -1,4 +1,4 @@
-use crate::common::read_file_lines;
+use crate::common::*;
 use itertools::enumerate;
 use ndarray::prelude::*;
 use std::collections::VecDeque;
@@ -11,8 +11,8 @@ struct Entity {
     is_elf: bool,
 }
 
-fn parse_input() -> (Vec<Entity>, Array2<bool>) {
-    let lines = read_file_lines("inputs/day15");
+fn parse_input(lines: Lines) -> Result<(Vec<Entity>, Array2<bool>)> {
+    ensure!(!lines.is_empty(), "empty input");
 
     let width = lines[0].len();
     let height = lines.len();
@@ -21,7 +21,7 @@ fn parse_input() -> (Vec<Entity>, Array2<bool>) {
     let mut entities = vec![];
 
     for (y, line) in enumerate(lines) {
-        assert_eq!(width, line.len());
+        ensure!(width == line.len(), "uneven line widths on line {}", y + 1);
 
         for (x, c) in enumerate(line.chars()) {
             if c == 'G' || c == 'E' {
@@ -34,12 +34,12 @@ fn parse_input() -> (Vec<Entity>, Array2<bool>) {
             } else if c == '#' || c == '.' {
                 walls[[x, y]] = c == '#';
             } else {
-                panic!("unknown symbol: {:?}", c);
+                bail!("unknown symbol {:?} on line {}", c, y + 1);
             }
         }
     }
 
-    (entities, walls)
+    Ok((entities, walls))
 }
 
 fn flood(obstacle: &Array2<bool>, start: [usize; 2]) -> Array2<Option<i32>> {
@@ -240,8 +240,8 @@ fn simulate_war(entities: &mut [Entity], walls: &Array2<bool>) -> i32 {
     }
 }
 
-pub fn run(_: &[&str]) {
-    let (entities, walls) = parse_input();
+pub fn run(lines: Lines) -> Result {
+    let (entities, walls) = parse_input(lines)?;
 
     {
         let mut entities = entities.clone();
@@ -270,4 +270,6 @@ pub fn run(_: &[&str]) {
             break;
         }
     }
+
+    Ok(())
 }
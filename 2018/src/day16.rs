@@ -1,25 +1,54 @@
-use crate::common::read_file_lines;
-use regex::Regex;
+use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult, Sections};
+use std::convert::TryInto;
 use std::default::Default;
 
 type Instr = [i32; 4];
 type Regs = [i32; 4];
+type Sample = (Regs, Instr, Regs);
 
-fn parse_captures(re: &Regex, line: &str) -> [i32; 4] {
-    if let Some(cap) = re.captures(line) {
-        let mut output = [0; 4];
+fn parse_values(c: &mut Cursor, sep: &str) -> ParseResult<[i32; 4]> {
+    let start = *c;
+    let values = c.list(sep, Cursor::int)?;
 
-        cap.iter()
-            .skip(1)
-            .map(|x| x.unwrap().as_str())
-            .map(|x| x.parse::<i32>().expect(x))
-            .enumerate()
-            .for_each(|(i, v)| output[i] = v);
+    values
+        .try_into()
+        .map_err(|_| start.error("expected four values"))
+}
+
+fn parse_regs(c: &mut Cursor, label: &str) -> ParseResult<Regs> {
+    c.tag(label)?;
+    c.tag("[")?;
+    let regs = parse_values(c, ", ")?;
+    c.tag("]")?;
+
+    Ok(regs)
+}
+
+fn parse_instr(c: &mut Cursor) -> ParseResult<Instr> {
+    let start = *c;
+    let instr = parse_values(c, " ")?;
 
-        output
-    } else {
-        panic!("failed regex '{}' on '{}'", re.as_str(), line);
+    // every operand is used as a register index by `exec_instr`
+    if !(0..NUM_OPCODES as i32).contains(&instr[0])
+        || instr[1..].iter().any(|v| !(0..=3).contains(v))
+    {
+        return Err(start.error(format_args!("invalid instruction {:?}", instr)));
     }
+
+    Ok(instr)
+}
+
+fn parse_sample(block: Block<&str>) -> ParseResult<Sample> {
+    if block.len() != 3 {
+        return Err(block.error(0, "expected a sample of three lines"));
+    }
+
+    let before = block.parse_line(0, |c| parse_regs(c, "Before: "))?;
+    let instr = block.parse_line(1, parse_instr)?;
+    let after = block.parse_line(2, |c| parse_regs(c, "After:  "))?;
+
+    Ok((before, instr, after))
 }
 
 const NUM_OPCODES: usize = 16;
@@ -62,38 +91,24 @@ fn name_instr(opcode: i32) -> &'static str {
     names.get(opcode as usize).unwrap_or(&"unknown")
 }
 
-fn parse_input() -> (Vec<(Regs, Instr, Regs)>, Vec<Instr>) {
-    let before_re = Regex::new(r"Before: \[(\d+), (\d+), (\d+), (\d+)\]").unwrap();
-    let instr_re = Regex::new(r"(\d+) (\d+) (\d+) (\d+)").unwrap();
-    let after_re = Regex::new(r"After:  \[(\d+), (\d+), (\d+), (\d+)\]").unwrap();
-
-    let mut index = 0;
-    let lines = read_file_lines("inputs/day16");
-    let mut samples = vec![];
-    let mut program = vec![];
-
-    while !lines[index].is_empty() {
-        let before = parse_captures(&before_re, &lines[index]);
-        let instr = parse_captures(&instr_re, &lines[index + 1]);
-        let after = parse_captures(&after_re, &lines[index + 2]);
-        index += 4;
-
-        samples.push((before, instr, after));
-    }
-
-    index += 2;
-
-    while index < lines.len() {
-        let instr = parse_captures(&instr_re, &lines[index]);
-        index += 1;
-
-        program.push(instr);
-    }
-
-    (samples, program)
+fn parse_input(lines: Lines) -> Result<(Vec<Sample>, Vec<Instr>)> {
+    // The samples are separated by single empty lines and the program comes
+    // after the last sample.
+    let sections = Sections::new(lines);
+    let blocks = sections.iter().collect::<Vec<_>>();
+    let (program, samples) = blocks.split_last().context("empty input")?;
+
+    let samples = samples
+        .iter()
+        .filter(|b| !b.is_empty())
+        .map(|&b| parse_sample(b))
+        .collect::<ParseResult<Vec<_>>>()?;
+    let program = program.parse_lines(parse_instr)?;
+
+    Ok((samples, program))
 }
 
-fn find_mapping(samples: &[(Regs, Instr, Regs)]) -> [i32; NUM_OPCODES] {
+fn find_mapping(samples: &[Sample]) -> Result<[i32; NUM_OPCODES]> {
     // we build a matrix in which element (i, j) indicates if the given
     // opcode i could be mapping to the real opcode j. Eventually, this
     // matrix should contain exactly one "true" on each row, indicating
@@ -115,7 +130,7 @@ fn find_mapping(samples: &[(Regs, Instr, Regs)]) -> [i32; NUM_OPCODES] {
 
     // check matrix.
     for _ in 0..NUM_OPCODES {
-        let mut m = (!0, !0);
+        let mut m = None;
 
         for i in 0..NUM_OPCODES {
             print!("{} ", name_instr(i as i32));
@@ -137,11 +152,11 @@ fn find_mapping(samples: &[(Regs, Instr, Regs)]) -> [i32; NUM_OPCODES] {
             let pos = matrix[i].iter().position(|x| *x);
 
             if count == 1 {
-                m = (i, pos.unwrap());
+                m = Some((i, pos.unwrap()));
             }
         }
 
-        let (i, j) = m;
+        let (i, j) = m.context("samples do not determine the opcodes")?;
         mapping[i] = j as i32;
         println!(" mapping: {} = {}", name_instr(j as i32), i);
         println!();
@@ -153,12 +168,12 @@ fn find_mapping(samples: &[(Regs, Instr, Regs)]) -> [i32; NUM_OPCODES] {
         }
     }
 
-    mapping
+    Ok(mapping)
 }
 
-pub fn run(_: &[&str]) {
-    let (samples, program) = parse_input();
-    let mapping = find_mapping(&samples);
+pub fn run(lines: Lines) -> Result {
+    let (samples, program) = parse_input(lines)?;
+    let mapping = find_mapping(&samples)?;
 
     let mut answer_a = 0;
     for (before, instr, after) in samples.iter().cloned() {
@@ -181,4 +196,6 @@ pub fn run(_: &[&str]) {
     }
 
     println!("answer B: {:?}", regs);
+
+    Ok(())
 }
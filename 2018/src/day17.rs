@@ -1,40 +1,37 @@
-use crate::common::read_file_lines;
+use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use ndarray::prelude::*;
-use regex::Regex;
-
-fn parse_input() -> Array2<char> {
-    let re_xy = Regex::new(r"x=(\d+), y=(\d+)..(\d+)").unwrap();
-    let re_yx = Regex::new(r"y=(\d+), x=(\d+)..(\d+)").unwrap();
-    let mut points = vec![];
-
-    for line in read_file_lines("inputs/day17") {
-        let to_int = |s: &str| s.parse::<i32>().unwrap();
-
-        let bounds = if let Some(cap) = re_xy.captures(&line) {
-            (
-                to_int(&cap[1]),
-                to_int(&cap[1]),
-                to_int(&cap[2]),
-                to_int(&cap[3]),
-            )
-        } else if let Some(cap) = re_yx.captures(&line) {
-            (
-                to_int(&cap[2]),
-                to_int(&cap[3]),
-                to_int(&cap[1]),
-                to_int(&cap[1]),
-            )
-        } else {
-            panic!("failed to parse line {}", line);
-        };
-
-        points.push(bounds);
-    }
+
+fn parse_vein(c: &mut Cursor) -> ParseResult<(i32, i32, i32, i32)> {
+    let axis = c.one_of(&[("x=", 'x'), ("y=", 'y')])?;
+    let fixed = c.int()?;
+    c.tag(iff!(axis == 'x', ", y=", ", x="))?;
+    let lo = c.int()?;
+    c.tag("..")?;
+    let hi = c.int()?;
+
+    Ok(iff!(
+        axis == 'x',
+        (fixed, fixed, lo, hi),
+        (lo, hi, fixed, fixed)
+    ))
+}
+
+fn parse_input(lines: Lines) -> Result<Array2<char>> {
+    let points = Block::new(lines).parse_lines(parse_vein)?;
+
+    // water can flow one tile past the clay on either side
+    ensure!(!points.is_empty(), "no clay veins given");
+    ensure!(
+        points.iter().all(|p| p.0 >= 1 && p.2 >= 0),
+        "clay veins must have positive coordinates"
+    );
 
     let y_lbnd = points.iter().map(|p| p.2).min().unwrap();
     let y_ubnd = points.iter().map(|p| p.3).max().unwrap();
+    let x_ubnd = points.iter().map(|p| p.1).max().unwrap();
     let height = (y_ubnd - y_lbnd + 1) as usize;
-    let width = 1000;
+    let width = (x_ubnd + 2).max(501) as usize;
 
     let mut ground = Array2::from_elem((width, height), ' ');
 
@@ -46,7 +43,7 @@ fn parse_input() -> Array2<char> {
         }
     }
 
-    ground
+    Ok(ground)
 }
 
 fn fill_horizontal(
@@ -121,7 +118,7 @@ fn fill_down([x, y]: [usize; 2], ground: &mut Array2<char>) -> bool {
     }
 }
 
-fn write_image(ground: &Array2<char>) {
+fn write_image(ground: &Array2<char>) -> Result {
     let width = ground.shape()[0] as u32;
     let height = ground.shape()[1] as u32;
     let mut img = image::RgbImage::new(width, height);
@@ -141,12 +138,14 @@ fn write_image(ground: &Array2<char>) {
     }
 
     let filename = "day17.png";
-    img.save(filename).unwrap();
+    img.save(filename)?;
     println!("saved image as {:?}", filename);
+
+    Ok(())
 }
 
-pub fn run(_: &[&str]) {
-    let mut ground = parse_input();
+pub fn run(lines: Lines) -> Result {
+    let mut ground = parse_input(lines)?;
     let spring: [usize; 2] = [500, 0];
 
     ground[spring] = '~';
@@ -159,5 +158,5 @@ pub fn run(_: &[&str]) {
     println!("answer A: {}", answer_a);
     println!("answer B: {}", answer_b);
 
-    write_image(&ground);
+    write_image(&ground)
 }
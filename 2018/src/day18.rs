@@ -1,92 +1,46 @@
-use crate::common::read_file_lines;
-use ndarray::prelude::*;
-
-fn parse_input() -> Array2<char> {
-    let lines = read_file_lines("inputs/day18");
-    let size = lines.len();
-
-    let mut cells = Array2::from_elem((size + 2, size + 2), '.');
-    let mut inner = cells.slice_mut(s![1..-1, 1..-1]);
-
-    for (row, line) in izip!(inner.genrows_mut(), lines) {
-        for (entry, c) in izip!(row, line.chars()) {
-            *entry = c;
-        }
-    }
-
-    cells
+use crate::common::*;
+use advent_common::automaton::{DenseAutomaton, Neighborhood};
+use advent_common::cycle::nth_state;
+use advent_common::parse::Block;
+
+fn parse_input(lines: Lines) -> Result<DenseAutomaton<char>> {
+    let grid = Block::new(lines).parse_grid(|c| iff!("|#.".contains(c), Some(c), None))?;
+    Ok(DenseAutomaton::bounded(grid))
 }
 
-#[inline(never)]
-fn simulate(current: &Array2<char>, next: &mut Array2<char>) {
-    let inner = next.slice_mut(s![1..-1, 1..-1]);
-    let windows = current.windows([3, 3]);
-
-    for (entry, win) in izip!(inner, windows) {
-        let mut num_yards = 0;
-        let mut num_trees = 0;
+fn simulate(area: &Neighborhood<char>) -> char {
+    let num_yards = area.neighbors8().filter(|&&c| c == '#').count();
+    let num_trees = area.neighbors8().filter(|&&c| c == '|').count();
 
-        for c in win {
-            num_yards += iff!(*c == '#', 1, 0);
-            num_trees += iff!(*c == '|', 1, 0);
-        }
-
-        *entry = match win[[1, 1]] {
-            '.' => iff!(num_trees >= 3, '|', '.'),
-            '|' => iff!(num_yards >= 3, '#', '|'),
-            '#' => iff!(num_trees >= 1 && num_yards >= 2, '#', '.'),
-            x => panic!("unknown symbol {:?}", x),
-        };
+    match *area.center() {
+        '.' => iff!(num_trees >= 3, '|', '.'),
+        '|' => iff!(num_yards >= 3, '#', '|'),
+        '#' => iff!(num_trees >= 1 && num_yards >= 1, '#', '.'),
+        _ => unreachable!(),
     }
 }
 
-pub fn run(_: &[&str]) {
-    let mut current = parse_input();
-    let mut next = current.clone();
-    let mut scores = vec![];
-
-    // just simulate for 1000 timesteps
-    for _ in 0..1000 {
-        let mut num_yards = 0;
-        let mut num_trees = 0;
-        for c in current.iter() {
-            match c {
-                '#' => num_yards += 1,
-                '|' => num_trees += 1,
-                _ => {}
-            }
-        }
+fn step(current: &DenseAutomaton<char>) -> DenseAutomaton<char> {
+    current.step(simulate)
+}
 
-        scores.push((num_yards, num_trees));
+fn resource_value(cells: &DenseAutomaton<char>) -> usize {
+    let num_yards = cells.count(|&c| c == '#');
+    let num_trees = cells.count(|&c| c == '|');
+    num_yards * num_trees
+}
 
-        simulate(&current, &mut next);
-        std::mem::swap(&mut current, &mut next);
-    }
+pub fn run(lines: Lines) -> Result {
+    let initial = parse_input(lines)?;
 
     // part A: score after 10 minutes
-    println!("answer A: {}", scores[10].0 * scores[10].1);
-
-    // part B: score after 1000000000 minutes. We assume there is some
-    // kind of cyclic pattern of length "cycle" and then we can go
-    // back in time some amount of k * cycle steps.
-    let n = scores.len();
-    let mut cycle = 1;
+    let cells = nth_state(initial.clone(), step, 10);
+    println!("answer A: {}", resource_value(&cells));
 
-    loop {
-        let a = &scores[n - cycle..];
-        let b = &scores[n - 2 * cycle..n - cycle];
-
-        if a != b {
-            cycle += 1;
-        } else {
-            break;
-        }
-    }
-
-    let mut i = 1000000000;
-    while i >= n {
-        i -= cycle;
-    }
+    // part B: score after 1000000000 minutes. The landscape eventually
+    // repeats itself, which allows skipping over most of the minutes.
+    let cells = nth_state(initial, step, 1000000000);
+    println!("answer B: {}", resource_value(&cells));
 
-    println!("answer B: {}", scores[i].0 * scores[i].1);
+    Ok(())
 }
@@ -1,4 +1,4 @@
-use crate::common::read_file_lines;
+use crate::common::*;
 use std::collections::{HashMap as Map, HashSet as Set, VecDeque as Deque};
 
 type Point = [i32; 2];
@@ -11,7 +11,7 @@ enum MyRegex {
     Alt(Vec<MyRegex>), // Options for exprs
 }
 
-fn parse_myregex(iter: &mut Deque<char>) -> MyRegex {
+fn parse_myregex(iter: &mut Deque<char>) -> Result<MyRegex> {
     let mut options = vec![];
     let mut curr = vec![];
 
@@ -22,8 +22,8 @@ fn parse_myregex(iter: &mut Deque<char>) -> MyRegex {
                 curr = vec![];
             }
             '(' => {
-                curr.push(parse_myregex(iter));
-                assert!(iter.pop_front() == Some(')'));
+                curr.push(parse_myregex(iter)?);
+                ensure!(iter.pop_front() == Some(')'), "unbalanced parentheses");
             }
             'N' | 'S' | 'E' | 'W' => {
                 curr.push(MyRegex::Leaf(c));
@@ -36,17 +36,22 @@ fn parse_myregex(iter: &mut Deque<char>) -> MyRegex {
     }
 
     options.push(MyRegex::Seq(curr));
-    MyRegex::Alt(options)
+    Ok(MyRegex::Alt(options))
 }
 
-fn parse_input() -> MyRegex {
-    let lines = read_file_lines("inputs/day20");
+fn parse_input(lines: Lines) -> Result<MyRegex> {
+    ensure!(lines.len() == 1, "expected a single line");
     let mut iter = lines[0].chars().collect::<Deque<_>>();
 
-    assert!(iter.pop_front() == Some('^'));
-    assert!(iter.pop_back() == Some('$'));
+    ensure!(iter.pop_front() == Some('^'), "expected '^' at the start");
+    ensure!(iter.pop_back() == Some('$'), "expected '$' at the end");
 
-    parse_myregex(&mut iter)
+    let regex = parse_myregex(&mut iter)?;
+    if let Some(c) = iter.front() {
+        bail!("unexpected symbol {:?}", c);
+    }
+
+    Ok(regex)
 }
 
 fn walk_paths(node: &MyRegex, active: &Set<Point>, doors: &mut Set<Door>) -> Set<Point> {
@@ -104,8 +109,8 @@ fn find_room_dists(center: Point, doors: &Set<Door>) -> Map<Point, i32> {
     dists
 }
 
-pub fn run(_: &[&str]) {
-    let root = parse_input();
+pub fn run(lines: Lines) -> Result {
+    let root = parse_input(lines)?;
 
     let mut doors = Set::new();
     let mut active = Set::new();
@@ -119,4 +124,6 @@ pub fn run(_: &[&str]) {
 
     let num_paths = dists.values().filter(|v| **v >= 1000).count();
     println!("answer B: {:?}", num_paths);
+
+    Ok(())
 }
@@ -1,85 +1,99 @@
-use crate::common::read_file_lines;
-use regex::Regex;
+use crate::common::*;
+use crate::elfcode::{Instr, Program};
+use advent_common::assume::Assumptions;
 use std::collections::HashSet;
-use std::i64;
-
-type Instr = (String, i64, i64, i64);
-type Regs = [i64; 6];
-
-fn parse_input() -> (usize, Vec<Instr>) {
-    let instr_re = Regex::new(r"([a-z]{4}) (\d+) (\d+) (\d+)").unwrap();
-    let ip_re = Regex::new(r"#ip (\d+)").unwrap();
-
-    let lines = read_file_lines("inputs/day21");
-    let mut ip = 0;
-    let mut instrs = vec![];
-
-    for line in lines {
-        if let Some(cap) = ip_re.captures(&line) {
-            ip = cap[1].parse().unwrap();
-        } else if let Some(cap) = instr_re.captures(&line) {
-            instrs.push((
-                cap[1].to_string(),
-                cap[2].parse().unwrap(),
-                cap[3].parse().unwrap(),
-                cap[4].parse().unwrap(),
-            ))
-        } else {
-            panic!("failed to match line {:?}", line);
-        }
-    }
 
-    (ip, instrs)
+// A loop that divides register `src` by `divisor` by counting upwards in
+// register `dst`, which takes ages for large numbers.
+struct DivisionLoop {
+    start: usize,
+    end: usize,
+    dst: usize,
+    src: usize,
+    divisor: i64,
 }
 
-fn exec_instr(instr: &Instr, mut regs: Regs) -> Regs {
-    let to_int = |x| if x { 1 } else { 0 };
-
-    let (name, a, b, c) = instr;
-    let (a, b, c) = (*a, *b, *c);
-    let (ua, ub, uc) = (a as usize, b as usize, c as usize);
-
-    regs[uc] = match &**name {
-        "addr" => i64::checked_add(regs[ua], regs[ub]).unwrap(),
-        "addi" => i64::checked_add(regs[ua], b).unwrap(),
-        "mulr" => i64::checked_mul(regs[ua], regs[ub]).unwrap(),
-        "muli" => i64::checked_mul(regs[ua], b).unwrap(),
-        "banr" => regs[ua] & regs[ub],
-        "bani" => regs[ua] & b,
-        "borr" => regs[ua] | regs[ub],
-        "bori" => regs[ua] | b,
-        "setr" => regs[ua],
-        "seti" => a,
-        "gtir" => to_int(a > regs[ub]),
-        "gtri" => to_int(regs[ua] > b),
-        "gtrr" => to_int(regs[ua] > regs[ub]),
-        "eqir" => to_int(a == regs[ub]),
-        "eqri" => to_int(regs[ua] == b),
-        "eqrr" => to_int(regs[ua] == regs[ub]),
-        _ => panic!("unknown instruction {:?}", name),
-    };
-
-    regs
+fn find_division_loop(instrs: &[Instr]) -> Option<DivisionLoop> {
+    instrs.windows(10).enumerate().find_map(|(start, w)| {
+        let (dst, tmp) = (w[0].c, w[1].c);
+        let found = (w[0].name, w[0].a) == ("seti", 0)
+            && (w[1].name, w[1].a, w[1].b) == ("addi", dst, 1)
+            && (w[2].name, w[2].a, w[2].c) == ("muli", tmp, tmp)
+            && (w[3].name, w[3].a) == ("gtrr", tmp)
+            && (w[9].name, w[9].a, w[9].c) == ("setr", dst, w[3].b)
+            && w[2].b > 0;
+
+        iff!(
+            found,
+            Some(DivisionLoop {
+                start,
+                end: start + 9,
+                dst: dst as usize,
+                src: w[3].b as usize,
+                divisor: w[2].b,
+            }),
+            None
+        )
+    })
 }
 
-pub fn run(_: &[&str]) {
-    let (ip, instrs) = parse_input();
+pub fn run(lines: Lines) -> Result {
+    let program = Program::parse(lines)?;
+    let ip = program.ip;
+    let division = find_division_loop(&program.instrs);
+
+    // the program halts once the only instruction that reads register 0
+    // finds that it equals the other register it reads.
+    let mut assumptions = Assumptions::new();
+    let readers = program
+        .instrs
+        .iter()
+        .enumerate()
+        .filter(|(_, instr)| instr.reads().any(|r| r == 0))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let writers = program.instrs.iter().filter(|instr| instr.c == 0).count();
+
+    assumptions.check(
+        readers.len() == 1,
+        format_args!("one instruction reads r0, found {}", readers.len()),
+    );
+    assumptions.check(
+        writers == 0,
+        format_args!("no instruction writes r0, found {}", writers),
+    );
+    assumptions.verify()?;
+
+    let check = readers[0];
+    let instr = program.instrs[check];
+    let reg = instr.reads().find(|&r| r != 0);
+
+    assumptions.check(
+        instr.name == "eqrr" && reg.is_some(),
+        format_args!("r0 is compared to another register, found {:?}", instr),
+    );
+    assumptions.verify()?;
+
+    let reg = reg.unwrap();
+
     let mut seen = HashSet::new();
     let mut values = vec![];
 
     let mut regs = [0; 6];
     loop {
-        // the code from line 17 to 26 is just an ineffcient bitshift
-        // skip this code an perform the bitshift directly.
-        if regs[ip] == 17 {
-            regs[4] = regs[1] >> 8;
-            regs[ip] = 26;
+        let pc = regs[ip] as usize;
+
+        // skip the division loop and perform the division directly.
+        if let Some(l) = division.as_ref().filter(|l| l.start == pc) {
+            regs[l.dst] = regs[l.src] / l.divisor;
+            regs[ip] = l.end as i64;
+            continue;
         }
 
-        // the code on line 28 checks if registers r0 and r3 matche.
-        // store the value of r3 and break if we have seen the value before.
-        if regs[ip] == 28 {
-            let v = regs[3];
+        // store the value compared to r0 and break if we have seen the
+        // value before.
+        if pc == check {
+            let v = regs[reg];
 
             if seen.insert(v) {
                 values.push(v);
@@ -88,10 +102,11 @@ pub fn run(_: &[&str]) {
             }
         }
 
-        regs = exec_instr(&instrs[regs[ip] as usize], regs);
-        regs[ip] += 1;
+        ensure!(program.step(&mut regs)?, "program halted unexpectedly");
     }
 
     println!("answer A: {:?}", values.first());
     println!("answer B: {:?}", values.last());
+
+    Ok(())
 }
@@ -1,8 +1,7 @@
-use binary_heap_plus::BinaryHeap;
-use crate::common::read_file_lines;
+use crate::common::*;
+use advent_common::parse::Block;
+use advent_common::search::astar;
 use ndarray::prelude::*;
-use regex::Regex;
-use std::collections::hash_map::{Entry, HashMap};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 enum Tool {
@@ -40,19 +39,24 @@ impl Surface {
     }
 }
 
-fn parse_input() -> (i32, [usize; 2]) {
-    let re_depth = Regex::new("depth: ([0-9]+)").unwrap();
-    let re_target = Regex::new("target: ([0-9]+),([0-9]+)").unwrap();
-    let lines = read_file_lines("inputs/day22");
+fn parse_input(lines: Lines) -> Result<(i32, [usize; 2])> {
+    let block = Block::new(lines);
+    ensure!(block.len() == 2, "expected two lines");
 
-    let m = re_depth.captures(&lines[0]).unwrap();
-    let depth = m[1].parse::<i32>().unwrap();
+    let depth = block.parse_line(0, |c| {
+        c.tag("depth: ")?;
+        c.int()
+    })?;
 
-    let m = re_target.captures(&lines[1]).unwrap();
-    let x = m[1].parse::<usize>().unwrap();
-    let y = m[2].parse::<usize>().unwrap();
+    let target = block.parse_line(1, |c| {
+        c.tag("target: ")?;
+        let x = c.int()?;
+        c.tag(",")?;
+        let y = c.int()?;
+        Ok([x, y])
+    })?;
 
-    (depth, [x, y])
+    Ok((depth, target))
 }
 
 fn build_cave(width: usize, height: usize, depth: i32, target: [usize; 2]) -> Array2<Surface> {
@@ -84,49 +88,49 @@ fn build_cave(width: usize, height: usize, depth: i32, target: [usize; 2]) -> Ar
     cave
 }
 
-fn find_paths(cave: &Array2<Surface>, target: [usize; 2]) -> i32 {
-    let mut dists = HashMap::new();
-    let mut queue = BinaryHeap::new_by_key(|v: &(_, _, _, i32)| -v.3);
-
+fn find_paths(cave: &Array2<Surface>, target: [usize; 2]) -> Result<i32> {
     let (width, height) = (cave.shape()[0] as isize, cave.shape()[1] as isize);
     let (tx, ty) = (target[0] as isize, target[1] as isize);
 
-    queue.push((0, 0, Torch, 0));
-
-    while let Some((x, y, tool, time)) = queue.pop() {
-        match dists.entry((x, y, tool)) {
-            Entry::Vacant(v) => v.insert(time),
-            Entry::Occupied(_) => continue,
-        };
-
+    let successors = |&(x, y, tool): &(isize, isize, Tool)| {
         let adj = [
-            (x - 1, y, tool, time + 1),
-            (x + 1, y, tool, time + 1),
-            (x, y - 1, tool, time + 1),
-            (x, y + 1, tool, time + 1),
-            (x, y, Torch, time + 7),
-            (x, y, Gear, time + 7),
-            (x, y, Empty, time + 7),
+            ((x - 1, y, tool), 1),
+            ((x + 1, y, tool), 1),
+            ((x, y - 1, tool), 1),
+            ((x, y + 1, tool), 1),
+            ((x, y, Torch), 7),
+            ((x, y, Gear), 7),
+            ((x, y, Empty), 7),
         ];
 
-        for (x, y, tool, time) in adj.iter().cloned() {
-            if x >= 0 && y >= 0 && x < width && y < height {
-                if cave[[x as usize, y as usize]].suitable(&tool) {
-                    queue.push((x, y, tool, time));
-                }
-            }
-        }
-    }
-
-    if let Some(time) = dists.get(&(tx, ty, Torch)) {
-        *time
-    } else {
-        panic!("path unreachable!");
-    }
+        adj.iter()
+            .cloned()
+            .filter(|&((x, y, tool), _)| {
+                x >= 0
+                    && y >= 0
+                    && x < width
+                    && y < height
+                    && cave[[x as usize, y as usize]].suitable(&tool)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Every remaining step takes at least one minute, and switching to the
+    // torch takes seven.
+    let heuristic = |&(x, y, tool): &(isize, isize, Tool)| {
+        let dist = ((x - tx).abs() + (y - ty).abs()) as i32;
+        dist + iff!(tool == Torch, 0, 7)
+    };
+
+    let paths = astar(vec![(0, 0, Torch)], successors, heuristic, |&s| {
+        s == (tx, ty, Torch)
+    });
+
+    paths.goal_distance().context("target is unreachable")
 }
 
-pub fn run(_: &[&str]) {
-    let (depth, target) = parse_input();
+pub fn run(lines: Lines) -> Result {
+    let (depth, target) = parse_input(lines)?;
     let (width, height) = (target[0] * 2, target[1] * 2);
     let cave = build_cave(width, height, depth, target);
 
@@ -136,6 +140,8 @@ pub fn run(_: &[&str]) {
         .sum();
     println!("Answer A: {:?}", total_risk);
 
-    let fastest = find_paths(&cave, target);
+    let fastest = find_paths(&cave, target)?;
     println!("answer B: {:?}", fastest);
+
+    Ok(())
 }
@@ -1,9 +1,9 @@
-use crate::common::read_file_lines;
-use enumset::EnumSet;
-use regex::Regex;
+use crate::common::*;
+use advent_common::bitset::SmallSet;
+use advent_common::parse::{Cursor, ParseResult, Sections};
 use std::collections::HashSet;
 
-#[derive(EnumSetType, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 enum Attack {
     Fire,
     Radiation,
@@ -13,15 +13,14 @@ enum Attack {
 }
 
 impl Attack {
-    fn from(s: &str) -> Option<Self> {
-        Some(match s {
-            "fire" => Attack::Fire,
-            "radiation" => Attack::Radiation,
-            "slashing" => Attack::Slashing,
-            "bludgeoning" => Attack::Bludgeoning,
-            "cold" => Attack::Cold,
-            _ => return None,
-        })
+    fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        c.one_of(&[
+            ("fire", Attack::Fire),
+            ("radiation", Attack::Radiation),
+            ("slashing", Attack::Slashing),
+            ("bludgeoning", Attack::Bludgeoning),
+            ("cold", Attack::Cold),
+        ])
     }
 }
 
@@ -38,72 +37,69 @@ struct Army {
     damage: i64,
     initiative: i64,
     team: Team,
-    immune: EnumSet<Attack>,
-    weakness: EnumSet<Attack>,
+    immune: SmallSet,
+    weakness: SmallSet,
     attack: Attack,
 }
 
-fn parse_input() -> Vec<Army> {
-    let re = Regex::new(concat!(
-        r"([0-9]+) units each with ([0-9]+) hit points ",
-        r"(?:\(([a-z ,;]+)\) )?with an attack that does ",
-        r"([0-9]+) ([a-z]+) damage at initiative ([0-9]+)",
-    ))
-    .unwrap();
+fn parse_army(c: &mut Cursor, team: Team) -> ParseResult<Army> {
+    let mut immune = SmallSet::new();
+    let mut weakness = SmallSet::new();
 
-    let lines = read_file_lines("inputs/day24");
-    let mut team = Team::Infection;
-    let mut armies = vec![];
+    let units = c.int()?;
+    c.tag(" units each with ")?;
+    let hp = c.int()?;
+    c.tag(" hit points ")?;
+
+    if c.eat("(") {
+        c.list("; ", |c| {
+            let is_weak = c.one_of(&[("weak to ", true), ("immune to ", false)])?;
 
-    for line in lines {
-        if line.is_empty() {
-            continue;
-        } else if &line == "Immune System:" {
-            team = Team::Immune;
-        } else if &line == "Infection:" {
-            team = Team::Infection;
-        } else if let Some(cap) = re.captures(&line) {
-            let mut immune = EnumSet::new();
-            let mut weakness = EnumSet::new();
-
-            if let Some(c) = cap.get(3) {
-                for part in c.as_str().split("; ") {
-                    if part.starts_with("weak to ") {
-                        for s in part[8..].split(", ") {
-                            weakness |= Attack::from(s).unwrap();
-                        }
-                    } else if part.starts_with("immune to ") {
-                        for s in part[10..].split(", ") {
-                            immune |= Attack::from(s).unwrap();
-                        }
-                    } else {
-                        panic!("unknown part: {:?}", part);
-                    }
+            for attack in c.list(", ", Attack::parse)? {
+                if is_weak {
+                    weakness.insert(attack as usize);
+                } else {
+                    immune.insert(attack as usize);
                 }
             }
 
-            let units = cap[1].parse().unwrap();
-            let hp = cap[2].parse().unwrap();
-            let damage = cap[4].parse().unwrap();
-            let attack = Attack::from(&cap[5]).unwrap();
-            let initiative = cap[6].parse().unwrap();
-
-            armies.push(Army {
-                immune,
-                weakness,
-                team,
-                units,
-                hp,
-                damage,
-                attack,
-                initiative,
-            });
-        } else {
-            panic!("failed to parse line: {:?}", line);
-        }
+            Ok(())
+        })?;
+        c.tag(") ")?;
+    }
+
+    c.tag("with an attack that does ")?;
+    let damage = c.int()?;
+    c.tag(" ")?;
+    let attack = Attack::parse(c)?;
+    c.tag(" damage at initiative ")?;
+    let initiative = c.int()?;
+
+    Ok(Army {
+        immune,
+        weakness,
+        team,
+        units,
+        hp,
+        damage,
+        attack,
+        initiative,
+    })
+}
+
+fn parse_input(lines: Lines) -> Result<Vec<Army>> {
+    let sections = Sections::new(lines);
+    let mut armies = vec![];
+
+    for &(header, team) in &[
+        ("Immune System:", Team::Immune),
+        ("Infection:", Team::Infection),
+    ] {
+        let block = sections.named(header)?;
+        armies.extend(block.parse_lines(|c| parse_army(c, team))?);
     }
 
-    armies
+    Ok(armies)
 }
 
 fn calc_damage(from: &Army, to: &Army) -> i64 {
@@ -113,11 +109,11 @@ fn calc_damage(from: &Army, to: &Army) -> i64 {
         dmg = 0;
     }
 
-    if to.immune.contains(from.attack) {
+    if to.immune.contains(from.attack as usize) {
         dmg = 0;
     }
 
-    if to.weakness.contains(from.attack) {
+    if to.weakness.contains(from.attack as usize) {
         dmg *= 2;
     }
 
@@ -207,8 +203,8 @@ fn total_units(armies: &[Army]) -> i64 {
     armies.iter().map(|a| a.units).sum()
 }
 
-pub fn run(_: &[&str]) {
-    let armies = parse_input();
+pub fn run(lines: Lines) -> Result {
+    let armies = parse_input(lines)?;
 
     let mut simple = armies.clone();
     simulate_war(&mut simple);
@@ -233,4 +229,6 @@ pub fn run(_: &[&str]) {
     }
 
     println!("answer B: {}", total_units(&result));
+
+    Ok(())
 }
@@ -1,21 +1,18 @@
-use crate::common::read_file_lines;
+use crate::common::*;
+use advent_common::parse::{Block, Cursor};
 use itertools::enumerate;
 use std::collections::HashSet;
 use std::iter::FromIterator;
 
-pub fn run(_: &[&str]) {
+pub fn run(lines: Lines) -> Result {
     let mut points: Vec<[i64; 4]> = vec![];
     let mut labels = vec![];
-    let lines = read_file_lines("inputs/day25");
 
-    for line in lines {
-        let mut parts = line.split(",");
-        points.push([
-            parts.next().unwrap().parse().unwrap(),
-            parts.next().unwrap().parse().unwrap(),
-            parts.next().unwrap().parse().unwrap(),
-            parts.next().unwrap().parse().unwrap(),
-        ]);
+    for coords in Block::new(lines).parse_lines(|c| c.list(",", Cursor::int))? {
+        match coords[..] {
+            [x, y, z, w] => points.push([x, y, z, w]),
+            _ => bail!("point {} does not have four coordinates", points.len() + 1),
+        }
 
         labels.push(points.len());
     }
@@ -31,7 +28,7 @@ pub fn run(_: &[&str]) {
                 let (src, dst) = (labels[i], labels[j]);
 
                 for l in &mut labels {
-                    if *l == src { 
+                    if *l == src {
                         *l = dst;
                     }
                 }
@@ -41,4 +38,6 @@ pub fn run(_: &[&str]) {
 
     let uniq_labels = HashSet::<_>::from_iter(labels);
     println!("answer A: {}", uniq_labels.len());
+
+    Ok(())
 }
@@ -0,0 +1,140 @@
+use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
+
+pub const NUM_REGS: usize = 6;
+
+pub type Regs = [i64; NUM_REGS];
+
+// The names of the opcodes, together with whether operands a and b refer
+// to registers. Operand c is always a register.
+const OPCODES: [(&str, bool, bool); 16] = [
+    ("addr", true, true),
+    ("addi", true, false),
+    ("mulr", true, true),
+    ("muli", true, false),
+    ("banr", true, true),
+    ("bani", true, false),
+    ("borr", true, true),
+    ("bori", true, false),
+    ("setr", true, false),
+    ("seti", false, false),
+    ("gtir", false, true),
+    ("gtri", true, false),
+    ("gtrr", true, true),
+    ("eqir", false, true),
+    ("eqri", true, false),
+    ("eqrr", true, true),
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Instr {
+    pub name: &'static str,
+    pub a: i64,
+    pub b: i64,
+    pub c: i64,
+}
+
+impl Instr {
+    /// Registers read by this instruction.
+    pub fn reads(&self) -> impl Iterator<Item = usize> {
+        let (_, a_reg, b_reg) = OPCODES.iter().find(|op| op.0 == self.name).unwrap();
+        let a = iff!(*a_reg, Some(self.a as usize), None);
+        let b = iff!(*b_reg, Some(self.b as usize), None);
+        a.into_iter().chain(b)
+    }
+
+    pub fn exec(&self, regs: &mut Regs) -> Result {
+        let to_int = |x| if x { 1 } else { 0 };
+
+        let (a, b, c) = (self.a, self.b, self.c);
+        let (ua, ub, uc) = (a as usize, b as usize, c as usize);
+
+        regs[uc] = match self.name {
+            "addr" => regs[ua].checked_add(regs[ub]),
+            "addi" => regs[ua].checked_add(b),
+            "mulr" => regs[ua].checked_mul(regs[ub]),
+            "muli" => regs[ua].checked_mul(b),
+            "banr" => Some(regs[ua] & regs[ub]),
+            "bani" => Some(regs[ua] & b),
+            "borr" => Some(regs[ua] | regs[ub]),
+            "bori" => Some(regs[ua] | b),
+            "setr" => Some(regs[ua]),
+            "seti" => Some(a),
+            "gtir" => Some(to_int(a > regs[ub])),
+            "gtri" => Some(to_int(regs[ua] > b)),
+            "gtrr" => Some(to_int(regs[ua] > regs[ub])),
+            "eqir" => Some(to_int(a == regs[ub])),
+            "eqri" => Some(to_int(regs[ua] == b)),
+            "eqrr" => Some(to_int(regs[ua] == regs[ub])),
+            _ => unreachable!(),
+        }
+        .with_context(|| format!("overflow in {:?}", self))?;
+
+        Ok(())
+    }
+}
+
+fn parse_register(c: &mut Cursor) -> ParseResult<i64> {
+    let start = *c;
+    let reg = c.int()?;
+
+    if reg < 0 || reg >= NUM_REGS as i64 {
+        return Err(start.error(format_args!("invalid register {}", reg)));
+    }
+
+    Ok(reg)
+}
+
+fn parse_instr(c: &mut Cursor) -> ParseResult<Instr> {
+    let start = *c;
+    let name = c.word()?;
+    let &(name, a_reg, b_reg) = OPCODES
+        .iter()
+        .find(|op| op.0 == name)
+        .ok_or_else(|| start.error(format_args!("unknown instruction {:?}", name)))?;
+
+    let operand = |c: &mut Cursor, is_reg| {
+        c.tag(" ")?;
+        iff!(is_reg, parse_register(c), c.int())
+    };
+
+    let a = operand(c, a_reg)?;
+    let b = operand(c, b_reg)?;
+    let dst = operand(c, true)?;
+
+    Ok(Instr { name, a, b, c: dst })
+}
+
+/// A program, where register `ip` is bound to the instruction pointer.
+#[derive(Debug, Clone)]
+pub struct Program {
+    pub ip: usize,
+    pub instrs: Vec<Instr>,
+}
+
+impl Program {
+    pub fn parse(lines: Lines) -> Result<Self> {
+        let (mut header, body) = Block::new(lines).split_first()?;
+        header.tag("#ip ")?;
+        let ip = parse_register(&mut header)? as usize;
+        header.end()?;
+
+        let instrs = body.parse_lines(parse_instr)?;
+
+        Ok(Self { ip, instrs })
+    }
+
+    /// Executes the instruction the instruction pointer points to. Returns
+    /// false if the program has halted.
+    pub fn step(&self, regs: &mut Regs) -> Result<bool> {
+        let instr = match self.instrs.get(regs[self.ip] as usize) {
+            Some(instr) if regs[self.ip] >= 0 => instr,
+            _ => return Ok(false),
+        };
+
+        instr.exec(regs)?;
+        regs[self.ip] += 1;
+
+        Ok(true)
+    }
+}
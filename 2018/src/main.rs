@@ -1,16 +1,11 @@
 #![feature(try_from)]
 #![feature(bind_by_move_pattern_guards)]
-use std::env;
 
 extern crate image;
-#[macro_use]
 extern crate itertools;
 #[macro_use]
 extern crate ndarray;
-extern crate binary_heap_plus;
-extern crate regex;
-#[macro_use]
-extern crate enumset;
+extern crate advent_common;
 
 #[macro_use]
 mod common;
@@ -39,14 +34,14 @@ mod day22;
 mod day23;
 mod day24;
 mod day25;
+mod elfcode;
 
-fn main() {
-    let mut args = env::args();
-    let binary = args.next().unwrap();
-    let name = args.next();
-    let tail = args.collect::<Vec<_>>();
-    let rest = tail.iter().map(|x| x as &str).collect::<Vec<_>>();
+use crate::common::*;
+use std::env;
+use std::fs::read_to_string;
+use std::path::Path;
 
+fn main() -> Result {
     let funs = [
         day01::run,
         day02::run,
@@ -75,17 +70,36 @@ fn main() {
         day25::run,
     ];
 
-    match name.clone().map(|x| x.parse::<usize>()) {
-        Some(Ok(i)) if (i > 0 && i <= funs.len()) => {
-            funs[i - 1](&rest);
-        }
-        Some(_) => {
-            println!("invalid day: {:?}", name.unwrap_or_default());
-            return;
-        }
-        _ => {
-            println!("usage: {} [day]", binary);
-            return;
+    let mut args = env::args();
+    let binary = args.next().unwrap_or_default();
+    let day = args.next().unwrap_or_default();
+
+    let day = if let Ok(i) = day.parse::<usize>() {
+        i
+    } else {
+        bail!("usage: {} [day]", binary);
+    };
+
+    if day == 0 || day > funs.len() {
+        bail!("day must be a number between 1 and {}", funs.len());
+    }
+
+    let mut input_file = String::new();
+
+    for &prefix in &[".", "..", "inputs", "../inputs/"] {
+        input_file = format!("{}/day{:02}", prefix, day);
+
+        if Path::new(&input_file).exists() {
+            break;
         }
     }
+
+    let content =
+        read_to_string(&input_file).with_context(|| format!("failed to open: {}", input_file))?;
+    let lines = content
+        .trim_end_matches('\n')
+        .split('\n')
+        .collect::<Vec<_>>();
+
+    (funs[day - 1])(&lines)
 }
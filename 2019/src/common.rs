@@ -1,8 +1,6 @@
 pub use anyhow::{Context, Error};
 pub use itertools::{all, any, enumerate, zip, Itertools as _};
 use std::default::Default;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::iter::Sum;
 pub use thiserror::Error;
 
@@ -63,12 +61,4 @@ pub fn cycle<T: Clone>(val: T) -> impl Iterator<Item = T> {
 }
 
 pub type Result<T = (), E = Error> = std::result::Result<T, E>;
-
-pub(crate) fn read_input(filename: &str) -> Result<Vec<String>> {
-    let path = format!("inputs/{}", filename);
-    let f = File::open(&path).with_context(|| format!("failed to open {}", path))?;
-    BufReader::new(f)
-        .lines()
-        .collect::<Result<_, _>>()
-        .with_context(|| format!("error while reading {}", path))
-}
+pub type Lines<'a> = &'a [&'a str];
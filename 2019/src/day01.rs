@@ -12,11 +12,11 @@ fn fuel_for_mass_complex(x: f64) -> f64 {
     iff!(f > 0.0, f + fuel_for_mass_complex(f), 0.0)
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
+pub(crate) fn run(lines: Lines) -> Result {
     let mut part_a = 0.0;
     let mut part_b = 0.0;
 
-    for (index, line) in enumerate(read_input("day01")?) {
+    for (index, line) in enumerate(lines) {
         let mass = line
             .parse::<f64>()
             .with_context(|| format!("failed to parse mass on line {}", index + 1))?;
@@ -67,10 +67,9 @@ fn calculate_intersection(p: Line, q: Line) -> Option<(i64, i64)> {
     }
 }
 
-pub fn run(_args: &[&str]) -> Result {
-    let input = read_input("day03")?;
-    let a = parse_wire(&input[0])?;
-    let b = parse_wire(&input[1])?;
+pub fn run(lines: Lines) -> Result {
+    let a = parse_wire(lines[0])?;
+    let b = parse_wire(lines[1])?;
 
     let mut closest_dist = std::i64::MAX;
     let mut fastest_time = std::i64::MAX;
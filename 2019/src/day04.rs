@@ -19,8 +19,8 @@ fn is_valid_password(code: i64, exact: bool) -> bool {
     false
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let range = read_input("day04")?[0]
+pub(crate) fn run(lines: Lines) -> Result {
+    let range = lines[0]
         .split('-')
         .map(|x| x.parse::<i64>())
         .collect::<Result<Vec<_>, _>>()?;
@@ -1,8 +1,8 @@
 use crate::common::*;
 use crate::intcode::*;
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let original = parse_program("day05")?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let original = parse_program(lines)?;
 
     let mut program = original.clone();
     let outputs = program.run(&[1])?;
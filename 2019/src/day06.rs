@@ -1,11 +1,11 @@
 use crate::common::*;
 use std::collections::HashMap;
 
-pub(crate) fn run(_args: &[&str]) -> Result {
+pub(crate) fn run(lines: Lines) -> Result {
     // parse input
     let mut orbits = HashMap::new();
 
-    for line in read_input("day06")? {
+    for line in lines {
         if let Some(index) = line.find(')') {
             let src = line[..index].to_string();
             let dst = line[(index + 1)..].to_string();
@@ -23,8 +23,8 @@ fn permutations(n: usize) -> Vec<Vec<usize>> {
     output
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let original = parse_program("day07")?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let original = parse_program(lines)?;
     let mut max_value = 0;
 
     for order in permutations(5) {
@@ -4,9 +4,9 @@ fn count(slice: &[char], c: char) -> usize {
     slice.iter().map(|&a| (a == c) as usize).sum()
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
+pub(crate) fn run(lines: Lines) -> Result {
     let (width, height) = (25, 6);
-    let buffer = read_input("day08")?[0].trim().chars().collect::<Vec<_>>();
+    let buffer = lines[0].trim().chars().collect::<Vec<_>>();
 
     let layers = buffer.chunks_exact(width * height).collect::<Vec<_>>();
 
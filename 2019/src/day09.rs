@@ -1,8 +1,8 @@
 use crate::common::*;
 use crate::intcode::*;
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let mut program = parse_program("day09")?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let mut program = parse_program(lines)?;
 
     let output = program.run(&[1])?;
     println!("answer A: {:?}", output);
@@ -1,11 +1,8 @@
 use crate::common::*;
+use advent_common::math::gcd;
 use float_ord::FloatOrd;
 use std::collections::{BTreeMap, BinaryHeap, HashSet};
 
-fn gcd(a: isize, b: isize) -> isize {
-    iff!(b == 0, a, gcd(b, a % b))
-}
-
 fn count_detected_astroids(x: isize, y: isize, astroids: &[(isize, isize)]) -> usize {
     let mut angles = HashSet::new();
 
@@ -13,7 +10,7 @@ fn count_detected_astroids(x: isize, y: isize, astroids: &[(isize, isize)]) -> u
         if (ax, ay) != (x, y) {
             let dx = ax - x;
             let dy = ay - y;
-            let common = gcd(dx.abs(), dy.abs());
+            let common = gcd(dx as i64, dy as i64) as isize;
 
             angles.insert((dx / common, dy / common));
         }
@@ -53,10 +50,10 @@ fn order_astroids_by_laser(x: isize, y: isize, astroids: &[(isize, isize)]) -> V
     output
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
+pub(crate) fn run(lines: Lines) -> Result {
     let mut astroids = vec![];
 
-    for (y, line) in enumerate(read_input("day10")?) {
+    for (y, line) in enumerate(lines) {
         for (x, c) in enumerate(line.chars()) {
             if c == '#' {
                 astroids.push((x as isize, y as isize));
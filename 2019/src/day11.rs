@@ -72,8 +72,8 @@ fn paint(mut program: Program, start_tile: i64) -> Result<HashMap<(isize, isize)
     Ok(panels)
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let program = parse_program("day11")?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let program = parse_program(lines)?;
 
     let panels = paint(program.clone(), 0)?;
     println!("answer A: {:?}", panels.len());
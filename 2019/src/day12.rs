@@ -1,16 +1,7 @@
 use crate::common::*;
-use std::collections::HashMap;
-
-fn gcd(a: i64, b: i64) -> i64 {
-    match (a, b) {
-        (a, 0) => a,
-        (a, b) => gcd(b, a % b),
-    }
-}
-
-fn lcd(a: i64, b: i64) -> i64 {
-    (a * b) / gcd(a, b)
-}
+use advent_common::cycle::brent;
+use advent_common::math::lcm;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
 fn iterate_timestep(pos: &mut [[i64; 3]], vel: &mut [[i64; 3]]) {
     assert_eq!(pos.len(), vel.len());
@@ -39,6 +30,16 @@ fn iterate_timestep(pos: &mut [[i64; 3]], vel: &mut [[i64; 3]]) {
     }
 }
 
+fn iterate_axis(state: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    state
+        .iter()
+        .map(|&(p, v)| {
+            let gravity = state.iter().map(|&(q, _)| (q - p).signum()).sum::<i64>();
+            (p + v + gravity, v + gravity)
+        })
+        .collect()
+}
+
 fn calculate_energy(pos: &[[i64; 3]], vel: &[[i64; 3]]) -> i64 {
     let mut energy = 0;
 
@@ -49,22 +50,21 @@ fn calculate_energy(pos: &[[i64; 3]], vel: &[[i64; 3]]) -> i64 {
     energy
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let pattern = regex::Regex::new("<x=(-?[0-9]+), y=(-?[0-9]+), z=(-?[0-9]+)>")?;
-    let mut pos = vec![];
-    let mut vel = vec![];
-
-    for line in read_input("day12")? {
-        let m = pattern.captures(&line).unwrap();
+fn parse_moon(c: &mut Cursor) -> ParseResult<[i64; 3]> {
+    c.tag("<x=")?;
+    let x = c.int()?;
+    c.tag(", y=")?;
+    let y = c.int()?;
+    c.tag(", z=")?;
+    let z = c.int()?;
+    c.tag(">")?;
 
-        pos.push([
-            m[1].parse::<i64>().unwrap(),
-            m[2].parse::<i64>().unwrap(),
-            m[3].parse::<i64>().unwrap(),
-        ]);
+    Ok([x, y, z])
+}
 
-        vel.push([0, 0, 0]);
-    }
+pub(crate) fn run(lines: Lines) -> Result {
+    let pos = Block::new(lines).parse_lines(parse_moon)?;
+    let vel = vec![[0, 0, 0]; pos.len()];
 
     {
         let (mut pos, mut vel) = (pos.clone(), vel.clone());
@@ -74,33 +74,21 @@ pub(crate) fn run(_args: &[&str]) -> Result {
         println!("answer A: {}", calculate_energy(&pos, &vel));
     }
 
-    // find the cycles length along each axis
+    // find the cycles length along each axis. The axes are independent and
+    // every step can be reversed, thus the initial state is part of the cycle.
     let mut cycle_length = [0; 3];
     for k in 0..3 {
-        let mut states = HashMap::new();
-        let (mut pos, mut vel) = (pos.clone(), vel.clone());
-
-        for curr in 0.. {
-            // state is vector of coordinate along k-th axis for each planet
-            let state = zip(&pos, &vel)
-                .map(|(p, v)| (p[k], v[k]))
-                .collect::<Vec<_>>();
-
-            // if state has already been seen, we have hit a cycle
-            if let Some(prev) = states.insert(state, curr) {
-                cycle_length[k] = curr - prev;
-                println!("cycle along {}-th axis: {}", k, curr - prev);
-                break;
-            }
-
-            iterate_timestep(&mut pos, &mut vel);
-        }
+        // state is vector of coordinate along k-th axis for each planet
+        let state = zip(&pos, &vel)
+            .map(|(p, v)| (p[k], v[k]))
+            .collect::<Vec<_>>();
+        let cycle = brent(state, |state| iterate_axis(state));
+
+        cycle_length[k] = cycle.len as i64;
+        println!("cycle along {}-th axis: {}", k, cycle.len);
     }
 
-    let mut cycle = 1;
-    cycle = lcd(cycle, cycle_length[0]);
-    cycle = lcd(cycle, cycle_length[1]);
-    cycle = lcd(cycle, cycle_length[2]);
+    let cycle = cycle_length.iter().fold(1, |a, &b| lcm(a, b));
     println!("answer B: {:?}", cycle);
 
     Ok(())
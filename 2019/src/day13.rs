@@ -2,6 +2,7 @@ use crate::common::*;
 use crate::intcode::*;
 use ndarray::prelude::*;
 use std::cmp::Ordering;
+use std::env;
 use std::thread::sleep;
 use std::time::Duration;
 
@@ -81,10 +82,11 @@ fn print_board(board: &Array2<Tile>) {
     }
 }
 
-pub(crate) fn run(args: &[&str]) -> Result {
-    let mut program = parse_program("day13")?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let mut program = parse_program(lines)?;
     let mut board = Array2::from_elem((50, 25), Unknown);
     let mut score = 0;
+    let visualize = env::var_os("VISUALIZE").is_some();
 
     // insert quarter
     program.set(0, 2)?;
@@ -95,8 +97,8 @@ pub(crate) fn run(args: &[&str]) -> Result {
 
     // Run game until all blocks are gone
     while count_block_tiles(&board) > 0 {
-        // Print visualization if argument given
-        if !args.is_empty() {
+        // Print visualization if requested
+        if visualize {
             print_board(&board);
             sleep(Duration::from_millis(50));
         }
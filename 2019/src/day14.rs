@@ -7,7 +7,7 @@ struct Reaction {
     output: (String, i64),
 }
 
-fn parse_input() -> Result<Vec<Reaction>> {
+fn parse_input(lines: Lines) -> Result<Vec<Reaction>> {
     let mut reactions = vec![];
 
     fn parse_element(line: &str) -> Result<(String, i64)> {
@@ -18,7 +18,7 @@ fn parse_input() -> Result<Vec<Reaction>> {
         Ok((name, count))
     }
 
-    for line in read_input("day14")? {
+    for line in lines {
         let parts = line.splitn(2, " => ").collect::<Vec<_>>();
         let mut reaction: Reaction = default();
 
@@ -95,8 +95,8 @@ fn find_fuel_for_ore(reactions: &[Reaction], ore: i64) -> i64 {
     lbnd
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let mut reactions = parse_input()?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let mut reactions = parse_input(lines)?;
     toposort(&mut reactions);
 
     println!("answer A: {}", find_ore_for_fuel(&reactions, 1));
@@ -1,5 +1,6 @@
 use crate::common::*;
 use crate::intcode::*;
+use advent_common::search::{bfs, Paths};
 use ndarray::prelude::*;
 use std::collections::VecDeque;
 use std::mem::replace;
@@ -87,27 +88,20 @@ fn print_grid(grid: ArrayView2<Tile>) {
     }
 }
 
-fn calculate_dist(start: (usize, usize), grid: ArrayView2<Tile>) -> Array2<i64> {
-    let mut dist = grid.map(|_| std::i64::MAX);
-    let mut queue = VecDeque::new();
-    queue.push_front((start, 0));
-
-    while let Some((index, d)) = queue.pop_front() {
-        for &dir in &[North, East, South, West] {
-            let index = dir.apply_delta(index);
-
-            if grid[index] != Wall && dist[index] > d + 1 {
-                dist[index] = d + 1;
-                queue.push_back((index, d + 1));
-            }
-        }
-    }
+fn calculate_dist(start: (usize, usize), grid: ArrayView2<Tile>) -> Paths<(usize, usize), usize> {
+    let successors = |&index: &(usize, usize)| {
+        [North, East, South, West]
+            .iter()
+            .map(move |dir| dir.apply_delta(index))
+            .filter(|&index| grid[index] != Wall)
+            .collect_vec()
+    };
 
-    dist
+    bfs(vec![start], successors, |_| false)
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let program = parse_program("day15")?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let program = parse_program(lines)?;
 
     let dim = 50;
     let start = ((dim / 2), (dim / 2));
@@ -115,12 +109,17 @@ pub(crate) fn run(_args: &[&str]) -> Result {
 
     print_grid(grid.view());
 
-    let goal = grid.indexed_iter().find(|(_, &val)| val == Goal).unwrap().0;
+    let goal = grid
+        .indexed_iter()
+        .find(|(_, &val)| val == Goal)
+        .context("goal was not found")?
+        .0;
 
-    let dist = calculate_dist(goal, grid.view());
-    println!("answer A: {}", dist[start]);
+    let paths = calculate_dist(goal, grid.view());
+    let dist = paths.distance(&start).context("start is unreachable")?;
+    println!("answer A: {}", dist);
 
-    let max_dist = dist.iter().filter(|&&d| d != std::i64::MAX).max();
+    let max_dist = paths.distances().map(|(_, d)| d).max();
 
     println!("answer B: {:?}", max_dist);
 
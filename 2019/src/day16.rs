@@ -61,8 +61,8 @@ fn array2index(arr: &[i8]) -> usize {
     output
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let signal = read_input("day16")?[0]
+pub(crate) fn run(lines: Lines) -> Result {
+    let signal = lines[0]
         .chars()
         .map(|c| (c as i8) - ('0' as i8))
         .collect::<Vec<_>>();
@@ -1,57 +1,25 @@
 use crate::common::*;
 use crate::intcode::*;
+use advent_common::grid::{Grid, Pos};
 use itertools::Itertools;
 
-type Grid = Vec<Vec<char>>;
-
-pub(crate) fn read_grid(program: &mut Program) -> Result<Grid> {
-    let mut grid = Vec::new();
-    let (mut x, mut y) = (0, 0);
+pub(crate) fn read_grid(program: &mut Program) -> Result<Grid<char>> {
+    let mut output = String::new();
 
     while let ExecState::Output(c) = program.resume(None)? {
-        if c == '\n' as i64 {
-            x = 0;
-            y += 1;
-        } else {
-            if x >= grid.len() {
-                grid.push(vec![]);
-            }
-
-            if y >= grid[x].len() {
-                grid[x].push('.');
-            }
-
-            grid[x][y] = c as u8 as char;
-            x += 1;
-        }
+        output.push(c as u8 as char);
     }
 
-    Ok(grid)
+    let lines = output.lines().filter(|l| !l.is_empty()).collect_vec();
+    Ok(Grid::parse(&lines)?)
 }
 
-fn print_grid(grid: &Grid) {
-    for y in 0..grid[0].len() {
-        for x in 0..grid.len() {
-            print!("{}", grid[x][y]);
-        }
-        println!();
-    }
-}
-
-fn find_intersections(grid: &Grid) -> Vec<(usize, usize)> {
+fn find_intersections(grid: &Grid<char>) -> Vec<(usize, usize)> {
     let mut result = vec![];
-    let (w, h) = (grid.len(), grid[0].len());
-
-    for x in 1..(w - 1) {
-        for y in 1..(h - 1) {
-            if grid[x][y] == '#'
-                && grid[x][y + 1] == '#'
-                && grid[x][y - 1] == '#'
-                && grid[x - 1][y] == '#'
-                && grid[x + 1][y] == '#'
-            {
-                result.push((x, y))
-            }
+
+    for [x, y] in grid.positions() {
+        if grid[[x, y]] == '#' && grid.neighbors4([x, y]).filter(|&p| grid[p] == '#').count() == 4 {
+            result.push((x, y))
         }
     }
 
@@ -91,47 +59,45 @@ impl Direction {
     }
 }
 
-fn find_path(grid: &Grid) -> Vec<Step> {
-    let (w, h) = (grid.len(), grid[0].len());
+fn find_path(grid: &Grid<char>) -> Result<Vec<Step>> {
     let mut robot = None;
 
-    for x in 0..w {
-        for y in 0..h {
-            if let Some(dir) = match grid[x][y] {
-                '^' => Some(North),
-                '>' => Some(East),
-                '<' => Some(West),
-                'v' => Some(South),
-                _ => None,
-            } {
-                robot = Some((x, y, dir));
-            }
+    for (pos, &c) in grid.enumerate() {
+        if let Some(dir) = match c {
+            '^' => Some(North),
+            '>' => Some(East),
+            '<' => Some(West),
+            'v' => Some(South),
+            _ => None,
+        } {
+            robot = Some((pos, dir));
         }
     }
 
-    let (mut x, mut y, mut dir) = robot.expect("no robot found");
+    let (mut pos, mut dir) = robot.context("no robot found")?;
     let mut path = vec![];
 
-    let apply_delta = |x: usize, y: usize, d: Direction| -> (usize, usize) {
-        match d {
-            North => (x, y.wrapping_sub(1)),
-            East => (x + 1, y),
-            South => (x, y + 1),
-            West => (x.wrapping_sub(1), y),
-        }
+    let apply_delta = |pos: Pos, d: Direction| -> Option<Pos> {
+        let delta = match d {
+            North => [0, -1],
+            East => [1, 0],
+            South => [0, 1],
+            West => [-1, 0],
+        };
+
+        grid.offset(pos, delta)
     };
 
-    let is_walkable = |x: usize, y: usize, d: Direction| -> bool {
-        let (nx, ny) = apply_delta(x, y, d);
-        nx < w && ny < h && grid[nx][ny] != '.'
+    let is_walkable = |pos: Pos, d: Direction| -> bool {
+        matches!(apply_delta(pos, d), Some(p) if grid[p] != '.')
     };
 
     loop {
-        if !is_walkable(x, y, dir) {
-            if is_walkable(x, y, dir.turn_left()) {
+        if !is_walkable(pos, dir) {
+            if is_walkable(pos, dir.turn_left()) {
                 path.push(Left);
                 dir = dir.turn_left();
-            } else if is_walkable(x, y, dir.turn_right()) {
+            } else if is_walkable(pos, dir.turn_right()) {
                 path.push(Right);
                 dir = dir.turn_right();
             } else {
@@ -140,12 +106,10 @@ fn find_path(grid: &Grid) -> Vec<Step> {
         }
 
         path.push(Forward);
-        let pos = apply_delta(x, y, dir);
-        x = pos.0;
-        y = pos.1;
+        pos = apply_delta(pos, dir).unwrap();
     }
 
-    path
+    Ok(path)
 }
 
 fn path2command(path: &[Step]) -> String {
@@ -264,15 +228,15 @@ fn send_commands(program: &mut Program, trace: &[usize], routines: &[&[Step]]) -
     Ok(output)
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let mut program = parse_program("day17")?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let mut program = parse_program(lines)?;
     let grid = read_grid(&mut program)?;
-    print_grid(&grid);
+    println!("{}", grid);
 
     let alignments = map(|(x, y)| x * y, find_intersections(&grid));
     println!("answer A: {}", sum(alignments));
 
-    let path = find_path(&grid);
+    let path = find_path(&grid)?;
 
     let mut routines = vec![];
     let mut trace = vec![];
@@ -1,63 +1,15 @@
 use crate::common::*;
-use binary_heap_plus::BinaryHeap;
+use advent_common::bitset::SmallSet;
+use advent_common::parse::Block;
+use advent_common::search::{bfs, dijkstra};
 use itertools::Itertools;
 use ndarray::prelude::*;
-use std::collections::{HashMap, VecDeque};
-use std::fmt;
 
-#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
-struct KeySet(u32);
+type KeySet = SmallSet;
 
-impl fmt::Display for KeySet {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str("KeySet(")?;
-
-        for i in 0..26 {
-            let x = (b'a' + i) as char;
-            let c = iff!(self.contains_key(x), x, '.');
-            write!(f, "{}", c)?;
-        }
-
-        f.write_str(")")
-    }
-}
-
-impl KeySet {
-    fn new() -> Self {
-        Self(0)
-    }
-
-    fn all() -> Self {
-        Self((1 << 26) - 1)
-    }
-
-    fn from(c: char) -> Self {
-        if c >= 'a' && c <= 'z' {
-            let i = (c as usize) - ('a' as usize);
-            let mask = 1 << i;
-            Self(mask)
-        } else {
-            Self(0)
-        }
-    }
-
-    fn opens_door(self, c: char) -> bool {
-        if c >= 'A' && c <= 'Z' {
-            let i = (c as usize) - ('A' as usize);
-            let mask = 1 << i;
-            self.0 & mask != 0
-        } else {
-            false
-        }
-    }
-
-    fn contains_key(self, c: char) -> bool {
-        self.union(KeySet::from(c)) == self
-    }
-
-    fn union(self, other: KeySet) -> Self {
-        KeySet(self.0 | other.0)
-    }
+/// Index of the key that belongs to a key or a door.
+fn key_index(c: char) -> usize {
+    (c.to_ascii_lowercase() as usize) - ('a' as usize)
 }
 
 fn build_distance_matrix(grid: ArrayView2<char>, characters: &[char]) -> Array2<Option<usize>> {
@@ -65,41 +17,55 @@ fn build_distance_matrix(grid: ArrayView2<char>, characters: &[char]) -> Array2<
     let mut dists = Array2::from_elem((n, n), None);
 
     for (index, &target) in enumerate(characters) {
-        let (x, y) = grid
-            .indexed_iter()
-            .find(move |(_, &v)| v == target)
-            .expect("to find character")
-            .0;
-
-        let mut queue = VecDeque::new();
-        let mut visited = grid.map(|_| false);
-
-        queue.push_back((x, y, 0));
-        visited[[x, y]] = true;
-
-        while let Some((cx, cy, dist)) = queue.pop_front() {
-            let c = grid[[cx, cy]];
-
-            if let Some(offset) = characters.iter().position(|&v| v == c) {
+        // Keys and doors that are not in the maze cannot be reached.
+        let start = match grid.indexed_iter().find(move |(_, &v)| v == target) {
+            Some((start, _)) => start,
+            None => continue,
+        };
+
+        // Other keys and doors are endpoints, the search does not continue
+        // beyond them.
+        let successors = |&(x, y): &(usize, usize)| {
+            let c = grid[[x, y]];
+            let passable = c == '.' || c == target;
+
+            [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                .iter()
+                .cloned()
+                .filter(|&p| passable && grid[p] != '#')
+                .collect::<Vec<_>>()
+        };
+
+        let paths = bfs(vec![start], successors, |_| false);
+
+        for (&p, dist) in paths.distances() {
+            if let Some(offset) = characters.iter().position(|&v| v == grid[p]) {
                 dists[[index, offset]] = Some(dist);
             }
-
-            if c != '.' && c != target {
-                continue;
-            }
-
-            for &[x, y] in &[[cx - 1, cy], [cx + 1, cy], [cx, cy - 1], [cx, cy + 1]] {
-                if !visited[[x, y]] {
-                    visited[[x, y]] = true;
-                    queue.push_back((x, y, dist + 1));
-                }
-            }
         }
     }
 
     dists
 }
 
+fn next_states<'a>(
+    nodes: &'a [char],
+    dists: &'a Array2<Option<usize>>,
+    index: usize,
+    keys: KeySet,
+) -> impl Iterator<Item = (usize, KeySet, usize)> + 'a {
+    enumerate(zip(nodes, dists.row(index))).filter_map(move |(next_index, (&c, &l))| {
+        let l = l?;
+
+        if c.is_ascii_uppercase() && !keys.contains(key_index(c)) {
+            return None;
+        }
+
+        let keys = iff!(c.is_ascii_lowercase(), keys.with(key_index(c)), keys);
+        Some((next_index, keys, l))
+    })
+}
+
 fn explore_grid(grid: ArrayView2<char>) -> Option<usize> {
     let nodes = "@abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ"
         .chars()
@@ -107,38 +73,15 @@ fn explore_grid(grid: ArrayView2<char>) -> Option<usize> {
 
     let dists = build_distance_matrix(grid.view(), &nodes);
 
-    let mut queue = VecDeque::new();
-    let mut states = HashMap::new();
+    let successors = |&(index, keys): &(usize, KeySet)| {
+        next_states(&nodes, &dists, index, keys).map(|(next, keys, l)| ((next, keys), l))
+    };
 
-    states.insert((0, KeySet::new()), 0);
-    queue.push_back((0, KeySet::new()));
+    let paths = dijkstra(vec![(0, KeySet::new())], successors, |&(_, keys)| {
+        keys == KeySet::below(26)
+    });
 
-    while let Some((index, keys)) = queue.pop_front() {
-        let d = states[&(index, keys)];
-
-        for (next_index, (&c, &l)) in enumerate(zip(&nodes, &dists.row(index))) {
-            if let Some(l) = l {
-                if (c >= 'A' && c <= 'Z') && !keys.opens_door(c) {
-                    continue;
-                }
-
-                let next_keys = KeySet::union(keys, KeySet::from(c));
-                let entry = states
-                    .entry((next_index, next_keys))
-                    .or_insert(std::usize::MAX);
-
-                if *entry > d + l {
-                    *entry = d + l;
-                    queue.push_back((next_index, next_keys));
-                }
-            }
-        }
-    }
-
-    states
-        .into_iter()
-        .filter_map(|((_, keys), dist)| iff!(keys == KeySet::all(), Some(dist), None))
-        .min()
+    paths.goal_distance()
 }
 
 fn explore_grid_four(grid: ArrayView2<char>) -> Option<usize> {
@@ -148,63 +91,44 @@ fn explore_grid_four(grid: ArrayView2<char>) -> Option<usize> {
 
     let dists = build_distance_matrix(grid.view(), &nodes);
 
-    let mut queue = BinaryHeap::new_by_key(|p: &([usize; 4], KeySet, usize)| -(p.2 as isize));
-    let mut states = HashMap::new();
-
-    let k = ([0, 1, 2, 3], KeySet::new());
-    states.insert(k, 0);
-    queue.push((k.0, k.1, 0));
-    let mut waiting = 0;
-
-    while let Some((indices, keys, d)) = queue.pop() {
-        if waiting == 0 {
-            println!("{} {} {}", d, states.len(), queue.len());
-            waiting = 1_000_000;
-        }
-        waiting -= 1;
-
-        if keys == KeySet::all() {
-            return Some(d);
-        }
+    let successors = |&(indices, keys): &([usize; 4], KeySet)| {
+        let mut result = vec![];
 
         for robot in 0..4 {
-            let index = indices[robot];
-
-            for (next_index, (&c, &l)) in enumerate(zip(&nodes, &dists.row(index))) {
-                if let Some(l) = l {
-                    if (c >= 'A' && c <= 'Z') && !keys.opens_door(c) {
-                        continue;
-                    }
-
-                    let next_keys = KeySet::union(keys, KeySet::from(c));
-                    let mut next_indices = indices;
-                    next_indices[robot] = next_index;
-
-                    let entry = states
-                        .entry((next_indices, next_keys))
-                        .or_insert(std::usize::MAX);
-
-                    if *entry > d + l {
-                        *entry = d + l;
-                        queue.push((next_indices, next_keys, d + l));
-                    }
-                }
+            for (next, next_keys, l) in next_states(&nodes, &dists, indices[robot], keys) {
+                let mut next_indices = indices;
+                next_indices[robot] = next;
+                result.push(((next_indices, next_keys), l));
             }
         }
-    }
 
-    None
-}
+        result
+    };
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let lines = read_input("day18")?;
-    let (w, h) = (lines.len(), lines[0].len());
-    let mut grid = Array2::from_elem((w, h), '#');
+    let paths = dijkstra(
+        vec![([0, 1, 2, 3], KeySet::new())],
+        successors,
+        |&(_, keys)| keys == KeySet::below(26),
+    );
 
-    for (x, line) in enumerate(lines) {
-        for (y, c) in enumerate(line.chars()) {
-            grid[[x, y]] = c;
-        }
+    paths.goal_distance()
+}
+
+pub(crate) fn run(lines: Lines) -> Result {
+    let maze = Block::new(lines).parse_grid(Some)?;
+    let (w, h) = (maze.height(), maze.width());
+    let mut grid = Array2::from_shape_fn((w, h), |(x, y)| maze[[y, x]]);
+
+    // The search steps to neighbors without bounds checks, so the maze must
+    // be enclosed by walls.
+    let is_border = |x: usize, y: usize| x == 0 || y == 0 || x + 1 == w || y + 1 == h;
+    if w < 3
+        || h < 3
+        || grid
+            .indexed_iter()
+            .any(|((x, y), &c)| is_border(x, y) && c != '#')
+    {
+        bail!("maze is not surrounded by walls");
     }
 
     let answer = explore_grid(grid.view());
@@ -56,8 +56,8 @@ pub(crate) fn fit_ship(program: &Program) -> Result<(usize, usize)> {
     Ok(result)
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let program = parse_program("day19")?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let program = parse_program(lines)?;
 
     let count = scan_grid(&program, 50)?
         .iter()
@@ -1,30 +1,17 @@
 use crate::common::*;
-use std::collections::{HashMap, HashSet, VecDeque};
+use advent_common::grid::{Grid, Pos, NEIGHBORS4};
+use advent_common::search::bfs;
+use std::collections::HashMap;
 
-type Grid = HashMap<(i32, i32), char>;
-
-fn read_grid() -> Result<Grid> {
-    let mut grid = Grid::new();
-
-    for (x, line) in enumerate(read_input("day20")?) {
-        for (y, c) in enumerate(line.chars()) {
-            if c != ' ' {
-                grid.insert((x as i32, y as i32), c);
-            }
-        }
-    }
-
-    Ok(grid)
-}
-
-fn find_portals(grid: &Grid) -> HashMap<String, Vec<(i32, i32)>> {
+fn find_portals(grid: &Grid<char>) -> HashMap<String, Vec<Pos>> {
     let mut output: HashMap<_, Vec<_>> = HashMap::new();
 
-    for (&(x, y), &c) in grid {
+    for (p, &c) in grid.enumerate() {
         if c == '.' {
-            for &(dx, dy) in &[(0, 1), (0, -1), (1, 0), (-1, 0)] {
-                let a = grid.get(&(x - dx, y - dy)).unwrap_or(&' ');
-                let b = grid.get(&(x - 2 * dx, y - 2 * dy)).unwrap_or(&' ');
+            for &[dx, dy] in &NEIGHBORS4 {
+                let label = |n: isize| grid.offset(p, [-n * dx, -n * dy]).map(|q| grid[q]);
+                let a = label(1).unwrap_or(' ');
+                let b = label(2).unwrap_or(' ');
 
                 if a.is_ascii_alphabetic() && b.is_ascii_alphabetic() {
                     let name = iff!(
@@ -33,7 +20,7 @@ fn find_portals(grid: &Grid) -> HashMap<String, Vec<(i32, i32)>> {
                         format!("{}{}", b, a)
                     );
 
-                    output.entry(name).or_default().push((x, y));
+                    output.entry(name).or_default().push(p);
                 }
             }
         }
@@ -42,19 +29,16 @@ fn find_portals(grid: &Grid) -> HashMap<String, Vec<(i32, i32)>> {
     output
 }
 
-fn distance_to_center((x, y): (i32, i32)) -> i32 {
-    (x - 50) * (x - 50) + (y - 50) * (y - 50)
+fn distance_to_center(grid: &Grid<char>, [x, y]: Pos) -> usize {
+    let [cx, cy] = [grid.width() / 2, grid.height() / 2];
+    let [dx, dy] = [cx.max(x) - cx.min(x), cy.max(y) - cy.min(y)];
+    dx * dx + dy * dy
 }
 
-fn find_path_length(start: &str, end: &str, grid: &Grid, recur_space: bool) -> Option<usize> {
-    let portals = find_portals(&grid);
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
-    let mut options = vec![];
-
-    let (sx, sy) = portals[start][0];
-    let (ex, ey) = portals[end][0];
-    queue.push_back(((sx, sy, 0), 0));
+fn find_path_length(start: &str, end: &str, grid: &Grid<char>, recur_space: bool) -> Option<usize> {
+    let portals = find_portals(grid);
+    let start = portals[start][0];
+    let end = portals[end][0];
 
     let mut teleports = HashMap::new();
     for (_, list) in portals {
@@ -63,46 +47,40 @@ fn find_path_length(start: &str, end: &str, grid: &Grid, recur_space: bool) -> O
 
             let delta = if !recur_space {
                 0
-            } else if distance_to_center(a) < distance_to_center(b) {
+            } else if distance_to_center(grid, a) < distance_to_center(grid, b) {
                 1
             } else {
                 -1
             };
 
-            teleports.insert(a, (b.0, b.1, delta));
-            teleports.insert(b, (a.0, a.1, -delta));
+            teleports.insert(a, (b, delta));
+            teleports.insert(b, (a, -delta));
         }
     }
 
-    while let Some(((x, y, ring), dist)) = queue.pop_front() {
-        if (x, y, ring) == (ex, ey, 0) {
-            return Some(dist);
-        }
+    let successors = |&(pos, ring): &(Pos, i32)| {
+        let mut options = vec![];
 
-        for &(dx, dy) in &[(0, 1), (0, -1), (1, 0), (-1, 0)] {
-            if grid.get(&(x + dx, y + dy)) == Some(&'.') {
-                options.push((x + dx, y + dy, ring));
+        for q in grid.neighbors4(pos) {
+            if grid[q] == '.' {
+                options.push((q, ring));
             }
         }
 
-        if let Some(&(nx, ny, delta)) = teleports.get(&(x, y)) {
+        if let Some(&(next, delta)) = teleports.get(&pos) {
             if ring + delta >= 0 {
-                options.push((nx, ny, ring + delta));
+                options.push((next, ring + delta));
             }
         }
 
-        for p in options.drain(..) {
-            if visited.insert(p) {
-                queue.push_back((p, dist + 1));
-            }
-        }
-    }
+        options
+    };
 
-    None
+    bfs(vec![(start, 0)], successors, |&state| state == (end, 0)).goal_distance()
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let grid = read_grid()?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let grid = Grid::parse_padded(lines, ' ');
 
     let answer_a = find_path_length("AA", "ZZ", &grid, false);
     println!("answer A: {:?}", answer_a);
@@ -25,8 +25,8 @@ fn launch_springdroid(mut program: Program, code: &str) -> Result<Option<i64>> {
     Ok(None)
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let program = parse_program("day21")?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let program = parse_program(lines)?;
 
     // !(A && B && C) && D
     // Jump if D is true and any of [A, B, C] is false
@@ -1,18 +1,19 @@
 use crate::common::*;
+use advent_common::math::{mod_inverse, mod_mul};
 
 #[derive(Debug, Clone, Copy)]
 enum Technique {
     Reverse,
-    Cut(i128),
-    Deal(i128),
+    Cut(i64),
+    Deal(i64),
 }
 
 use Technique::*;
 
-fn parse_techniques() -> Result<Vec<Technique>> {
+fn parse_techniques(lines: Lines) -> Result<Vec<Technique>> {
     let mut output = vec![];
 
-    for line in read_input("day22")? {
+    for &line in lines {
         let t = if line == "deal into new stack" {
             Reverse
         } else if line.starts_with("cut") {
@@ -31,43 +32,18 @@ fn parse_techniques() -> Result<Vec<Technique>> {
     Ok(output)
 }
 
-fn apply_technique(t: Technique, index: i128, num: i128) -> i128 {
+fn apply_technique(t: Technique, index: i64, num: i64) -> i64 {
     assert!(index >= 0 && index < num);
 
     match t {
         Reverse => num - 1 - index,
-        Deal(n) => (n * index) % num,
-        Cut(n) => (index - n + num) % num,
+        Deal(n) => mod_mul(n, index, num),
+        Cut(n) => (index - n).rem_euclid(num),
     }
 }
 
-// Euclidean extended algorithm. From Wikipedia
-fn mult_inverse(a: i128, n: i128) -> i128 {
-    let mut t = 0;
-    let mut r = n;
-    let mut newt = 1;
-    let mut newr = a;
-
-    while newr != 0 {
-        let q = r / newr;
-
-        let oldt = t;
-        t = newt;
-        newt = oldt - q * newt;
-
-        let oldr = r;
-        r = newr;
-        newr = oldr - q * newr
-    }
-
-    while t < 0 {
-        t += n;
-    }
-    t
-}
-
 // applies "factor * x + constant" given "repeat" times
-fn repeated_apply(factor: i128, constant: i128, repeat: i128, n: i128) -> (i128, i128) {
+fn repeated_apply(factor: i64, constant: i64, repeat: u64, n: i64) -> (i64, i64) {
     // repeat == 0 so result is "1 * x + 0"
     if repeat == 0 {
         (1, 0)
@@ -76,21 +52,21 @@ fn repeated_apply(factor: i128, constant: i128, repeat: i128, n: i128) -> (i128,
     else if repeat % 2 == 0 {
         let (f, c) = repeated_apply(factor, constant, repeat / 2, n);
 
-        ((f * f).rem_euclid(n), (f * c + c).rem_euclid(n))
+        (mod_mul(f, f, n), (mod_mul(f, c, n) + c).rem_euclid(n))
     }
     // repeat == 2k + 1
     else {
         let (f, c) = repeated_apply(factor, constant, repeat - 1, n);
 
         (
-            (factor * f).rem_euclid(n),
-            (factor * c + constant).rem_euclid(n),
+            mod_mul(factor, f, n),
+            (mod_mul(factor, c, n) + constant).rem_euclid(n),
         )
     }
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let input = parse_techniques()?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let input = parse_techniques(lines)?;
 
     let n = 10007;
     let mut index = 2019;
@@ -116,8 +92,8 @@ pub(crate) fn run(_args: &[&str]) -> Result {
     let factor = (b - a).rem_euclid(n);
     println!("equation: {} x + {}", factor, constant);
 
-    let inv_factor = mult_inverse(factor, n);
-    let inv_constant = (-inv_factor * constant).rem_euclid(n);
+    let inv_factor = mod_inverse(factor, n).context("factor is not invertible")?;
+    let inv_constant = mod_mul(-inv_factor, constant, n);
     println!("inverse equation: {} x + {}", inv_factor, inv_constant);
 
     let (rep_factor, rep_constant) = repeated_apply(inv_factor, inv_constant, repeats, n);
@@ -126,8 +102,8 @@ pub(crate) fn run(_args: &[&str]) -> Result {
         inv_factor, inv_constant
     );
 
-    let index = 2020i128;
-    let result = (rep_factor * index + rep_constant).rem_euclid(n);
+    let index = 2020;
+    let result = (mod_mul(rep_factor, index, n) + rep_constant) % n;
 
     println!("answer B: {:?}", result);
 
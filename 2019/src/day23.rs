@@ -2,8 +2,8 @@ use crate::common::*;
 use crate::intcode::*;
 use std::mem::take;
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let program = parse_program("day23")?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let program = parse_program(lines)?;
 
     let mut computers = vec![];
     let mut queues = vec![];
@@ -240,8 +240,8 @@ fn crack_security(program: &mut Program, inventory: &[String]) -> Result<Room> {
     bail!("failed to crack security");
 }
 
-pub(crate) fn run(_args: &[&str]) -> Result {
-    let mut program = parse_program("day25")?;
+pub(crate) fn run(lines: Lines) -> Result {
+    let mut program = parse_program(lines)?;
     let inventory = pickup_everything_and_find_security(&mut program)?;
     crack_security(&mut program, &inventory)?;
 
@@ -31,8 +31,10 @@ pub(crate) enum ExecState {
     Output(i64),
 }
 
-pub(crate) fn parse_program(filename: &str) -> Result<Program> {
-    let program = read_input(filename)?[0]
+pub(crate) fn parse_program(lines: Lines) -> Result<Program> {
+    let program = lines
+        .first()
+        .context("empty program")?
         .split(',')
         .map(|s| s.parse::<i64>())
         .collect::<Result<Vec<_>, _>>()?;
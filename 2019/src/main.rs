@@ -30,9 +30,12 @@ mod day24;
 mod day25;
 mod intcode;
 
+use crate::common::*;
 use std::env;
+use std::fs::read_to_string;
+use std::path::Path;
 
-fn main() {
+fn main() -> Result {
     let funs = [
         day01::run,
         day02::run,
@@ -61,21 +64,36 @@ fn main() {
         day25::run,
     ];
 
-    let args: Vec<_> = env::args().collect();
-    let args: Vec<_> = args.iter().map(String::as_ref).collect();
-    let binary = args.get(0).unwrap_or(&"");
-    let day = args.get(1).unwrap_or(&"");
-    let rest = args.get(2..).unwrap_or(&[]);
+    let mut args = env::args();
+    let binary = args.next().unwrap_or_default();
+    let day = args.next().unwrap_or_default();
 
-    if let Ok(x) = day.parse::<usize>() {
-        if x > 0 && x <= funs.len() {
-            if let Err(msg) = (funs[x - 1])(&rest) {
-                eprintln!("error occurred: {:?}", msg);
-            }
-        } else {
-            eprintln!("day must be between 1 and {}", funs.len() + 1);
-        }
+    let day = if let Ok(i) = day.parse::<usize>() {
+        i
     } else {
-        eprintln!("usage: {} [day]", binary);
+        bail!("usage: {} [day]", binary);
+    };
+
+    if day == 0 || day > funs.len() {
+        bail!("day must be a number between 1 and {}", funs.len());
     }
+
+    let mut input_file = String::new();
+
+    for &prefix in &[".", "..", "inputs", "../inputs/"] {
+        input_file = format!("{}/day{:02}", prefix, day);
+
+        if Path::new(&input_file).exists() {
+            break;
+        }
+    }
+
+    let content =
+        read_to_string(&input_file).with_context(|| format!("failed to open: {}", input_file))?;
+    let lines = content
+        .trim_end_matches('\n')
+        .split('\n')
+        .collect::<Vec<_>>();
+
+    (funs[day - 1])(&lines)
 }
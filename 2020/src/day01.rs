@@ -1,9 +1,9 @@
 use crate::common::*;
 use std::cmp::Ordering::*;
 
-fn parse_input(filename: &str) -> Result<Vec<usize>> {
-    let mut numbers = read_input(filename)?
-        .into_iter()
+fn parse_input(lines: Lines) -> Result<Vec<usize>> {
+    let mut numbers = lines
+        .iter()
         .filter(|x| !x.is_empty())
         .map(|x| x.parse().context("invalid number"))
         .collect::<Result<Vec<_>, _>>()?;
@@ -40,8 +40,8 @@ fn find_three(numbers: &[usize], sum: usize) -> Option<[usize; 3]> {
     None
 }
 
-pub fn run() -> Result {
-    let numbers = parse_input("day01")?;
+pub fn run(lines: Lines) -> Result {
+    let numbers = parse_input(lines)?;
 
     let [a, b] = find_two(&numbers, 2020)
         .ok_or_else(|| anyhow!("failed to find two numbers that sum to 2020"))?;
@@ -1,6 +1,5 @@
 use crate::common::*;
-use lazy_static::lazy_static;
-use regex::Regex;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
 #[derive(PartialEq, Eq, Debug)]
 struct Password {
@@ -10,23 +9,25 @@ struct Password {
     password: String,
 }
 
-fn parse_line(line: &str) -> Result<Password> {
-    lazy_static! {
-        static ref PATTERN: Regex = Regex::new(r"^(\d+)-(\d+) ([a-z]): ([a-z]+)$").unwrap();
+fn parse_password(c: &mut Cursor) -> ParseResult<Password> {
+    let start = *c;
+    let lowest = c.int()?;
+    c.tag("-")?;
+    let highest = c.int()?;
+    c.tag(" ")?;
+    let letter = c.next_char()?;
+    c.tag(": ")?;
+    let password = c.take_while(|c| c.is_ascii_lowercase()).to_string();
+
+    if lowest == 0 || lowest > highest || highest > password.len() {
+        return Err(start.error("positions must be within the password"));
     }
 
-    let matches = PATTERN.captures(line).ok_or_else(|| {
-        anyhow!(
-            "line {:?} does not match pattern [0-9]-[0-9] [a-z]: [a-z]+",
-            line
-        )
-    })?;
-
     Ok(Password {
-        lowest: matches[1].parse().unwrap(),
-        highest: matches[2].parse().unwrap(),
-        letter: matches[3].chars().next().unwrap(),
-        password: matches[4].to_string(),
+        lowest,
+        highest,
+        letter,
+        password,
     })
 }
 
@@ -37,17 +38,15 @@ fn is_valid_sled_rental_place(w: &Password) -> bool {
 }
 
 fn is_valid_official_toboggan_corporate(w: &Password) -> bool {
-    let a = w.password.chars().nth(w.lowest - 1).unwrap() == w.letter;
-    let b = w.password.chars().nth(w.highest - 1).unwrap() == w.letter;
+    // The positions are checked against the password while parsing.
+    let a = w.password.chars().nth(w.lowest - 1) == Some(w.letter);
+    let b = w.password.chars().nth(w.highest - 1) == Some(w.letter);
 
     a ^ b
 }
 
-pub fn run() -> Result {
-    let lines = read_input("day02")?
-        .iter()
-        .map(|s| parse_line(s))
-        .collect::<Result<Vec<_>>>()?;
+pub fn run(lines: Lines) -> Result {
+    let lines = Block::new(lines).parse_lines(parse_password)?;
 
     let count_valid = lines
         .iter()
@@ -69,6 +68,11 @@ pub fn run() -> Result {
 #[cfg(test)]
 mod test {
     use super::*;
+    use advent_common::parse::parse_line;
+
+    fn password(line: &str) -> Password {
+        parse_line(1, line, parse_password).unwrap()
+    }
 
     #[test]
     fn test_is_valid() {
@@ -95,11 +99,7 @@ mod test {
             },
         ];
 
-        let results = vec![
-            parse_line(lines[0]).unwrap(),
-            parse_line(lines[1]).unwrap(),
-            parse_line(lines[2]).unwrap(),
-        ];
+        let results = [password(lines[0]), password(lines[1]), password(lines[2])];
 
         assert_eq!(pw[0], results[0]);
         assert_eq!(pw[1], results[1]);
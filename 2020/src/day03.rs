@@ -1,23 +1,14 @@
 use crate::common::*;
+use advent_common::parse::Block;
 use ndarray::prelude::*;
 
-fn parse_input(lines: Vec<String>) -> Result<Array2<char>> {
-    let height = lines.len();
-    let width = lines[0].len();
+fn parse_input(lines: Lines) -> Result<Array2<char>> {
+    let grid = Block::new(lines).parse_grid(|c| matches!(c, '#' | '.').then(|| c))?;
+    let (height, width) = (grid.height(), grid.width());
 
-    if !all(&lines, |v| v.chars().count() == width) {
-        return Err(anyhow!("not all lines have equal length"));
-    }
-
-    let mut result = Array2::default((height, width));
-
-    for (i, line) in enumerate(lines) {
-        for (j, c) in enumerate(line.chars()) {
-            result[[i, j]] = c;
-        }
-    }
-
-    Ok(result)
+    Ok(Array2::from_shape_fn((height, width), |(i, j)| {
+        grid[[j, i]]
+    }))
 }
 
 fn count_trees(matrix: ArrayView2<char>, shift_per_row: usize, shift_per_col: usize) -> usize {
@@ -36,8 +27,8 @@ fn count_trees(matrix: ArrayView2<char>, shift_per_row: usize, shift_per_col: us
     trees
 }
 
-pub fn run() -> Result {
-    let matrix = parse_input(read_input("day03")?)?;
+pub fn run(lines: Lines) -> Result {
+    let matrix = parse_input(lines)?;
 
     let trees = count_trees(matrix.view(), 3, 1);
     println!("part A: {}", trees);
@@ -1,26 +1,20 @@
 use crate::common::*;
-use lazy_static::lazy_static;
-use regex::Regex;
+use advent_common::parse::{Cursor, ParseResult, Sections};
 use std::collections::HashMap;
 
-fn parse_input(content: &str) -> Vec<HashMap<&str, &str>> {
-    lazy_static! {
-        static ref SPLIT_LINES: Regex = Regex::new("[ ]*\n[ ]*\n[ ]*").unwrap();
-        static ref FIELD: Regex = Regex::new("([^ \n:]*):([^ \n]*)").unwrap();
-    }
+fn parse_fields<'a>(c: &mut Cursor<'a>) -> ParseResult<Vec<(&'a str, &'a str)>> {
+    c.record(" ", ":", |c| Ok(c.take_while(|v| v != ' ')))
+}
 
+fn parse_input<'a>(lines: Lines<'a>) -> Result<Vec<HashMap<&'a str, &'a str>>> {
     let mut result = vec![];
-    for chunk in SPLIT_LINES.split(&content) {
-        let mut fields = HashMap::new();
-
-        for part in FIELD.captures_iter(chunk) {
-            fields.insert(part.get(1).unwrap().as_str(), part.get(2).unwrap().as_str());
-        }
 
-        result.push(fields);
+    for block in Sections::new(lines).iter() {
+        let fields = block.parse_lines(parse_fields)?;
+        result.push(fields.into_iter().flatten().collect());
     }
 
-    result
+    Ok(result)
 }
 
 fn has_fields(passport: &HashMap<&str, &str>) -> bool {
@@ -49,8 +43,15 @@ fn is_valid_passport(passport: &HashMap<&str, &str>) -> bool {
         }
     }
 
-    fn check_regex(value: &str, regex: &str) -> bool {
-        Regex::new(regex).unwrap().is_match(value)
+    fn check_hair(value: &str) -> bool {
+        match value.strip_prefix('#') {
+            Some(hex) => hex.len() == 6 && all(hex.chars(), |c| matches!(c, '0'..='9' | 'a'..='f')),
+            None => false,
+        }
+    }
+
+    fn check_pid(value: &str) -> bool {
+        value.len() == 9 && all(value.chars(), |c| c.is_ascii_digit())
     }
 
     fn check_eyes(value: &str) -> bool {
@@ -62,14 +63,13 @@ fn is_valid_passport(passport: &HashMap<&str, &str>) -> bool {
         && check_int(passport["iyr"], 2010, 2020)
         && check_int(passport["eyr"], 2020, 2030)
         && check_height(passport["hgt"])
-        && check_regex(passport["hcl"], "^#[0-9a-f]{6}$")
+        && check_hair(passport["hcl"])
         && check_eyes(passport["ecl"])
-        && check_regex(passport["pid"], "^[0-9]{9}$")
+        && check_pid(passport["pid"])
 }
 
-pub fn run() -> Result {
-    let content = &read_input("day04")?.join("\n");
-    let passports = parse_input(&content);
+pub fn run(lines: Lines) -> Result {
+    let passports = parse_input(lines)?;
 
     let count = passports.iter().filter(|p| has_fields(p)).count();
     println!("part A: {}", count);
@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use std::collections::BTreeSet;
 
 fn boarding_pass_to_number(string: &str) -> usize {
@@ -7,20 +8,35 @@ fn boarding_pass_to_number(string: &str) -> usize {
         .fold(0, |v, c| v * 2 + "RB".contains(c) as usize)
 }
 
-pub fn run() -> Result {
-    let passes = read_input("day05")?
-        .iter()
-        .map(|s| boarding_pass_to_number(s))
+fn parse_pass(c: &mut Cursor) -> ParseResult<usize> {
+    let start = *c;
+    let row = c.take_while(|c| c == 'F' || c == 'B');
+    let column = c.take_while(|c| c == 'L' || c == 'R');
+
+    if row.len() != 7 || column.len() != 3 {
+        return Err(start.error("expected 7 times F or B followed by 3 times L or R"));
+    }
+
+    Ok(boarding_pass_to_number(&start.rest()[..10]))
+}
+
+pub fn run(lines: Lines) -> Result {
+    let passes = Block::new(lines)
+        .parse_lines(parse_pass)?
+        .into_iter()
         .collect::<BTreeSet<_>>();
 
-    let &lowest = passes.iter().min().unwrap();
-    let &highest = passes.iter().max().unwrap();
+    let (&lowest, &highest) = passes
+        .iter()
+        .minmax()
+        .into_option()
+        .context("no boarding passes")?;
     println!("part A: {}", highest);
 
     let missing = (lowest..=highest)
         .filter(|i| !passes.contains(&i))
         .next()
-        .unwrap();
+        .context("no free seat")?;
 
     println!("part B: {}", missing);
 
@@ -1,14 +1,12 @@
 use crate::common::*;
+use advent_common::parse::Sections;
 use std::collections::HashSet;
 
-fn parse_input(lines: &[String]) -> Vec<Vec<HashSet<char>>> {
-    lines
+fn parse_input(lines: Lines) -> Vec<Vec<HashSet<char>>> {
+    Sections::new(lines)
         .iter()
-        .map(|line| line.chars().collect::<HashSet<_>>())
-        .group_by(|line| line.is_empty())
-        .into_iter()
-        .filter(|(empty, _)| !empty)
-        .map(|(_, lines)| lines.collect())
+        .filter(|group| !group.is_empty())
+        .map(|group| map(group.lines, |line| line.chars().collect()).collect())
         .collect()
 }
 
@@ -24,8 +22,8 @@ fn intersection(answers: &[HashSet<char>]) -> HashSet<char> {
     })
 }
 
-pub fn run() -> Result {
-    let groups = parse_input(&read_input("day06")?);
+pub fn run(lines: Lines) -> Result {
+    let groups = parse_input(lines);
 
     let sum: usize = groups.iter().map(|answers| union(answers).len()).sum();
 
@@ -49,10 +47,7 @@ mod test {
     fn test_example() {
         let input = [
             "abc", "", "a", "b", "c", "", "ab", "ac", "", "a", "a", "a", "a", "", "b",
-        ]
-        .iter()
-        .map(|v| v.to_string())
-        .collect::<Vec<_>>();
+        ];
 
         let groups = parse_input(&input);
 
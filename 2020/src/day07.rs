@@ -1,41 +1,64 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use petgraph::prelude::*;
 use std::collections::{HashMap, HashSet};
 
-fn parse_edge(
-    line: &str,
-    graph: &mut DiGraph<String, usize>,
-    nodes: &mut HashMap<String, NodeIndex>,
-) -> Result {
-    let mut parts = line.split(" bags contain ");
-    let color = parts.next().unwrap_or_default().to_string();
-    let rest = parts.next().unwrap_or_default();
-
-    if rest.is_empty() || rest == "no other bags." {
-        return Ok(());
-    }
+type Rule<'a> = (&'a str, Vec<(usize, &'a str)>);
+
+fn parse_color<'a>(c: &mut Cursor<'a>) -> ParseResult<&'a str> {
+    let rest = c.rest();
+    c.word()?;
+    c.tag(" ")?;
+    c.word()?;
+
+    Ok(&rest[..rest.len() - c.rest().len()])
+}
+
+fn parse_content<'a>(c: &mut Cursor<'a>) -> ParseResult<(usize, &'a str)> {
+    let amount = c.int()?;
+    c.tag(" ")?;
+    let color = parse_color(c)?;
+    c.tag(" bag")?;
+    c.eat("s");
 
-    let src = *nodes
-        .entry(color.clone())
-        .or_insert_with(|| graph.add_node(color));
+    Ok((amount, color))
+}
 
-    for p in find_all("([0-9]+) ([a-z ]+) bag[s]?[.,]?", rest) {
-        let amount = p[1]
-            .parse::<usize>()
-            .with_context(|| format!("while parsing line {:?}", &p[0]))?;
+fn parse_rule<'a>(c: &mut Cursor<'a>) -> ParseResult<Rule<'a>> {
+    let color = parse_color(c)?;
+    c.tag(" bags contain ")?;
 
-        let color = p[2].to_string();
-        let dst = *nodes
-            .entry(color.clone())
-            .or_insert_with(|| graph.add_node(color));
+    let contents = if c.eat("no other bags") {
+        vec![]
+    } else {
+        c.list(", ", parse_content)?
+    };
 
-        graph.add_edge(src, dst, amount);
+    c.tag(".")?;
+
+    Ok((color, contents))
+}
+
+fn build_graph<'a>(rules: &[Rule<'a>]) -> (DiGraph<&'a str, usize>, HashMap<&'a str, NodeIndex>) {
+    let mut graph = DiGraph::new();
+    let mut nodes = HashMap::new();
+
+    for (color, contents) in rules {
+        let src = *nodes
+            .entry(*color)
+            .or_insert_with(|| graph.add_node(*color));
+
+        for &(amount, color) in contents {
+            let dst = *nodes.entry(color).or_insert_with(|| graph.add_node(color));
+
+            graph.add_edge(src, dst, amount);
+        }
     }
 
-    Ok(())
+    (graph, nodes)
 }
 
-fn bfs(source: NodeIndex, graph: &DiGraph<String, usize>, visited: &mut HashSet<NodeIndex>) {
+fn bfs(source: NodeIndex, graph: &DiGraph<&str, usize>, visited: &mut HashSet<NodeIndex>) {
     visited.insert(source);
 
     for neighbor in graph.neighbors_directed(source, Direction::Incoming) {
@@ -43,7 +66,7 @@ fn bfs(source: NodeIndex, graph: &DiGraph<String, usize>, visited: &mut HashSet<
     }
 }
 
-fn count(source: NodeIndex, graph: &DiGraph<String, usize>) -> usize {
+fn count(source: NodeIndex, graph: &DiGraph<&str, usize>) -> usize {
     let mut total = 1;
 
     for edge in graph.edges_directed(source, Direction::Outgoing) {
@@ -53,19 +76,18 @@ fn count(source: NodeIndex, graph: &DiGraph<String, usize>) -> usize {
     total
 }
 
-pub fn run() -> Result {
-    let mut graph = DiGraph::new();
-    let mut nodes = HashMap::new();
-
-    for line in read_input("day07")? {
-        parse_edge(&line, &mut graph, &mut nodes)?;
-    }
+pub fn run(lines: Lines) -> Result {
+    let rules = Block::new(lines).parse_lines(parse_rule)?;
+    let (graph, nodes) = build_graph(&rules);
+    let source = *nodes
+        .get("shiny gold")
+        .context("no rule for shiny gold bags")?;
 
     let mut visited = HashSet::new();
-    bfs(nodes["shiny gold"], &graph, &mut visited);
+    bfs(source, &graph, &mut visited);
     println!("part A: {}", visited.len() - 1);
 
-    let count = count(nodes["shiny gold"], &graph);
+    let count = count(source, &graph);
     println!("part B: {}", count - 1);
 
     Ok(())
@@ -77,8 +99,6 @@ mod test {
 
     #[test]
     fn test_count() {
-        let mut graph = DiGraph::new();
-        let mut nodes = HashMap::new();
         let lines = vec![
             "shiny gold bags contain 2 dark red bags.",
             "dark red bags contain 2 dark orange bags.",
@@ -89,9 +109,8 @@ mod test {
             "dark violet bags contain no other bags.",
         ];
 
-        for line in lines {
-            parse_edge(line, &mut graph, &mut nodes).unwrap();
-        }
+        let rules = Block::new(&lines).parse_lines(parse_rule).unwrap();
+        let (graph, nodes) = build_graph(&rules);
 
         let c = count(nodes["shiny gold"], &graph);
 
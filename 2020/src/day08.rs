@@ -4,7 +4,7 @@ use std::collections::HashSet;
 
 fn run_until_done(program: &Program) -> Result<Process> {
     let mut visited = HashSet::new();
-    let mut p = program.run();
+    let mut p = program.run(&[]);
 
     while !p.done() && visited.insert(p.pc()) {
         p.advance()?;
@@ -36,12 +36,12 @@ fn fix_program(program: &Program) -> Result<Program> {
     bail!("failed to fix program");
 }
 
-pub fn run() -> Result {
-    let p = Program::parse_input("day08")?;
-    println!("part A: {}", run_until_done(&p)?.acc());
+pub fn run(lines: Lines) -> Result {
+    let p = Program::parse_lines(lines)?;
+    println!("part A: {}", run_until_done(&p)?.reg(Reg::A));
 
     let fixed_p = fix_program(&p)?;
-    println!("part B: {}", run_until_done(&fixed_p)?.acc());
+    println!("part B: {}", run_until_done(&fixed_p)?.reg(Reg::A));
 
     Ok(())
 }
@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor};
 use std::collections::HashSet;
 
 fn find_xmass_weakness(numbers: &[usize]) -> Result<usize> {
@@ -34,11 +35,8 @@ fn find_range(numbers: &[usize], needle: usize) -> Result<(usize, usize)> {
     Err(anyhow!("range not found"))
 }
 
-pub fn run() -> Result {
-    let numbers = read_input("day09")?
-        .into_iter()
-        .map(|s| s.parse::<usize>())
-        .collect::<Result<Vec<_>, _>>()?;
+pub fn run(lines: Lines) -> Result {
+    let numbers: Vec<usize> = Block::new(lines).parse_lines(Cursor::int)?;
 
     let index = find_xmass_weakness(&numbers)?;
     let p = numbers[index];
@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::memo::memoize;
 use defaultmap::DefaultHashMap;
 
 fn find_differences(adapters: &[i32]) -> (usize, usize) {
@@ -16,20 +17,26 @@ fn find_differences(adapters: &[i32]) -> (usize, usize) {
 }
 
 fn find_combinations(adapters: &[i32]) -> u128 {
-    let mut result = 0;
-    let mut count = DefaultHashMap::new(0);
-    count[0] = 1;
-
-    for &v in adapters {
-        count[v] = count[v - 1] + count[v - 2] + count[v - 3];
-        result = count[v];
-    }
-
-    result
+    let available = adapters.iter().copied().collect::<HashSet<_>>();
+    let last = match adapters.last() {
+        Some(&last) => last,
+        None => return 0,
+    };
+
+    // Number of ways to chain adapters from the outlet up to `joltage`.
+    memoize(last, |recur, &joltage| {
+        if joltage == 0 {
+            1
+        } else if joltage < 0 || !available.contains(&joltage) {
+            0
+        } else {
+            recur(joltage - 1) + recur(joltage - 2) + recur(joltage - 3)
+        }
+    })
 }
 
-pub fn run() -> Result {
-    let mut adapters = read_input("day10")?
+pub fn run(lines: Lines) -> Result {
+    let mut adapters = lines
         .iter()
         .map(|line| line.parse::<i32>())
         .collect::<Result<Vec<_>, _>>()?;
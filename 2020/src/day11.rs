@@ -1,134 +1,66 @@
 use crate::common::*;
-use itertools::max;
-use ndarray::prelude::*;
+use advent_common::automaton::{DenseAutomaton, Neighborhood};
+use advent_common::grid::{Grid, NEIGHBORS8};
 
-const DIRECTIONS: [[isize; 2]; 8] = [
-    [-1, -1],
-    [-1, 0],
-    [-1, 1],
-    [0, 1],
-    [0, -1],
-    [1, -1],
-    [1, 0],
-    [1, 1],
-];
-
-fn parse_input(lines: &[String]) -> Array2<char> {
-    let rows = lines.len();
-    let cols = max(map(lines, |l| l.len())).unwrap_or(0);
-    let mut grid = Array2::default((rows, cols));
-
-    for (i, line) in enumerate(lines) {
-        for (j, c) in enumerate(line.chars()) {
-            grid[[i, j]] = c;
-        }
-    }
-
-    grid
+fn parse_input(lines: Lines) -> Result<Grid<char>> {
+    Ok(Grid::parse(lines)?)
 }
 
-fn neighbors(dims: [usize; 2], ij: [usize; 2]) -> impl Iterator<Item = [usize; 2]> {
-    DIRECTIONS.iter().filter_map(move |&[di, dj]| {
-        let [i, j] = ij;
-        let [rows, cols] = dims;
-        let [ni, nj] = [i as isize + di, j as isize + dj];
-
-        if ni >= 0 && ni < rows as isize && nj >= 0 && nj < cols as isize {
-            return Some([ni as usize, nj as usize]);
-        }
+fn apply_round(seats: &Neighborhood<char>) -> char {
+    let occupied = seats.neighbors8().filter(|&&c| c == '#').count();
 
-        None
-    })
+    match *seats.center() {
+        'L' if occupied == 0 => '#',
+        '#' if occupied >= 4 => 'L',
+        c => c,
+    }
 }
 
-fn apply_round(old: &Array2<char>) -> Array2<char> {
-    let (rows, cols) = old.dim();
-    let mut new = Array2::default((rows, cols));
-
-    for i in 0..rows {
-        for j in 0..cols {
-            let occupied = neighbors([rows, cols], [i, j])
-                .filter(|&a| old[a] == '#')
-                .count();
+/// Number of occupied seats that are visible in the eight directions.
+fn visible_occupied(seats: &Neighborhood<char>) -> usize {
+    let mut count = 0;
 
-            new[[i, j]] = match old[[i, j]] {
-                'L' if occupied == 0 => '#',
-                '#' if occupied >= 4 => 'L',
-                c => c,
-            }
-        }
-    }
-
-    new
-}
+    for &[dx, dy] in &NEIGHBORS8 {
+        let (dx, dy) = (dx as i64, dy as i64);
 
-fn far_neighbors(array: &Array2<char>, ij: [usize; 2]) -> impl Iterator<Item = [usize; 2]> + '_ {
-    DIRECTIONS.iter().filter_map(move |&[di, dj]| {
-        let [i, j] = ij;
-        let (rows, cols) = array.dim();
         for delta in 1.. {
-            let [ni, nj] = [i as isize + di * delta, j as isize + dj * delta];
-
-            if ni < 0 || ni >= rows as isize || nj < 0 || nj >= cols as isize {
-                break;
+            match seats.get([dx * delta, dy * delta]) {
+                Some('.') => continue,
+                Some('#') => count += 1,
+                _ => {}
             }
 
-            if array[[ni as usize, nj as usize]] != '.' {
-                return Some([ni as usize, nj as usize]);
-            }
+            break;
         }
+    }
 
-        None
-    })
+    count
 }
 
-fn apply_far_round(old: &Array2<char>) -> Array2<char> {
-    let (rows, cols) = old.dim();
-    let mut new = Array2::default((rows, cols));
-
-    for i in 0..rows {
-        for j in 0..cols {
-            let occupied = far_neighbors(old, [i, j])
-                .filter(|&a| old[a] == '#')
-                .count();
+fn apply_far_round(seats: &Neighborhood<char>) -> char {
+    let occupied = visible_occupied(seats);
 
-            new[[i, j]] = match old[[i, j]] {
-                'L' if occupied == 0 => '#',
-                '#' if occupied >= 5 => 'L',
-                c => c,
-            }
-        }
+    match *seats.center() {
+        'L' if occupied == 0 => '#',
+        '#' if occupied >= 5 => 'L',
+        c => c,
     }
-
-    new
 }
 
-fn count_occupied(grid: &Array2<char>) -> usize {
-    map(grid, |&c| (c == '#') as usize).sum()
-}
-
-fn repeat_until_convergence<T: Eq, F: Fn(&T) -> T>(val: &T, fun: F) -> T {
-    let mut new_val = (fun)(&val);
-
-    loop {
-        let old_val = new_val;
-        new_val = (fun)(&old_val);
-
-        if old_val == new_val {
-            break old_val;
-        }
-    }
+fn count_occupied(seats: &DenseAutomaton<char>) -> usize {
+    seats.count(|&c| c == '#')
 }
 
-pub fn run() -> Result {
-    let grid = parse_input(&read_input("day11")?);
+pub fn run(lines: Lines) -> Result {
+    let grid = parse_input(lines)?;
+    let seats = DenseAutomaton::bounded(grid);
 
-    let new_grid = repeat_until_convergence(&grid, apply_round);
-    let count = count_occupied(&new_grid);
+    let (new_seats, _) = seats.stabilize(apply_round);
+    let count = count_occupied(&new_seats);
     println!("part A: {}", count);
 
-    let new_grid = repeat_until_convergence(&grid, apply_far_round);
-    let count = count_occupied(&new_grid);
+    let (new_seats, _) = seats.stabilize(apply_far_round);
+    let count = count_occupied(&new_seats);
     println!("part B: {}", count);
 
     Ok(())
@@ -1,30 +1,46 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
-use recap::Recap;
-use serde::Deserialize;
-
-#[derive(Recap, Deserialize)]
-#[recap(regex = "(?P<c>.)(?P<v>[0-9]+)")]
 struct Instr {
     c: char,
     v: i32,
 }
 
-fn parse_input(lines: &[String]) -> Result<Vec<Instr>> {
-    lines.iter().map(|line| Ok(line.parse()?)).collect()
+fn parse_instr(c: &mut Cursor) -> ParseResult<Instr> {
+    let action = c
+        .peek()
+        .filter(|a| "NSEWLRF".contains(*a))
+        .ok_or_else(|| c.error("expected an action"))?;
+    c.next_char()?;
+
+    let start = *c;
+    let value = c.int()?;
+
+    if "LR".contains(action) && value % 90 != 0 {
+        return Err(start.error("expected a multiple of 90 degrees"));
+    }
+
+    Ok(Instr {
+        c: action,
+        v: value,
+    })
+}
+
+fn parse_input(lines: Lines) -> Result<Vec<Instr>> {
+    Ok(Block::new(lines).parse_lines(parse_instr)?)
 }
 
-fn rotate((x, y): (i32, i32), angle: i32) -> (i32, i32) {
-    match i32::rem_euclid(angle, 360) {
+fn rotate((x, y): (i32, i32), angle: i32) -> Result<(i32, i32)> {
+    Ok(match i32::rem_euclid(angle, 360) {
         0 => (x, y),
         90 => (y, -x),
         180 => (-x, -y),
         270 => (-y, x),
-        _ => panic!("invalid angle"),
-    }
+        _ => bail!("invalid angle: {}", angle),
+    })
 }
 
-fn execute(instr: &[Instr]) -> (i32, i32) {
+fn execute(instr: &[Instr]) -> Result<(i32, i32)> {
     let (mut x, mut y) = (0, 0);
     let mut dir = (1, 0);
 
@@ -34,8 +50,8 @@ fn execute(instr: &[Instr]) -> (i32, i32) {
             'S' => y -= v,
             'E' => x += v,
             'W' => x -= v,
-            'R' => dir = rotate(dir, v),
-            'L' => dir = rotate(dir, -v),
+            'R' => dir = rotate(dir, v)?,
+            'L' => dir = rotate(dir, -v)?,
             'F' => {
                 x += dir.0 * v;
                 y += dir.1 * v;
@@ -44,10 +60,10 @@ fn execute(instr: &[Instr]) -> (i32, i32) {
         }
     }
 
-    (x, y)
+    Ok((x, y))
 }
 
-fn execute_real(instr: &[Instr]) -> (i32, i32) {
+fn execute_real(instr: &[Instr]) -> Result<(i32, i32)> {
     let (mut x, mut y) = (0, 0);
     let mut wp = (10, 1);
 
@@ -57,8 +73,8 @@ fn execute_real(instr: &[Instr]) -> (i32, i32) {
             'W' => wp.0 -= v,
             'N' => wp.1 += v,
             'S' => wp.1 -= v,
-            'R' => wp = rotate(wp, v),
-            'L' => wp = rotate(wp, -v),
+            'R' => wp = rotate(wp, v)?,
+            'L' => wp = rotate(wp, -v)?,
             'F' => {
                 x += wp.0 * v;
                 y += wp.1 * v;
@@ -67,17 +83,16 @@ fn execute_real(instr: &[Instr]) -> (i32, i32) {
         }
     }
 
-    (x, y)
+    Ok((x, y))
 }
 
-pub fn run() -> Result {
-    let input = read_input("day12")?;
-    let instr = parse_input(&input)?;
+pub fn run(lines: Lines) -> Result {
+    let instr = parse_input(lines)?;
 
-    let (x, y) = execute(&instr);
+    let (x, y) = execute(&instr)?;
     println!("part A: ({}, {}) -> {}", x, y, x.abs() + y.abs());
 
-    let (x, y) = execute_real(&instr);
+    let (x, y) = execute_real(&instr)?;
     println!("part B: ({}, {}) -> {}", x, y, x.abs() + y.abs());
 
     Ok(())
@@ -89,17 +104,13 @@ mod test {
 
     #[test]
     fn test() {
-        let input = ["F10", "N3", "F7", "R90", "F11"]
-            .iter()
-            .copied()
-            .map(str::to_string)
-            .collect::<Vec<_>>();
+        let input = ["F10", "N3", "F7", "R90", "F11"];
         let instr = parse_input(&input).unwrap();
 
-        let (x, y) = execute(&instr);
+        let (x, y) = execute(&instr).unwrap();
         assert_eq!((x, y), (17, -8));
 
-        let (x, y) = execute_real(&instr);
+        let (x, y) = execute_real(&instr).unwrap();
         assert_eq!((x, y), (214, -72));
     }
 }
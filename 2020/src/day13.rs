@@ -1,44 +1,56 @@
 use crate::common::*;
+use advent_common::math::crt;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
-fn parse_input(lines: &[String]) -> Result<(i32, Vec<(i32, i32)>)> {
-    let timestamp = lines[0].parse()?;
-    let busses = lines[1]
-        .split(',')
+fn parse_bus(c: &mut Cursor) -> ParseResult<Option<i32>> {
+    if c.eat("x") {
+        return Ok(None);
+    }
+
+    let start = *c;
+    match c.int()? {
+        id if id > 0 => Ok(Some(id)),
+        _ => Err(start.error("bus id must be positive")),
+    }
+}
+
+fn parse_input(lines: Lines) -> Result<(i32, Vec<(i32, i32)>)> {
+    let block = Block::new(lines);
+    ensure!(
+        block.len() == 2,
+        "expected a timestamp and a list of busses"
+    );
+
+    let timestamp = block.parse_line(0, Cursor::int)?;
+    let busses = block
+        .parse_line(1, |c| c.list(",", parse_bus))?
+        .into_iter()
         .enumerate()
-        .filter_map(|(i, x)| Some((i as i32, x.parse().ok()?)))
+        .filter_map(|(i, x)| Some((i as i32, x?)))
         .collect();
     Ok((timestamp, busses))
 }
 
-fn find_earliest_bus(timestamp: i32, busses: &[(i32, i32)]) -> (i32, i32) {
+fn find_earliest_bus(timestamp: i32, busses: &[(i32, i32)]) -> Option<(i32, i32)> {
     busses
         .iter()
         .map(|&(_, b)| (b, (-timestamp).rem_euclid(b)))
         .min_by_key(|(_, t)| *t)
-        .unwrap()
 }
 
-fn find_earliest_time(busses: &[(i32, i32)]) -> i128 {
-    let mut result = 0 as i128;
-    let mut factor = 1 as i128;
-
-    for &(index, bus_id) in busses {
-        let (bus_id, index) = (bus_id as i128, index as i128);
-        let remaining = (-index).rem_euclid(bus_id);
-
-        while result % bus_id != remaining {
-            result += factor;
-        }
-
-        factor *= bus_id;
-    }
+fn find_earliest_time(busses: &[(i32, i32)]) -> Option<i64> {
+    // bus departs at t + index, so t == -index (mod bus_id)
+    let congruences = busses
+        .iter()
+        .map(|&(index, bus_id)| (-index as i64, bus_id as i64))
+        .collect_vec();
 
-    result
+    crt(&congruences).map(|(t, _)| t)
 }
 
-pub fn run() -> Result {
-    let (timestamp, busses) = parse_input(&read_input("day13")?)?;
-    let (bus_id, remaining) = find_earliest_bus(timestamp, &busses);
+pub fn run(lines: Lines) -> Result {
+    let (timestamp, busses) = parse_input(lines)?;
+    let (bus_id, remaining) = find_earliest_bus(timestamp, &busses).context("no busses")?;
 
     println!(
         "part A: {} * {} = {}",
@@ -47,7 +59,7 @@ pub fn run() -> Result {
         bus_id * remaining
     );
 
-    let t = find_earliest_time(&busses);
+    let t = find_earliest_time(&busses).context("no solution found")?;
     println!("part B: {}", t);
 
     Ok(())
@@ -60,18 +72,18 @@ mod test {
     #[test]
     fn test() {
         let input = vec![(0, 17), (2, 13), (3, 19)];
-        assert_eq!(find_earliest_time(&input), 3417);
+        assert_eq!(find_earliest_time(&input).unwrap(), 3417);
 
         let input = vec![(0, 67), (1, 7), (2, 59), (3, 61)];
-        assert_eq!(find_earliest_time(&input), 754018);
+        assert_eq!(find_earliest_time(&input).unwrap(), 754018);
 
         let input = vec![(0, 67), (2, 7), (3, 59), (4, 61)];
-        assert_eq!(find_earliest_time(&input), 779210);
+        assert_eq!(find_earliest_time(&input).unwrap(), 779210);
 
         let input = vec![(0, 67), (1, 7), (3, 59), (4, 61)];
-        assert_eq!(find_earliest_time(&input), 1261476);
+        assert_eq!(find_earliest_time(&input).unwrap(), 1261476);
 
         let input = vec![(0, 1789), (1, 37), (2, 47), (3, 1889)];
-        assert_eq!(find_earliest_time(&input), 1202161486);
+        assert_eq!(find_earliest_time(&input).unwrap(), 1202161486);
     }
 }
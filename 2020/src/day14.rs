@@ -1,6 +1,5 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use std::collections::HashMap;
 
 use Instr::*;
@@ -10,38 +9,37 @@ enum Instr {
     Assign(u64, u64),
 }
 
-fn parse_input(lines: &[String]) -> Result<Vec<Instr>> {
-    #[derive(Recap, Deserialize)]
-    #[recap(regex = "mask = (?P<mask>[01X]+)")]
-    struct Mask {
-        mask: String,
-    };
-
-    #[derive(Recap, Deserialize)]
-    #[recap(regex = "mem\\[(?P<addr>[0-9]+)\\] = (?P<value>[0-9]+)")]
-    struct Assign {
-        addr: u64,
-        value: u64,
-    };
-
-    let mut output = vec![];
-    for line in lines {
-        if let Ok(Mask { mask }) = line.parse() {
-            // X,0 -> 0, 1 -> 1
-            let value = u64::from_str_radix(&mask.replace('X', "0"), 2).unwrap();
+fn parse_instr(c: &mut Cursor) -> ParseResult<Instr> {
+    if c.eat("mask = ") {
+        let start = *c;
+        let bits = c.take_while(|b| "01X".contains(b));
+
+        if bits.len() != 36 {
+            return Err(start.error("expected a mask of 36 bits"));
+        }
 
+        let (mut mask, mut value) = (0, 0);
+        for bit in bits.chars() {
             // 0,1 -> 0, X -> 1
-            let mask = u64::from_str_radix(&mask.replace('1', "0").replace('X', "1"), 2).unwrap();
+            mask = (mask << 1) | (bit == 'X') as u64;
 
-            output.push(Mask(mask, value));
-        } else if let Ok(Assign { addr, value }) = line.parse() {
-            output.push(Assign(addr, value));
-        } else {
-            bail!("failed to parse line: {}", line);
+            // X,0 -> 0, 1 -> 1
+            value = (value << 1) | (bit == '1') as u64;
         }
+
+        Ok(Mask(mask, value))
+    } else {
+        c.tag("mem[")?;
+        let addr = c.int()?;
+        c.tag("] = ")?;
+        let value = c.int()?;
+
+        Ok(Assign(addr, value))
     }
+}
 
-    Ok(output)
+fn parse_input(lines: Lines) -> Result<Vec<Instr>> {
+    Ok(Block::new(lines).parse_lines(parse_instr)?)
 }
 
 fn execute_v1(instrs: &[Instr]) -> HashMap<u64, u64> {
@@ -100,8 +98,8 @@ fn execute_v2(instrs: &[Instr]) -> HashMap<u64, u64> {
     mem
 }
 
-pub fn run() -> Result {
-    let instrs = parse_input(&read_input("day14")?)?;
+pub fn run(lines: Lines) -> Result {
+    let instrs = parse_input(lines)?;
 
     let mem = execute_v1(&instrs);
     let total = sum(mem.values().copied());
@@ -120,11 +118,11 @@ mod test {
 
     #[test]
     fn test() {
-        let program = vec![
-            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X".to_string(),
-            "mem[8] = 11".to_string(),
-            "mem[7] = 101".to_string(),
-            "mem[8] = 0".to_string(),
+        let program = [
+            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X",
+            "mem[8] = 11",
+            "mem[7] = 101",
+            "mem[8] = 0",
         ];
 
         let program = parse_input(&program).unwrap();
@@ -1,16 +1,13 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor};
 use std::mem::replace;
 
-fn parse_input(line: &str) -> Result<Vec<usize>> {
-    line.split(',').map(|s| Ok(s.parse()?)).collect()
-}
-
-fn play_for_n_rounds(n: usize, nums: &[usize]) -> usize {
+fn play_for_n_rounds(n: usize, nums: &[usize]) -> Option<usize> {
     if n < nums.len() {
-        return nums[n];
+        return Some(nums[n]);
     }
 
-    let m = max(nums).unwrap();
+    let m = max(nums)?;
     let mut spoken = vec![!0; usize::max(n, m + 1)];
     let k = nums.len() - 1;
 
@@ -18,18 +15,20 @@ fn play_for_n_rounds(n: usize, nums: &[usize]) -> usize {
         spoken[v] = turn;
     }
 
-    (k..n).fold(nums[k], |v, turn| {
+    let last = (k..n).fold(nums[k], |v, turn| {
         usize::saturating_sub(turn, replace(&mut spoken[v], turn))
-    })
+    });
+
+    Some(last)
 }
 
-pub fn run() -> Result {
-    let nums = parse_input(&read_input("day15")?[0])?;
+pub fn run(lines: Lines) -> Result {
+    let nums = Block::new(lines).parse_single(|c| c.list(",", Cursor::int))?;
 
-    let result = play_for_n_rounds(2020 - 1, &nums);
+    let result = play_for_n_rounds(2020 - 1, &nums).context("no starting numbers")?;
     println!("part A: {}", result);
 
-    let result = play_for_n_rounds(30_000_000 - 1, &nums);
+    let result = play_for_n_rounds(30_000_000 - 1, &nums).context("no starting numbers")?;
     println!("part B: {}", result);
 
     Ok(())
@@ -45,7 +44,7 @@ mod test {
         let expected_output = [0, 3, 6, 0, 3, 3, 1, 0, 4, 0];
 
         let gotten_output = (0..10)
-            .map(|i| play_for_n_rounds(i, &input))
+            .map(|i| play_for_n_rounds(i, &input).unwrap())
             .collect::<Vec<_>>();
 
         assert_eq!(expected_output, &*gotten_output);
@@ -1,11 +1,7 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
+use advent_common::parse::{Cursor, ParseResult, Sections};
 
-#[derive(Recap, Deserialize, Debug, Clone)]
-#[recap(
-    regex = "^(?P<key>[a-zA-Z ]+): (?P<a_lo>[0-9]+)-(?P<a_hi>[0-9]+) or (?P<b_lo>[0-9]+)-(?P<b_hi>[0-9]+)$"
-)]
+#[derive(Debug, Clone)]
 struct Rule {
     key: String,
     a_lo: i32,
@@ -22,37 +18,46 @@ impl Rule {
     }
 }
 
-fn parse_list(line: &str) -> Result<Vec<i32>> {
-    line.split(',').map(|s| Ok(s.parse::<i32>()?)).collect()
+fn parse_rule(c: &mut Cursor) -> ParseResult<Rule> {
+    let key = c.take_while(|k| k != ':').to_string();
+    c.tag(": ")?;
+    let a_lo = c.int()?;
+    c.tag("-")?;
+    let a_hi = c.int()?;
+    c.tag(" or ")?;
+    let b_lo = c.int()?;
+    c.tag("-")?;
+    let b_hi = c.int()?;
+
+    Ok(Rule {
+        key,
+        a_lo,
+        a_hi,
+        b_lo,
+        b_hi,
+    })
 }
 
-fn expect(line: Option<&str>, expected: &str) -> Result {
-    match line {
-        Some(l) if l == expected => Ok(()),
-        _ => bail!("unexpected line, expecting {:?}", expected),
-    }
+fn parse_ticket(c: &mut Cursor) -> ParseResult<Ticket> {
+    c.list(",", Cursor::int)
 }
 
-fn parse_input(lines: &[String]) -> Result<(Vec<Rule>, Ticket, Vec<Ticket>)> {
-    let mut iter = lines.into_iter().map(|s| &**s);
-
-    let mut rules = vec![];
-    while let Some(line) = iter.next() {
-        if line.is_empty() {
-            break;
-        }
-
-        rules.push(line.parse()?);
-    }
-
-    expect(iter.next(), "your ticket:")?;
-    let my_ticket = parse_list(iter.next().unwrap_or_default())?;
-
-    expect(iter.next(), "")?;
-    expect(iter.next(), "nearby tickets:")?;
-    let mut tickets = vec![];
-    while let Some(line) = iter.next() {
-        tickets.push(parse_list(line)?);
+fn parse_input(lines: Lines) -> Result<(Vec<Rule>, Ticket, Vec<Ticket>)> {
+    let [rules, mine, nearby] = Sections::new(lines).blocks()?;
+
+    let rules = rules.parse_lines(parse_rule)?;
+    let my_ticket = mine.header("your ticket:")?.parse_single(parse_ticket)?;
+    let tickets = nearby
+        .header("nearby tickets:")?
+        .parse_lines(parse_ticket)?;
+
+    for ticket in Some(&my_ticket).into_iter().chain(&tickets) {
+        ensure!(
+            ticket.len() == rules.len(),
+            "ticket {:?} does not have {} fields",
+            ticket,
+            rules.len()
+        );
     }
 
     Ok((rules, my_ticket, tickets))
@@ -77,8 +82,8 @@ fn delete_invalid_tickets(rules: &[Rule], tickets: &mut Vec<Ticket>) -> i32 {
     error
 }
 
-fn reorder_fields(rules: &[Rule], tickets: &[Ticket]) -> Vec<Rule> {
-    let n = tickets[0].len();
+fn reorder_fields(rules: &[Rule], tickets: &[Ticket]) -> Result<Vec<Rule>> {
+    let n = rules.len();
     let mut new_rules = rules.to_vec();
     let mut options: Vec<Vec<usize>> = vec![];
     options.resize(n, vec![]);
@@ -92,14 +97,12 @@ fn reorder_fields(rules: &[Rule], tickets: &[Ticket]) -> Vec<Rule> {
     }
 
     for _ in 0..n {
-        let (mut j, mut i) = (!0, !0);
-        for (index, p) in enumerate(&mut options) {
-            if p.len() == 1 {
-                i = index;
-                j = p.pop().unwrap();
-                break;
-            }
-        }
+        let (i, j) = enumerate(&options)
+            .find_map(|(i, p)| match p[..] {
+                [j] => Some((i, j)),
+                _ => None,
+            })
+            .context("fields cannot be assigned to rules uniquely")?;
 
         new_rules[i] = rules[j].clone();
 
@@ -108,16 +111,16 @@ fn reorder_fields(rules: &[Rule], tickets: &[Ticket]) -> Vec<Rule> {
         }
     }
 
-    new_rules
+    Ok(new_rules)
 }
 
-pub fn run() -> Result {
-    let (mut rules, my_ticket, mut tickets) = parse_input(&read_input("day16")?)?;
+pub fn run(lines: Lines) -> Result {
+    let (mut rules, my_ticket, mut tickets) = parse_input(lines)?;
 
     let error_rate = delete_invalid_tickets(&rules, &mut tickets);
     println!("part A: {}", error_rate);
 
-    let rules = reorder_fields(&mut rules, &tickets);
+    let rules = reorder_fields(&mut rules, &tickets)?;
     let sum: usize = enumerate(rules)
         .filter(|(_, rule)| rule.key.starts_with("departure"))
         .map(|(i, _)| my_ticket[i] as usize)
@@ -1,15 +1,13 @@
 use crate::common::*;
-use itertools::iproduct;
+use advent_common::automaton::{Moore, Space, SparseAutomaton};
 
-pub type Pos = [i32; 4];
-
-fn parse_input(lines: &[String]) -> HashSet<Pos> {
-    let mut result = HashSet::default();
+fn parse_input(lines: Lines) -> Vec<[i64; 2]> {
+    let mut result = vec![];
 
     for (y, line) in enumerate(lines) {
         for (x, c) in enumerate(line.chars()) {
             if c == '#' {
-                result.insert([x as i32, y as i32, 0, 0]);
+                result.push([x as i64, y as i64]);
             }
         }
     }
@@ -17,43 +15,24 @@ fn parse_input(lines: &[String]) -> HashSet<Pos> {
     result
 }
 
-fn simulate(pos: &HashSet<Pos>, dims: usize) -> HashSet<Pos> {
-    let mut count = HashMap::<Pos, usize>::default();
-    let neighbors: Vec<_> = iproduct!(-1..=1, -1..=1, -1..=1, -1..=1)
-        .filter(|&d| d != (0, 0, 0, 0))
-        .filter(|&d| dims > 3 || d.3 == 0)
-        .collect();
-
-    for [x, y, z, w] in pos {
-        for &(dx, dy, dz, dw) in &neighbors {
-            let p = [x + dx, y + dy, z + dz, w + dw];
-            *count.entry(p).or_default() += 1;
-        }
+fn simulate<S: Space + Clone>(mut cubes: SparseAutomaton<S>, steps: usize) -> usize {
+    for _ in 0..steps {
+        cubes = cubes.step(|active, n| n == 3 || (active && n == 2));
     }
 
-    count
-        .into_iter()
-        .filter(|&(p, c)| c == 3 || (c == 2 && pos.contains(&p)))
-        .map(|(p, _)| p)
-        .collect()
+    cubes.len()
 }
 
-pub fn run() -> Result {
-    let pos = parse_input(&read_input("day17")?);
-
-    let mut new_pos = pos.clone();
-    for _ in 0..6 {
-        new_pos = simulate(&new_pos, 3);
-    }
-
-    println!("part A: {}", new_pos.len());
+pub fn run(lines: Lines) -> Result {
+    let cubes = parse_input(lines);
 
-    let mut new_pos = pos;
-    for _ in 0..6 {
-        new_pos = simulate(&new_pos, 4);
-    }
+    let space = Moore::<3>::new();
+    let initial = SparseAutomaton::new(space, cubes.iter().map(|&[x, y]| [x, y, 0]));
+    println!("part A: {}", simulate(initial, 6));
 
-    println!("part B: {}", new_pos.len());
+    let space = Moore::<4>::new();
+    let initial = SparseAutomaton::new(space, cubes.iter().map(|&[x, y]| [x, y, 0, 0]));
+    println!("part B: {}", simulate(initial, 6));
 
     Ok(())
 }
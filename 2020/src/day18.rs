@@ -69,17 +69,15 @@ fn evaluate_precedence(line: &str) -> Result<i64> {
     evaluate_generic(line, true)
 }
 
-pub fn run() -> Result {
-    let lines = read_input("day18")?;
-
+pub fn run(lines: Lines) -> Result {
     let mut sum = 0;
-    for line in &lines {
+    for line in lines {
         sum += evaluate(line)?;
     }
     println!("part A: {}", sum);
 
     let mut sum = 0;
-    for line in &lines {
+    for line in lines {
         sum += evaluate_precedence(line)?;
     }
     println!("part B: {}", sum);
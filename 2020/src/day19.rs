@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{parse_line, Cursor, ParseResult, Sections};
 use ndarray::prelude::*;
 
 enum Rule {
@@ -6,47 +7,43 @@ enum Rule {
     Seq(usize, Vec<usize>),
 }
 
-fn parse_rule(line: &str, rules: &mut Vec<Rule>) -> Result {
-    let mut parts = line.split(":");
-    let index = parts
-        .next()
-        .unwrap_or_default()
-        .parse::<usize>()
-        .map_err(|_| anyhow!("failed to parse: {:?}", line))?;
-
-    let rest = parts.next().unwrap_or_default().trim();
-    for part in rest.split("|") {
-        let rule = if part == "\"a\"" {
-            Rule::Term(index, 'a')
-        } else if part == "\"b\"" {
-            Rule::Term(index, 'b')
-        } else {
-            let seq = part
-                .trim()
-                .split(" ")
-                .map(|s| s.parse::<usize>())
-                .collect::<Result<Vec<_>, _>>()?;
-
-            Rule::Seq(index, seq)
-        };
-
-        rules.push(rule);
+fn parse_rule(c: &mut Cursor) -> ParseResult<Vec<Rule>> {
+    let index = c.int()?;
+    c.tag(": ")?;
+
+    if c.eat("\"") {
+        let letter = c.one_of(&[("a", 'a'), ("b", 'b')])?;
+        c.tag("\"")?;
+        return Ok(vec![Rule::Term(index, letter)]);
     }
 
-    Ok(())
-}
+    c.list(" | ", |c| {
+        let start = *c;
+        let mut seq = vec![c.int()?];
+        while let Some(index) = c.attempt(|c| {
+            c.tag(" ")?;
+            c.int()
+        }) {
+            seq.push(index);
+        }
+
+        if seq.len() > 3 {
+            return Err(start.error("sequences of more than three rules are not supported"));
+        }
 
-fn parse_input(lines: &[String]) -> Result<(Vec<Rule>, Vec<String>)> {
-    let index = lines.iter().position(|l| l.is_empty()).unwrap_or_default();
-    let preamble = lines[..index].to_vec();
-    let messages = lines[index..].to_vec();
+        Ok(Rule::Seq(index, seq))
+    })
+}
 
-    let mut rules = vec![];
-    for line in preamble {
-        parse_rule(&line, &mut rules)?;
-    }
+fn parse_input<'a>(lines: Lines<'a>) -> Result<(Vec<Rule>, Lines<'a>)> {
+    let [rules, messages] = Sections::new(lines).blocks()?;
+    let rules = rules
+        .parse_lines(parse_rule)?
+        .into_iter()
+        .flatten()
+        .collect();
 
-    Ok((rules, messages))
+    Ok((rules, messages.lines))
 }
 
 #[derive(Clone, Debug)]
@@ -56,7 +53,7 @@ struct NormRules {
     max_id: usize,
 }
 
-fn normalize_rules(input: &[Rule]) -> NormRules {
+fn normalize_rules(input: &[Rule]) -> Result<NormRules> {
     let mut terms = vec![];
     let mut triples = vec![];
     let mut aliases = vec![];
@@ -67,7 +64,7 @@ fn normalize_rules(input: &[Rule]) -> NormRules {
             Rule::Seq(i, _) => *i,
         })
         .max()
-        .unwrap()
+        .context("no rules")?
         + 1;
 
     for rule in input {
@@ -87,7 +84,7 @@ fn normalize_rules(input: &[Rule]) -> NormRules {
                 next_id += 1;
             }
             _ => {
-                panic!("unsupported rule");
+                bail!("unsupported rule");
             }
         }
     }
@@ -112,11 +109,16 @@ fn normalize_rules(input: &[Rule]) -> NormRules {
         }
     }
 
-    NormRules {
+    ensure!(
+        all(&triples, |&(_, j, k)| j < next_id && k < next_id),
+        "rule refers to an unknown rule"
+    );
+
+    Ok(NormRules {
         terms,
         triples,
         max_id: next_id,
-    }
+    })
 }
 
 fn matches(line: &str, rules: &NormRules) -> bool {
@@ -156,14 +158,13 @@ fn matches(line: &str, rules: &NormRules) -> bool {
     m[[n - 1, 0, 0]]
 }
 
-pub fn run() -> Result {
-    let input = read_input("day19")?;
-    let (mut rules, msgs) = parse_input(&input)?;
+pub fn run(lines: Lines) -> Result {
+    let (mut rules, msgs) = parse_input(lines)?;
 
-    let norm = normalize_rules(&rules);
+    let norm = normalize_rules(&rules)?;
     let mut count = 0;
 
-    for msg in &msgs {
+    for msg in msgs {
         let valid = matches(msg, &norm);
         count += valid as usize;
 
@@ -173,13 +174,13 @@ pub fn run() -> Result {
     println!("part A: {}", count);
 
     // Add some new rules
-    parse_rule("8: 42 8 | 42", &mut rules)?;
-    parse_rule("11: 42 11 31 | 42 31", &mut rules)?;
+    rules.extend(parse_line(1, "8: 42 8 | 42", parse_rule)?);
+    rules.extend(parse_line(1, "11: 42 11 31 | 42 31", parse_rule)?);
 
-    let norm = normalize_rules(&rules);
+    let norm = normalize_rules(&rules)?;
     let mut count = 0;
 
-    for msg in &msgs {
+    for msg in msgs {
         let valid = matches(msg, &norm);
         count += valid as usize;
 
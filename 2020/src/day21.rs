@@ -1,5 +1,5 @@
 use crate::common::*;
-use std::str::FromStr;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
 #[derive(Debug)]
 struct Food {
@@ -7,22 +7,24 @@ struct Food {
     allergens: Vec<String>,
 }
 
-impl FromStr for Food {
-    type Err = anyhow::Error;
-
-    fn from_str(line: &str) -> Result<Self> {
-        let m = find("^([a-z ]*) [(]contains ([a-z, ]+)[)]$", line)
-            .ok_or_else(|| anyhow!("invalid line {:?}", line))?;
-
-        Ok(Self {
-            ingredients: m[1].split(' ').map_into().collect(),
-            allergens: m[2].split(", ").map_into().collect(),
-        })
+fn parse_food(c: &mut Cursor) -> ParseResult<Food> {
+    let mut ingredients = vec![];
+    while let Some(ingredient) = c.attempt(|c| {
+        let ingredient = c.word()?;
+        c.tag(" ")?;
+        Ok(ingredient)
+    }) {
+        ingredients.push(ingredient.to_string());
     }
-}
 
-fn parse_input(lines: &[String]) -> Result<Vec<Food>> {
-    map(lines, |s| s.parse()).collect()
+    c.tag("(contains ")?;
+    let allergens = c.list(", ", Cursor::word)?;
+    c.tag(")")?;
+
+    Ok(Food {
+        ingredients,
+        allergens: allergens.into_iter().map_into().collect(),
+    })
 }
 
 fn find_allergens(foods: &[Food]) -> Result<HashMap<&str, &str>> {
@@ -58,8 +60,8 @@ fn find_allergens(foods: &[Food]) -> Result<HashMap<&str, &str>> {
     Ok(output)
 }
 
-pub fn run() -> Result {
-    let foods = parse_input(&read_input("day21")?)?;
+pub fn run(lines: Lines) -> Result {
+    let foods = Block::new(lines).parse_lines(parse_food)?;
 
     let ing2all = find_allergens(&foods)?;
 
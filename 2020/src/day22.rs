@@ -1,26 +1,20 @@
 use crate::common::*;
+use advent_common::parse::{Cursor, Sections};
 use std::collections::VecDeque;
 
-fn parse_input(lines: &[String]) -> Result<(Vec<usize>, Vec<usize>)> {
-    let mut iter = lines.iter().map(|s| &**s);
-    let mut player1 = vec![];
-    let mut player2 = vec![];
+fn parse_input(lines: Lines) -> Result<(Vec<usize>, Vec<usize>)> {
+    let [player1, player2] = Sections::new(lines).blocks()?;
+    let player1 = player1.header("Player 1:")?.parse_lines(Cursor::int)?;
+    let player2 = player2.header("Player 2:")?.parse_lines(Cursor::int)?;
 
-    if iter.next() != Some("Player 1:") {
-        bail!("invalid input");
-    }
-
-    while let Ok(i) = iter.next().unwrap_or_default().parse() {
-        player1.push(i);
-    }
-
-    if iter.next() != Some("Player 2:") {
-        bail!("invalid input");
-    }
-
-    while let Ok(i) = iter.next().unwrap_or_default().parse() {
-        player2.push(i);
-    }
+    ensure!(
+        player1.len() + player2.len() > 0,
+        "both players have no cards"
+    );
+    ensure!(
+        all(player1.iter().chain(&player2), |&card| card > 0),
+        "cards must be positive"
+    );
 
     Ok((player1, player2))
 }
@@ -82,8 +76,8 @@ fn play_game(cards1: &[usize], cards2: &[usize], recursive: bool) -> (usize, Vec
     }
 }
 
-pub fn run() -> Result {
-    let (cards1, cards2) = parse_input(&read_input("day22")?)?;
+pub fn run(lines: Lines) -> Result {
+    let (cards1, cards2) = parse_input(lines)?;
 
     let (_winner, cards) = play_game(&cards1, &cards2, false);
     println!("part A: {}", score(&cards));
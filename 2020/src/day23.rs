@@ -1,4 +1,20 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
+
+fn parse_cups(c: &mut Cursor) -> ParseResult<Vec<usize>> {
+    let start = *c;
+    let cups = c
+        .take_while(|c| c.is_ascii_digit())
+        .chars()
+        .map(|c| c as usize - '0' as usize)
+        .collect_vec();
+
+    if cups.len() < 5 || !cups.iter().sorted().eq(&(1..=cups.len()).collect_vec()) {
+        return Err(start.error("expected the cups 1 to n in some order, with n at least 5"));
+    }
+
+    Ok(cups)
+}
 
 fn play_game(mut cups: Vec<usize>, rounds: usize) -> Vec<usize> {
     let n = cups.len();
@@ -39,11 +55,8 @@ fn play_game(mut cups: Vec<usize>, rounds: usize) -> Vec<usize> {
     cups
 }
 
-pub fn run() -> Result {
-    let mut cups = vec![];
-    for c in read_input("day23")?[0].chars() {
-        cups.push(c.to_digit(10).unwrap() as _);
-    }
+pub fn run(lines: Lines) -> Result {
+    let mut cups = Block::new(lines).parse_single(parse_cups)?;
 
     let output = play_game(cups.clone(), 100);
     println!("part A: {}", output.iter().join(""));
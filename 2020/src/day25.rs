@@ -1,29 +1,25 @@
 use crate::common::*;
+use advent_common::math::{discrete_log, mod_pow};
+use advent_common::parse::{Block, Cursor};
 
-fn decrypt(mut sub: usize, pubkey: usize) -> usize {
-    let mut it = 1;
+const MODULUS: i64 = 20201227;
 
-    while sub != pubkey {
-        it += 1;
-        sub = (sub * 7) % 20201227;
-    }
-
-    it
+fn decrypt(sub: i64, pubkey: i64) -> Option<i64> {
+    discrete_log(sub, pubkey, MODULUS)
 }
 
-fn encrypt(sub: usize, it: usize) -> usize {
-    (0..it).fold(1, |acc, _| (acc * sub) % 20201227)
+fn encrypt(sub: i64, it: i64) -> i64 {
+    mod_pow(sub, it as u64, MODULUS)
 }
 
-pub fn run() -> Result {
-    let numbers = read_input("day25")?
-        .into_iter()
-        .map(|s| s.parse::<usize>())
-        .collect::<Result<Vec<_>, _>>()?;
-
-    let card = numbers[0];
-    let door = numbers[1];
-    let answer = encrypt(door, decrypt(7, card));
+pub fn run(lines: Lines) -> Result {
+    let numbers: Vec<i64> = Block::new(lines).parse_lines(Cursor::int)?;
+    let (card, door) = match numbers[..] {
+        [card, door] => (card, door),
+        _ => bail!("expected two public keys"),
+    };
+    let loop_size = decrypt(7, card).context("invalid public key")?;
+    let answer = encrypt(door, loop_size);
 
     println!("answer A: {}", answer);
 
@@ -36,8 +32,8 @@ mod test {
 
     #[test]
     fn test_determine() {
-        assert_eq!(decrypt(7, 5764801), 8);
-        assert_eq!(decrypt(7, 17807724), 11);
+        assert_eq!(decrypt(7, 5764801), Some(8));
+        assert_eq!(decrypt(7, 17807724), Some(11));
 
         assert_eq!(encrypt(7, 8), 5764801);
         assert_eq!(encrypt(7, 11), 17807724);
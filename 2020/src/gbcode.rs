@@ -1,50 +1,171 @@
 use crate::common::*;
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
 use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+use thiserror::Error;
 
-#[derive(Clone, Copy)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    #[error("unknown instruction {0:?}")]
+    UnknownInstruction(String),
+
+    #[error("missing argument for {0:?}")]
+    MissingArgument(String),
+
+    #[error("invalid number {0:?}")]
+    InvalidNumber(String),
+
+    #[error("invalid register {0:?}")]
+    InvalidRegister(String),
+
+    #[error("unexpected trailing input {0:?}")]
+    TrailingInput(String),
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("line {line}: {kind}")]
+pub struct ParseError {
+    pub line: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Reg {
+    A,
+    B,
+}
+
+impl Display for Reg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reg::A => write!(f, "a"),
+            Reg::B => write!(f, "b"),
+        }
+    }
+}
+
+impl FromStr for Reg {
+    type Err = ParseErrorKind;
+
+    fn from_str(s: &str) -> Result<Self, ParseErrorKind> {
+        match s {
+            "a" => Ok(Reg::A),
+            "b" => Ok(Reg::B),
+            _ => Err(ParseErrorKind::InvalidRegister(s.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Instr {
     Nop(i32),
     Acc(i32),
     Jmp(i32),
+    Add(Reg, i32),   // reg += value
+    Mov(Reg, Reg),   // dst = src
+    Jz(Reg, i32),    // jump if reg == 0
+    Jnz(Reg, i32),   // jump if reg != 0
+    Load(Reg, i32),  // reg = mem[addr]
+    Store(Reg, i32), // mem[addr] = reg
+    Input(Reg),
+    Output(Reg),
 }
 
-impl Instr {
-    pub fn parse(s: &str) -> Result<Self> {
-        let mut parts = s.trim().split_whitespace();
-        let instr = parts.next().unwrap_or_default();
-        let arg = parts
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .with_context(|| format!("while parsing line {:?}", s))?;
+impl Display for Instr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Instr::*;
+
+        match *self {
+            Nop(v) => write!(f, "nop {:+}", v),
+            Acc(v) => write!(f, "acc {:+}", v),
+            Jmp(v) => write!(f, "jmp {:+}", v),
+            Add(r, v) => write!(f, "add {} {:+}", r, v),
+            Mov(dst, src) => write!(f, "mov {} {}", dst, src),
+            Jz(r, v) => write!(f, "jz {} {:+}", r, v),
+            Jnz(r, v) => write!(f, "jnz {} {:+}", r, v),
+            Load(r, addr) => write!(f, "ld {} {}", r, addr),
+            Store(r, addr) => write!(f, "st {} {}", r, addr),
+            Input(r) => write!(f, "in {}", r),
+            Output(r) => write!(f, "out {}", r),
+        }
+    }
+}
+
+impl FromStr for Instr {
+    type Err = ParseErrorKind;
+
+    fn from_str(s: &str) -> Result<Self, ParseErrorKind> {
+        use Instr::*;
+
+        let mut parts = s.split_whitespace();
+        let name = parts.next().unwrap_or_default();
+        let mut arg = || {
+            parts
+                .next()
+                .ok_or_else(|| ParseErrorKind::MissingArgument(name.to_string()))
+        };
+
+        fn num(s: &str) -> Result<i32, ParseErrorKind> {
+            s.parse()
+                .map_err(|_| ParseErrorKind::InvalidNumber(s.to_string()))
+        }
+
+        let instr = match name {
+            "nop" => Nop(num(arg()?)?),
+            "acc" => Acc(num(arg()?)?),
+            "jmp" => Jmp(num(arg()?)?),
+            "add" => Add(arg()?.parse()?, num(arg()?)?),
+            "mov" => Mov(arg()?.parse()?, arg()?.parse()?),
+            "jz" => Jz(arg()?.parse()?, num(arg()?)?),
+            "jnz" => Jnz(arg()?.parse()?, num(arg()?)?),
+            "ld" => Load(arg()?.parse()?, num(arg()?)?),
+            "st" => Store(arg()?.parse()?, num(arg()?)?),
+            "in" => Input(arg()?.parse()?),
+            "out" => Output(arg()?.parse()?),
+            _ => return Err(ParseErrorKind::UnknownInstruction(name.to_string())),
+        };
+
+        let rest = parts.join(" ");
+        if !rest.is_empty() {
+            return Err(ParseErrorKind::TrailingInput(rest));
+        }
 
-        Ok(match instr {
-            "nop" => Instr::Nop(arg),
-            "acc" => Instr::Acc(arg),
-            "jmp" => Instr::Jmp(arg),
-            _ => bail!("unknown instruction {:?}", instr),
-        })
+        Ok(instr)
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct Program(Vec<Instr>);
 
 impl Program {
-    pub fn parse_input(filename: &str) -> Result<Self> {
-        let instrs = read_input(filename)?
-            .iter()
-            .map(|s| Instr::parse(&s))
-            .collect::<Result<Vec<_>>>()?;
+    pub fn parse_lines<S: AsRef<str>>(lines: &[S]) -> Result<Self, ParseError> {
+        let mut instrs = vec![];
+
+        for (index, line) in enumerate(lines) {
+            let line = line.as_ref().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let instr = line.parse().map_err(|kind| ParseError {
+                line: index + 1,
+                kind,
+            })?;
+
+            instrs.push(instr);
+        }
 
-        Ok(Self(instrs.into()))
+        Ok(Self(instrs))
     }
 
-    pub fn run(&self) -> Process {
+    pub fn run(&self, inputs: &[i32]) -> Process {
         Process {
             program: self.clone(),
             pc: 0,
-            acc: 0,
+            regs: [0; 2],
+            memory: vec![],
+            inputs: inputs.iter().copied().collect(),
         }
     }
 
@@ -53,6 +174,30 @@ impl Program {
     }
 }
 
+impl From<Vec<Instr>> for Program {
+    fn from(instrs: Vec<Instr>) -> Self {
+        Self(instrs)
+    }
+}
+
+impl Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for instr in &self.0 {
+            writeln!(f, "{}", instr)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Program {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        Self::parse_lines(&s.lines().collect_vec())
+    }
+}
+
 impl Index<usize> for Program {
     type Output = Instr;
 
@@ -70,31 +215,79 @@ impl IndexMut<usize> for Program {
 pub struct Process {
     program: Program,
     pc: i32,
-    acc: i32,
+    regs: [i32; 2],
+    memory: Vec<i32>,
+    inputs: VecDeque<i32>,
 }
 
 impl Process {
-    pub fn advance(&mut self) -> Result {
+    /// Executes one instruction and returns the value it wrote out, if any.
+    pub fn advance(&mut self) -> Result<Option<i32>> {
         use Instr::*;
 
         if self.pc < 0 || self.pc as usize >= self.program.len() {
             bail!("program counter out of bounds");
         }
 
+        let mut offset = 1;
+        let mut output = None;
+
         match self.program[self.pc as usize] {
-            Nop(_) => {
-                self.pc += 1;
-            }
+            Nop(_) => {}
             Acc(v) => {
-                self.acc += v;
-                self.pc += 1;
+                self.regs[Reg::A as usize] += v;
             }
             Jmp(v) => {
-                self.pc += v;
+                offset = v;
+            }
+            Add(r, v) => {
+                self.regs[r as usize] += v;
+            }
+            Mov(dst, src) => {
+                self.regs[dst as usize] = self.regs[src as usize];
+            }
+            Jz(r, v) => {
+                if self.regs[r as usize] == 0 {
+                    offset = v;
+                }
+            }
+            Jnz(r, v) => {
+                if self.regs[r as usize] != 0 {
+                    offset = v;
+                }
+            }
+            Load(r, addr) => {
+                self.regs[r as usize] = *self.cell(addr)?;
+            }
+            Store(r, addr) => {
+                *self.cell(addr)? = self.regs[r as usize];
+            }
+            Input(r) => {
+                self.regs[r as usize] = self
+                    .inputs
+                    .pop_front()
+                    .ok_or_else(|| anyhow!("insufficient number of inputs provided"))?;
+            }
+            Output(r) => {
+                output = Some(self.regs[r as usize]);
             }
         }
 
-        Ok(())
+        self.pc += offset;
+        Ok(output)
+    }
+
+    fn cell(&mut self, addr: i32) -> Result<&mut i32> {
+        if addr < 0 {
+            bail!("memory address out of bounds: {}", addr);
+        }
+
+        let addr = addr as usize;
+        if addr >= self.memory.len() {
+            self.memory.resize(addr + 1, 0);
+        }
+
+        Ok(&mut self.memory[addr])
     }
 
     pub fn done(&self) -> bool {
@@ -105,7 +298,62 @@ impl Process {
         self.pc
     }
 
-    pub fn acc(&self) -> i32 {
-        self.acc
+    pub fn reg(&self, r: Reg) -> i32 {
+        self.regs[r as usize]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let input = "nop +0\nacc -99\njmp +4\nadd b +3\nmov a b\njz a -2\njnz b +1\n\
+                     ld a 7\nst b 0\nin b\nout a\n";
+        let program: Program = input.parse().unwrap();
+
+        assert_eq!(program.len(), 11);
+        assert_eq!(program.to_string(), input);
+        assert_eq!(program.to_string().parse::<Program>().unwrap(), program);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        use ParseErrorKind::*;
+
+        let err = "nop +0\n\nfoo +1".parse::<Program>().unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.kind, UnknownInstruction("foo".to_string()));
+
+        let err = "acc +1\njz c +1".parse::<Program>().unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(err.kind, InvalidRegister("c".to_string()));
+
+        assert_eq!("jmp".parse::<Instr>(), Err(MissingArgument("jmp".into())));
+        assert_eq!("acc x".parse::<Instr>(), Err(InvalidNumber("x".into())));
+        assert_eq!("out a b".parse::<Instr>(), Err(TrailingInput("b".into())));
+    }
+
+    #[test]
+    fn test_execute() {
+        // Multiply the input by three using a countdown loop, and pass the
+        // result through memory before writing it out.
+        let program: Program = "in b\njz b +4\nacc +3\nadd b -1\njmp -3\nst a 4\nld b 4\nout b"
+            .parse()
+            .unwrap();
+
+        let mut p = program.run(&[7]);
+        let mut outputs = vec![];
+
+        while !p.done() {
+            outputs.extend(p.advance().unwrap());
+        }
+
+        assert_eq!(outputs, [21]);
+        assert_eq!(p.reg(Reg::B), 21);
+
+        let mut p = program.run(&[]);
+        assert!(p.advance().is_err());
     }
 }
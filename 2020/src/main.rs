@@ -28,6 +28,8 @@ mod gbcode;
 
 use common::*;
 use std::env;
+use std::fs::read_to_string;
+use std::path::Path;
 
 fn main() -> Result {
     let funs = [
@@ -61,15 +63,33 @@ fn main() -> Result {
     let mut args = env::args();
     let binary = args.next().unwrap_or_default();
     let day = args.next().unwrap_or_default();
-    //let rest: Vec<_> = args.collect();
 
-    if let Ok(x) = day.parse::<usize>() {
-        if x > 0 && x <= funs.len() {
-            (funs[x - 1])()
-        } else {
-            bail!("day must be a number between 1 and {}", funs.len());
-        }
+    let day = if let Ok(i) = day.parse::<usize>() {
+        i
     } else {
         bail!("usage: {} [day]", binary);
+    };
+
+    if day == 0 || day > funs.len() {
+        bail!("day must be a number between 1 and {}", funs.len());
     }
+
+    let mut input_file = String::new();
+
+    for &prefix in &[".", "..", "inputs", "../inputs/"] {
+        input_file = format!("{}/day{:02}", prefix, day);
+
+        if Path::new(&input_file).exists() {
+            break;
+        }
+    }
+
+    let content =
+        read_to_string(&input_file).with_context(|| format!("failed to open: {}", input_file))?;
+    let lines = content
+        .trim_end_matches('\n')
+        .split('\n')
+        .collect::<Vec<_>>();
+
+    (funs[day - 1])(&lines)
 }
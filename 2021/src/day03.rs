@@ -36,7 +36,7 @@ enum Rating {
     Co2,
 }
 
-fn solve_b(input: &[Number], item: Rating, n: i32) -> Number {
+fn solve_b(input: &[Number], item: Rating, n: i32) -> Result<Number> {
     let mut input = input.to_vec();
 
     for p in (0..n).rev() {
@@ -56,11 +56,11 @@ fn solve_b(input: &[Number], item: Rating, n: i32) -> Number {
         input.retain(|&number| number & mask == bit);
 
         if input.len() == 1 {
-            return input[0];
+            return Ok(input[0]);
         }
     }
 
-    panic!("number not found!");
+    bail!("number not found!");
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
@@ -69,8 +69,8 @@ pub(crate) fn run(lines: Lines) -> Result {
     let (gamma, epsilon) = solve_a(&lines, 12);
     println!("part A: {}", gamma * epsilon);
 
-    let oxy = solve_b(&lines, Rating::Oxygen, 12);
-    let co2 = solve_b(&lines, Rating::Co2, 12);
+    let oxy = solve_b(&lines, Rating::Oxygen, 12)?;
+    let co2 = solve_b(&lines, Rating::Co2, 12)?;
     println!("part A: {}", oxy * co2);
 
     Ok(())
@@ -98,8 +98,8 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let oxy = solve_b(&input(), Rating::Oxygen, 5);
-        let co2 = solve_b(&input(), Rating::Co2, 5);
+        let oxy = solve_b(&input(), Rating::Oxygen, 5).unwrap();
+        let co2 = solve_b(&input(), Rating::Co2, 5).unwrap();
 
         assert_eq!((oxy, co2), (10, 23));
     }
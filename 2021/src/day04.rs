@@ -1,38 +1,44 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult, Sections};
 use ndarray::{Array2, ArrayView2};
 
 const N: usize = 5;
 type Num = i32;
 type BingoCard = Array2<Num>;
 
-fn parse_numbers(line: &str) -> Result<Vec<Num>> {
-    parse_list(line, ',')
-}
-
-fn parse_cards(lines: &[&str]) -> Result<Vec<BingoCard>> {
-    let mut cards = vec![];
-    let mut lines = lines.iter();
-
-    loop {
-        let mut card = Array2::zeros((N, N));
+fn parse_card(block: Block<&str>) -> ParseResult<BingoCard> {
+    if block.len() != N {
+        return Err(block.error(0, format_args!("expected {} rows", N)));
+    }
 
-        for i in 0..N {
-            let line = lines.next();
-            let line = line.context("invalid input")?;
+    let mut card = Array2::zeros((N, N));
 
-            for (j, s) in enumerate(line.split_whitespace()) {
-                card[[i, j]] = s.parse().context("invalid number")?;
+    for i in 0..N {
+        block.parse_line(i, |c| {
+            for j in 0..N {
+                c.take_while(char::is_whitespace);
+                card[[i, j]] = c.int()?;
             }
-        }
-
-        cards.push(card);
 
-        if lines.next().is_none() {
-            break;
-        }
+            Ok(())
+        })?;
     }
 
-    Ok(cards)
+    Ok(card)
+}
+
+fn parse(lines: Lines) -> Result<(Vec<Num>, Vec<BingoCard>)> {
+    let sections = Sections::new(lines);
+    let numbers = sections
+        .get(0)?
+        .parse_single(|c| c.list(",", Cursor::int))?;
+    let cards = sections
+        .iter()
+        .skip(1)
+        .map(parse_card)
+        .collect::<ParseResult<Vec<_>>>()?;
+
+    Ok((numbers, cards))
 }
 
 fn has_bingo(checked: ArrayView2<bool>) -> bool {
@@ -61,34 +67,33 @@ fn play_card(numbers: &[Num], card: &BingoCard) -> Option<(usize, Num)> {
     None
 }
 
-fn play_cards_winner(numbers: &[Num], cards: &[BingoCard]) -> Num {
+fn play_cards_winner(numbers: &[Num], cards: &[BingoCard]) -> Result<Num> {
     let (turn, score) = cards
         .iter()
         .filter_map(|card| play_card(numbers, card))
         .min_by_key(|&(turn, _)| turn)
-        .unwrap();
+        .context("no card has bingo")?;
 
-    score * numbers[turn]
+    Ok(score * numbers[turn])
 }
 
-fn play_cards_loser(numbers: &[Num], cards: &[BingoCard]) -> Num {
+fn play_cards_loser(numbers: &[Num], cards: &[BingoCard]) -> Result<Num> {
     let (turn, score) = cards
         .iter()
         .filter_map(|card| play_card(numbers, card))
         .max_by_key(|&(turn, _)| turn)
-        .unwrap();
+        .context("no card has bingo")?;
 
-    score * numbers[turn]
+    Ok(score * numbers[turn])
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let numbers = parse_numbers(lines[0])?;
-    let cards = parse_cards(&lines[2..])?;
+    let (numbers, cards) = parse(lines)?;
 
-    let answer = play_cards_winner(&numbers, &cards);
+    let answer = play_cards_winner(&numbers, &cards)?;
     println!("part A: {:?}", answer);
 
-    let answer = play_cards_loser(&numbers, &cards);
+    let answer = play_cards_loser(&numbers, &cards)?;
     println!("part B: {:?}", answer);
 
     Ok(())
@@ -99,8 +104,9 @@ mod tests {
     use super::*;
 
     fn input() -> (Vec<Num>, Vec<BingoCard>) {
-        let line = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1";
         let lines = [
+            "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1",
+            "",
             "22 13 17 11  0",
             " 8  2 23  4 24",
             "21  9 14 16  7",
@@ -120,14 +126,14 @@ mod tests {
             " 2  0 12  3  7",
         ];
 
-        (parse_numbers(line).unwrap(), parse_cards(&lines).unwrap())
+        parse(&lines).unwrap()
     }
 
     #[test]
     fn test_a() {
         let (numbers, cards) = input();
 
-        let answer = play_cards_winner(&numbers, &cards);
+        let answer = play_cards_winner(&numbers, &cards).unwrap();
         assert_eq!(answer, 4512);
     }
 
@@ -135,7 +141,7 @@ mod tests {
     fn test_b() {
         let (numbers, cards) = input();
 
-        let answer = play_cards_loser(&numbers, &cards);
+        let answer = play_cards_loser(&numbers, &cards).unwrap();
         assert_eq!(answer, 1924);
     }
 }
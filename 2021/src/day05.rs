@@ -1,9 +1,7 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
-#[derive(Debug, Deserialize, PartialEq, Recap)]
-#[recap(regex = r#"(?P<x0>\d+),(?P<y0>\d+) -> (?P<x1>\d+),(?P<y1>\d+)"#)]
+#[derive(Debug, PartialEq)]
 struct Segment {
     x0: i32,
     y0: i32,
@@ -11,12 +9,20 @@ struct Segment {
     y1: i32,
 }
 
+fn parse_segment(c: &mut Cursor) -> ParseResult<Segment> {
+    let x0 = c.int()?;
+    c.tag(",")?;
+    let y0 = c.int()?;
+    c.tag(" -> ")?;
+    let x1 = c.int()?;
+    c.tag(",")?;
+    let y1 = c.int()?;
+
+    Ok(Segment { x0, y0, x1, y1 })
+}
+
 fn parse(lines: Lines) -> Result<Vec<Segment>> {
-    lines
-        .iter()
-        .filter(|s| !s.is_empty())
-        .map(|s| Ok(s.parse()?))
-        .collect()
+    Ok(Block::new(lines).parse_lines(parse_segment)?)
 }
 
 fn overlaps(segments: &[Segment], diagonals: bool) -> usize {
@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
 const N: usize = 9;
 
@@ -8,12 +9,20 @@ struct Population {
     offset: usize,
 }
 
-fn parse_population(line: &str) -> Result<Population> {
+fn parse_population(c: &mut Cursor) -> ParseResult<Population> {
     let mut pop = Population::default();
 
-    for n in parse_list::<usize>(line, ',')? {
+    c.list(",", |c| {
+        let start = *c;
+        let n: usize = c.int()?;
+
+        if n >= N {
+            return Err(start.error(format_args!("timer must be less than {}", N)));
+        }
+
         pop.counts[n] += 1;
-    }
+        Ok(())
+    })?;
 
     Ok(pop)
 }
@@ -34,7 +43,7 @@ fn population_after_days(mut fish: Population, days: usize) -> u128 {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let initial = parse_population(lines[0])?;
+    let initial = Block::new(lines).parse_single(parse_population)?;
 
     let total = population_after_days(initial.clone(), 80);
     println!("part A: {:?}", total);
@@ -50,7 +59,9 @@ mod tests {
     use super::*;
 
     fn input() -> Population {
-        parse_population("3,4,3,1,2").unwrap()
+        Block::new(&["3,4,3,1,2"])
+            .parse_single(parse_population)
+            .unwrap()
     }
 
     #[test]
@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor};
 
 fn solve<F: Fn(i32) -> i32>(pos: &[i32], fuel: F) -> i32 {
     let (&min, &max) = pos.iter().minmax().into_option().unwrap();
@@ -17,7 +18,7 @@ fn solve_b(pos: &[i32]) -> i32 {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let numbers = parse_list(lines[0], ',')?;
+    let numbers = Block::new(lines).parse_single(|c| c.list(",", Cursor::int))?;
 
     println!("part A: {}", solve_a(&numbers));
     println!("part B: {}", solve_b(&numbers));
@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::bitset::SmallSet;
 
 lazy_static::lazy_static! {
     static ref DIGITS: [Sample; 10] = {
@@ -11,7 +12,7 @@ lazy_static::lazy_static! {
     };
 }
 
-type Sample = [bool; 7];
+type Sample = SmallSet;
 
 struct Entry {
     inputs: [Sample; 10],
@@ -19,15 +20,15 @@ struct Entry {
 }
 
 fn parse_sample(chars: &str) -> Result<Sample> {
-    let mut sample: Sample = default();
+    let mut sample = Sample::new();
 
     for c in chars.chars() {
         let num = u32::wrapping_sub(c as u32, 'a' as u32) as usize;
-        if num >= sample.len() {
+        if num >= 7 {
             bail!("invalid character: {:?}", c);
         }
 
-        sample[num] = true;
+        sample.insert(num);
     }
 
     Ok(sample)
@@ -58,16 +59,12 @@ fn parse(lines: Lines) -> Result<Vec<Entry>> {
     lines.iter().map(|s| parse_entry(s)).collect()
 }
 
-fn count_digits(input: &[bool]) -> usize {
-    input.iter().map(|&b| b as usize).sum()
-}
-
 fn solve_a(entries: &[Entry]) -> usize {
     let mut sum = 0;
 
     for entry in entries {
         for output in entry.outputs {
-            if matches!(count_digits(&output), 2 | 3 | 4 | 7) {
+            if matches!(output.len(), 2 | 3 | 4 | 7) {
                 sum += 1;
             }
         }
@@ -79,11 +76,11 @@ fn solve_a(entries: &[Entry]) -> usize {
 type Mapping = [usize; 7];
 
 #[allow(clippy::needless_range_loop)]
-fn find_mapping(entry: &Entry) -> Mapping {
+fn find_mapping(entry: &Entry) -> Result<Mapping> {
     let mut table = [[true; 7]; 7];
 
     for src in 0..7 {
-        let count = entry.inputs.iter().map(|s| s[src] as usize).sum();
+        let count = entry.inputs.iter().filter(|s| s.contains(src)).count();
 
         let dst = match count {
             4 => 'e',
@@ -103,7 +100,7 @@ fn find_mapping(entry: &Entry) -> Mapping {
     }
 
     for src in entry.inputs {
-        let dst = match count_digits(&src) {
+        let dst = match src.len() {
             2 => DIGITS[1],
             3 => DIGITS[7],
             4 => DIGITS[4],
@@ -112,7 +109,7 @@ fn find_mapping(entry: &Entry) -> Mapping {
 
         for i in 0..7 {
             for j in 0..7 {
-                if src[i] ^ dst[j] {
+                if src.contains(i) ^ dst.contains(j) {
                     table[i][j] = false;
                 }
             }
@@ -131,57 +128,49 @@ fn find_mapping(entry: &Entry) -> Mapping {
         }
     }
 
-    if found != 7 {
-        panic!("invalid input");
-    }
+    ensure!(found == 7, "no unique mapping for entry");
 
-    mapping
+    Ok(mapping)
 }
 
-fn decode_digit(encoded: Sample, mapping: Mapping) -> usize {
-    let mut decoded = Sample::default();
-
-    for (i, &active) in enumerate(&encoded) {
-        if active {
-            decoded[mapping[i]] = true;
-        }
-    }
+fn decode_digit(encoded: Sample, mapping: Mapping) -> Result<usize> {
+    let decoded = encoded.iter().map(|i| mapping[i]).collect::<Sample>();
 
     for (i, &digit) in enumerate(&*DIGITS) {
         if digit == decoded {
-            return i;
+            return Ok(i);
         }
     }
 
-    panic!("unknown digit: {:?}", decoded);
+    bail!("unknown digit: {:?}", decoded);
 }
 
-fn decode_output(entry: &Entry, mapping: Mapping) -> usize {
+fn decode_output(entry: &Entry, mapping: Mapping) -> Result<usize> {
     let mut result = 0;
 
     for output in entry.outputs {
-        result = result * 10 + decode_digit(output, mapping);
+        result = result * 10 + decode_digit(output, mapping)?;
     }
 
-    result
+    Ok(result)
 }
 
-fn solve_b(entries: &[Entry]) -> usize {
+fn solve_b(entries: &[Entry]) -> Result<usize> {
     let mut sum = 0;
 
     for entry in entries {
-        let mapping = find_mapping(entry);
-        sum += decode_output(entry, mapping);
+        let mapping = find_mapping(entry)?;
+        sum += decode_output(entry, mapping)?;
     }
 
-    sum
+    Ok(sum)
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
     let entries = parse(lines)?;
 
     println!("part A: {:?}", solve_a(&entries));
-    println!("part B: {:?}", solve_b(&entries));
+    println!("part B: {:?}", solve_b(&entries)?);
 
     Ok(())
 }
@@ -214,6 +203,6 @@ mod tests {
 
     #[test]
     fn test_b() {
-        assert_eq!(solve_b(&input()), 61229);
+        assert_eq!(solve_b(&input()).unwrap(), 61229);
     }
 }
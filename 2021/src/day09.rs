@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::Block;
 use ndarray::{Array2, ArrayView2};
 
 type Num = i32;
@@ -14,17 +15,10 @@ fn neighbors(map: ArrayView2<Num>, x: usize, y: usize) -> impl Iterator<Item = (
 }
 
 fn parse(lines: Lines) -> Result<Array2<Num>> {
-    let n = lines.len();
-    let m = lines[0].len();
-    let mut grid = Array2::from_elem((n, m), 0);
+    let grid = Block::new(lines).parse_grid(|c| c.to_digit(10))?;
+    let (n, m) = (grid.height(), grid.width());
 
-    for (i, line) in enumerate(lines) {
-        for (j, c) in enumerate(line.chars()) {
-            grid[[i, j]] = c.to_string().parse()?;
-        }
-    }
-
-    Ok(grid)
+    Ok(Array2::from_shape_fn((n, m), |(i, j)| grid[[j, i]] as Num))
 }
 
 fn solve_a(map: ArrayView2<Num>) -> Num {
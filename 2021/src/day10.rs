@@ -53,7 +53,7 @@ fn solve_b(lines: Lines) -> Result<usize> {
                         ']' => 2,
                         '}' => 3,
                         '>' => 4,
-                        _ => panic!("invalid character: {:?}", c),
+                        _ => bail!("invalid character: {:?}", c),
                     }
             }
 
@@ -61,6 +61,7 @@ fn solve_b(lines: Lines) -> Result<usize> {
         }
     }
 
+    ensure!(!scores.is_empty(), "no incomplete lines");
     scores.sort_unstable();
     Ok(scores[scores.len() / 2])
 }
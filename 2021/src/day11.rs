@@ -1,106 +1,80 @@
 use crate::common::*;
-use ndarray::Array2;
-use std::collections::HashSet;
-
-const N: usize = 10;
-
-fn parse(lines: Lines) -> Result<Array2<i32>> {
-    ensure!(lines.len() == N, "invalid input");
-    let mut grid = Array2::zeros((N, N));
+use advent_common::automaton::DenseAutomaton;
+use advent_common::grid::Grid;
+use advent_common::parse::Block;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+enum Octopus {
+    Energy(u8),
+    Flashing,
+    Flashed,
+}
 
-    for (i, line) in enumerate(lines) {
-        for (j, c) in enumerate(line.chars()) {
-            let x = c.to_string().parse()?;
-            ensure!((0..9).contains(&x) && j < N, "invalid character: {:?}", x);
+use Octopus::*;
 
-            grid[[i, j]] = x;
-        }
-    }
-
-    Ok(grid)
+fn parse(lines: Lines) -> Result<Grid<Octopus>> {
+    Ok(Block::new(lines).parse_grid(|c| Some(Energy(c.to_digit(10)? as u8)))?)
 }
 
-fn neighbors(i: usize, j: usize) -> impl Iterator<Item = (usize, usize)> {
-    [
-        [-1, 1],
-        [-1, 0],
-        [-1, -1],
-        [0, 1],
-        [0, -1],
-        [1, 1],
-        [1, 0],
-        [1, -1],
-    ]
-    .iter()
-    .map(move |&[di, dj]| (i as isize + di, j as isize + dj))
-    .filter_map(|(x, y)| Some((x.try_into().ok()?, y.try_into().ok()?)))
-    .filter(move |&(x, y)| x < N && y < N)
+// An octopus with an energy level greater than 9 flashes.
+fn charge(energy: u8) -> Octopus {
+    if energy > 9 {
+        Flashing
+    } else {
+        Energy(energy)
+    }
 }
 
-fn step(grid: &mut Array2<i32>) -> usize {
-    assert_eq!(grid.shape(), &[N, N]);
-    let mut active = Vec::new();
-    let mut visited = HashSet::new();
-
+fn step(grid: &DenseAutomaton<Octopus>) -> (DenseAutomaton<Octopus>, usize) {
     // First, the energy level of each octopus increases by 1.
-    for i in 0..N {
-        for j in 0..N {
-            grid[[i, j]] += 1;
-
-            // any octopus with an energy level greater than 9 flashes.
-            if grid[[i, j]] > 9 {
-                active.push((i, j));
-                visited.insert((i, j));
-            }
-        }
-    }
-
-    while let Some((i, j)) = active.pop() {
-        for neighbor in neighbors(i, j) {
-            if visited.contains(&neighbor) {
-                continue;
-            }
-
-            // increases the energy level of all adjacent octopuses by 1,
-            // including octopuses that are diagonally adjacent
-            grid[neighbor] += 1;
-
-            // If this causes an octopus to have an energy level greater than 9, it also flashes.
-            if grid[neighbor] > 9 {
-                active.push(neighbor);
-                visited.insert(neighbor);
-            }
-        }
-    }
-
-    for &index in &visited {
-        grid[index] = 0;
-    }
-
-    visited.len()
+    let grid = grid.step(|o| match *o.center() {
+        Energy(e) => charge(e + 1),
+        other => other,
+    });
+
+    // A flash increases the energy level of all adjacent octopuses by 1,
+    // including octopuses that are diagonally adjacent. If this causes an
+    // octopus to have an energy level greater than 9, it also flashes.
+    let (grid, _) = grid.stabilize(|o| match *o.center() {
+        Energy(e) => charge(e + o.neighbors8().filter(|&&n| n == Flashing).count() as u8),
+        Flashing | Flashed => Flashed,
+    });
+
+    let flashes = grid.count(|&o| o == Flashed);
+    let grid = grid.step(|o| match *o.center() {
+        Flashed => Energy(0),
+        other => other,
+    });
+
+    (grid, flashes)
 }
 
-fn count_flashes(input: &Array2<i32>, steps: usize) -> usize {
+fn count_flashes(input: &Grid<Octopus>, steps: usize) -> usize {
     let mut total = 0;
-    let mut grid = input.clone();
+    let mut grid = DenseAutomaton::bounded(input.clone());
 
     for _ in 0..steps {
-        total += step(&mut grid);
+        let (next, flashes) = step(&grid);
+        total += flashes;
+        grid = next;
     }
 
     total
 }
 
-fn first_simulate_flash(input: &Array2<i32>) -> usize {
+fn first_simulate_flash(input: &Grid<Octopus>) -> usize {
     let mut steps = 0;
-    let mut grid = input.clone();
+    let mut grid = DenseAutomaton::bounded(input.clone());
 
     loop {
         steps += 1;
 
-        if step(&mut grid) == N * N {
+        let (next, flashes) = step(&grid);
+        if flashes == input.width() * input.height() {
             return steps;
         }
+
+        grid = next;
     }
 }
 
@@ -117,7 +91,7 @@ pub(crate) fn run(lines: Lines) -> Result {
 mod tests {
     use super::*;
 
-    fn input() -> Array2<i32> {
+    fn input() -> Grid<Octopus> {
         let lines = [
             "5483143223",
             "2745854711",
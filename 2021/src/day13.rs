@@ -1,28 +1,31 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult, Sections};
 use ndarray::{s, Array2};
-use recap::Recap;
-use serde::Deserialize;
 
-#[derive(Debug, Deserialize, PartialEq, Recap, Copy, Clone)]
-#[recap(regex = r#"fold along (?P<axis>[xy])=(?P<pos>\d+)"#)]
+#[derive(Debug, PartialEq, Copy, Clone)]
 struct Instruction {
     axis: char,
     pos: usize,
 }
 
-fn parse_grid(lines: Lines) -> Result<Array2<bool>> {
-    let mut coords = vec![];
-    let mut size = 0;
+fn parse_dot(c: &mut Cursor) -> ParseResult<(usize, usize)> {
+    let x = c.int()?;
+    c.tag(",")?;
+    let y = c.int()?;
+    Ok((x, y))
+}
 
-    for line in lines {
-        let (x, y) = line
-            .split_once(',')
-            .ok_or_else(|| anyhow!("invalid line: {:?}", line))?;
-        let (x, y) = (x.parse()?, y.parse()?);
+fn parse_instr(c: &mut Cursor) -> ParseResult<Instruction> {
+    c.tag("fold along ")?;
+    let axis = c.one_of(&[("x", 'x'), ("y", 'y')])?;
+    c.tag("=")?;
+    let pos = c.int()?;
+    Ok(Instruction { axis, pos })
+}
 
-        size = size.max(x + 1).max(y + 1);
-        coords.push((x, y));
-    }
+fn parse_grid(block: Block<&str>) -> Result<Array2<bool>> {
+    let coords = block.parse_lines(parse_dot)?;
+    let size = max(coords.iter().map(|&(x, y)| x.max(y) + 1)).unwrap_or(0);
 
     let mut grid = Array2::from_elem((size, size), false);
 
@@ -33,8 +36,8 @@ fn parse_grid(lines: Lines) -> Result<Array2<bool>> {
     Ok(grid)
 }
 
-fn parse_instrs(lines: Lines) -> Result<Vec<Instruction>> {
-    lines.iter().map(|line| Ok(line.parse()?)).collect()
+fn parse_instrs(block: Block<&str>) -> Result<Vec<Instruction>> {
+    Ok(block.parse_lines(parse_instr)?)
 }
 
 fn fold_x(grid: &mut Array2<bool>, fold: usize) {
@@ -66,9 +69,10 @@ fn count_after_one_fold(grid: &Array2<bool>, instr: Instruction) -> usize {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let mid = lines.iter().position(|x| x.is_empty()).unwrap();
-    let mut grid = parse_grid(&lines[..mid])?;
-    let instrs = parse_instrs(&lines[mid + 1..])?;
+    let [dots, folds] = Sections::new(lines).blocks()?;
+    let mut grid = parse_grid(dots)?;
+    let instrs = parse_instrs(folds)?;
+    ensure!(!instrs.is_empty(), "no fold instructions");
 
     println!("part A: {:?}", count_after_one_fold(&grid, instrs[0]));
 
@@ -114,8 +118,9 @@ mod tests {
             "fold along x=5",
         ];
 
-        let grid = parse_grid(&lines[..18]).unwrap();
-        let instrs = parse_instrs(&lines[19..]).unwrap();
+        let [dots, folds] = Sections::new(&lines).blocks().unwrap();
+        let grid = parse_grid(dots).unwrap();
+        let instrs = parse_instrs(folds).unwrap();
 
         (grid, instrs)
     }
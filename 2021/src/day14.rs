@@ -1,19 +1,21 @@
 use crate::common::*;
+use advent_common::parse::{Cursor, ParseResult, Sections};
 use defaultmap::DefaultHashMap;
-use recap::Recap;
-use serde::Deserialize;
-use std::str::FromStr;
 
-#[derive(Debug, Deserialize, PartialEq, Recap, Copy, Clone)]
-#[recap(regex = r#"(?P<lhs>[A-Z])(?P<rhs>[A-Z]) -> (?P<output>[A-Z])"#)]
+#[derive(Debug, PartialEq, Copy, Clone)]
 struct Rule {
     lhs: char,
     rhs: char,
     output: char,
 }
 
-fn parse(lines: Lines) -> Result<Vec<Rule>> {
-    lines.iter().map(|line| Ok(Rule::from_str(line)?)).collect()
+fn parse_rule(c: &mut Cursor) -> ParseResult<Rule> {
+    let lhs = c.next_char()?;
+    let rhs = c.next_char()?;
+    c.tag(" -> ")?;
+    let output = c.next_char()?;
+
+    Ok(Rule { lhs, rhs, output })
 }
 
 fn count_most_minus_least(input: &str, rules: &[Rule], steps: usize) -> usize {
@@ -53,8 +55,10 @@ fn count_most_minus_least(input: &str, rules: &[Rule], steps: usize) -> usize {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let input = lines[0];
-    let rules = parse(&lines[2..])?;
+    let [template, rules] = Sections::new(lines).blocks()?;
+    let input = template.parse_single(|c| Ok(c.take_while(|_| true)))?;
+    ensure!(!input.is_empty(), "empty polymer template");
+    let rules = rules.parse_lines(parse_rule)?;
 
     println!("part A: {:?}", count_most_minus_least(input, &rules, 10));
     println!("part B: {:?}", count_most_minus_least(input, &rules, 40));
@@ -65,6 +69,7 @@ pub(crate) fn run(lines: Lines) -> Result {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_common::parse::Block;
 
     fn input() -> (String, Vec<Rule>) {
         let input = "NNCB".to_string();
@@ -73,7 +78,7 @@ mod tests {
             "BH -> H", "NC -> B", "NB -> B", "BN -> B", "BB -> N", "BC -> B", "CC -> N", "CN -> C",
         ];
 
-        (input, parse(&lines).unwrap())
+        (input, Block::new(&lines).parse_lines(parse_rule).unwrap())
     }
 
     #[test]
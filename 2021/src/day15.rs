@@ -1,25 +1,15 @@
 use crate::common::*;
-use binary_heap_plus::BinaryHeap;
-use defaultmap::DefaultHashMap;
+use advent_common::parse::Block;
+use advent_common::search::dijkstra;
 use ndarray::{Array2, ArrayView2};
-use std::cmp::Reverse;
 
 fn parse(lines: Lines) -> Result<Array2<u32>> {
-    let h = lines.len();
-    let w = lines[0].len();
-    let mut map = Array2::from_elem((w, h), 0);
+    let grid = Block::new(lines).parse_grid(|c| c.to_digit(10))?;
 
-    for (y, line) in enumerate(lines) {
-        ensure!(line.len() == w, "invalid input");
-
-        for (x, c) in enumerate(line.chars()) {
-            map[[x, y]] = c
-                .to_digit(10)
-                .ok_or_else(|| anyhow!("invalid digit: {:?}", c))?;
-        }
-    }
-
-    Ok(map)
+    Ok(Array2::from_shape_fn(
+        (grid.width(), grid.height()),
+        |(x, y)| grid[[x, y]],
+    ))
 }
 
 fn grow_map(input: ArrayView2<u32>) -> Array2<u32> {
@@ -51,25 +41,13 @@ fn neighbors<T>(map: ArrayView2<T>, [x, y]: [usize; 2]) -> impl Iterator<Item =
 }
 
 fn lowest_risk(map: ArrayView2<u32>) -> u32 {
-    let mut risk = DefaultHashMap::new(u32::MAX);
-    let mut queue = BinaryHeap::new_by_key(|&(_, risk)| Reverse(risk));
-
-    queue.push(([0, 0], 0));
-
-    while let Some((pos, current)) = queue.pop() {
-        if current >= risk[pos] {
-            continue;
-        }
-
-        risk[pos] = current;
+    let (n, m) = map.dim();
+    let goal = [n - 1, m - 1];
 
-        for neighbor in neighbors(map.view(), pos) {
-            queue.push((neighbor, current + map[neighbor]));
-        }
-    }
+    let successors = |&pos: &[usize; 2]| neighbors(map.view(), pos).map(|p| (p, map[p]));
+    let paths = dijkstra([[0, 0]], successors, |&pos| pos == goal);
 
-    let (n, m) = map.dim();
-    risk[[n - 1, m - 1]]
+    paths.goal_distance().unwrap_or(u32::MAX)
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
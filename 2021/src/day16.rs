@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::Block;
 use std::str::Chars;
 
 type Num = u64;
@@ -47,7 +48,7 @@ impl Iterator for BitStream<'_> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.buffer_index >= self.buffer.len() {
-            let c = self.input.next()?.to_digit(16).unwrap();
+            let c = self.input.next()?.to_digit(16)?;
 
             for i in 0..4 {
                 self.buffer[i] = (c & (0x8 >> i)) != 0;
@@ -160,6 +161,7 @@ fn eval(packet: &Packet) -> Result<Num> {
         1 => children.into_iter().product(),
         2 => children.into_iter().min().unwrap_or_default(),
         3 => children.into_iter().max().unwrap_or_default(),
+        5..=7 if children.len() != 2 => bail!("comparison needs two operands"),
         5 => (children[0] > children[1]) as _,
         6 => (children[0] < children[1]) as _,
         7 => (children[0] == children[1]) as _,
@@ -168,7 +170,8 @@ fn eval(packet: &Packet) -> Result<Num> {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let p = parse(&mut BitStream::new(lines[0])?)?;
+    let line = Block::new(lines).parse_single(|c| Ok(c.take_while(|_| true)))?;
+    let p = parse(&mut BitStream::new(line)?)?;
 
     println!("part A: {:?}", sum_versions(&p));
     println!("part B: {:?}", eval(&p)?);
@@ -1,13 +1,9 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
 type Num = i32;
 
-#[derive(Debug, Deserialize, PartialEq, Recap, Copy, Clone)]
-#[recap(
-    regex = r#"target area: x=(?P<x0>[0-9]+)..(?P<x1>[0-9]+), y=(?P<y0>-[0-9]+)..(?P<y1>-[0-9]+)"#
-)]
+#[derive(Debug, PartialEq, Copy, Clone)]
 struct Target {
     x0: Num,
     y0: Num,
@@ -15,6 +11,19 @@ struct Target {
     y1: Num,
 }
 
+fn parse_target(c: &mut Cursor) -> ParseResult<Target> {
+    c.tag("target area: x=")?;
+    let x0 = c.int()?;
+    c.tag("..")?;
+    let x1 = c.int()?;
+    c.tag(", y=")?;
+    let y0 = c.int()?;
+    c.tag("..")?;
+    let y1 = c.int()?;
+
+    Ok(Target { x0, y0, x1, y1 })
+}
+
 fn simulate(mut vx: Num, mut vy: Num, target: Target) -> Option<Num> {
     let (mut x, mut y) = (0, 0);
     let mut max_y = 0;
@@ -78,7 +87,7 @@ fn number_velocities(target: Target) -> Num {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let target = lines[0].parse()?;
+    let target = Block::new(lines).parse_single(parse_target)?;
 
     println!("part A: {:?}", highest_position(target));
     println!("part B: {:?}", number_velocities(target));
@@ -91,7 +100,8 @@ mod tests {
     use super::*;
 
     fn input() -> Target {
-        "target area: x=20..30, y=-10..-5".parse().unwrap()
+        let lines = ["target area: x=20..30, y=-10..-5"];
+        Block::new(&lines).parse_single(parse_target).unwrap()
     }
 
     #[test]
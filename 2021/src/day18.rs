@@ -1,6 +1,6 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use std::fmt::{self, Display};
-use std::str::Chars;
 
 type Num = i64;
 
@@ -21,27 +21,29 @@ impl Display for SnailNum {
     }
 }
 
-fn parse_line(line: &mut Chars<'_>) -> Result<SnailNum> {
-    match line.next().unwrap_or_default() {
-        '[' => {
-            let left = parse_line(line)?;
-            ensure!(line.next() == Some(','), "expecting ','");
-            let right = parse_line(line)?;
-            ensure!(line.next() == Some(']'), "expecting ']'");
-            Ok(Pair(Box::new(left), Box::new(right)))
-        }
-        c @ '0'..='9' => Ok(Value(c as i64 - '0' as i64)),
-        other => {
-            bail!("invalid character: {:?}", other)
-        }
+// Pairs nested deeper than this cannot be exploded.
+const MAX_DEPTH: u32 = 4;
+
+fn parse_number(c: &mut Cursor, depth: u32) -> ParseResult<SnailNum> {
+    if c.peek() != Some('[') {
+        return Ok(Value(c.int()?));
+    }
+
+    if depth > MAX_DEPTH {
+        return Err(c.error("pair is nested too deeply"));
     }
+
+    c.tag("[")?;
+    let left = parse_number(c, depth + 1)?;
+    c.tag(",")?;
+    let right = parse_number(c, depth + 1)?;
+    c.tag("]")?;
+
+    Ok(Pair(Box::new(left), Box::new(right)))
 }
 
 fn parse_lines(lines: &[&str]) -> Result<Vec<SnailNum>> {
-    lines
-        .iter()
-        .map(|line| parse_line(&mut line.chars()))
-        .collect()
+    Ok(Block::new(lines).parse_lines(|c| parse_number(c, 0))?)
 }
 
 fn apply_explode(num: &mut SnailNum) -> bool {
@@ -144,6 +146,7 @@ fn largest_sum(numbers: &[SnailNum]) -> SnailNum {
 
 pub(crate) fn run(lines: Lines) -> Result {
     let numbers = parse_lines(lines)?;
+    ensure!(!numbers.is_empty(), "no snail numbers");
 
     println!("part A: {}", magnitude(&sum(&numbers)));
     println!("part A: {}", magnitude(&largest_sum(&numbers)));
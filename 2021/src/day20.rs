@@ -1,63 +1,58 @@
 use crate::common::*;
-use ndarray::Array2;
+use advent_common::automaton::{DenseAutomaton, Neighborhood};
+use advent_common::grid::Grid;
+use advent_common::parse::Sections;
 
 type Lookup = [bool; 512];
 
-fn parse(lines: Lines) -> Result<(Lookup, Array2<bool>)> {
-    ensure!(lines.len() > 2);
-
-    let mut lookup = [false; 512];
-    ensure!(lines[0].len() == lookup.len());
-
-    for (i, c) in enumerate(lines[0].chars()) {
-        lookup[i] = c == '#';
+fn pixel(c: char) -> Option<bool> {
+    match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
     }
+}
 
-    let height = lines.len() - 2;
-    let width = lines[2].len();
-    let mut img = Array2::from_elem((height, width), false);
-
-    for (i, line) in enumerate(&lines[2..]) {
-        ensure!(line.len() == width);
+fn parse(lines: Lines) -> Result<(Lookup, Grid<bool>)> {
+    let [algorithm, image] = Sections::new(lines).blocks()?;
+    let mut lookup = [false; 512];
 
-        for (j, c) in enumerate(line.chars()) {
-            img[[i, j]] = c == '#';
+    algorithm.parse_single(|c| {
+        for entry in &mut lookup {
+            let start = *c;
+            let ch = c.next_char()?;
+            *entry =
+                pixel(ch).ok_or_else(|| start.error(format_args!("invalid character {:?}", ch)))?;
         }
-    }
 
+        Ok(())
+    })?;
+
+    let img = image.parse_grid(pixel)?;
     Ok((lookup, img))
 }
 
-fn simulate(input: &Array2<bool>, lookup: &Lookup, default: bool) -> Array2<bool> {
-    let (n, m) = input.dim();
-
-    Array2::from_shape_fn((n + 2, m + 2), |(i, j)| {
-        let mut index = 0;
+fn enhance(pixels: &Neighborhood<bool>, lookup: &Lookup) -> bool {
+    let mut index = 0;
 
-        for di in [2, 1, 0] {
-            for dj in [2, 1, 0] {
-                let flag = if i >= di && j >= dj {
-                    input.get((i - di, j - dj)).copied().unwrap_or(default)
-                } else {
-                    default
-                };
-
-                index = (index * 2) + (flag as usize);
-            }
+    for dy in [-1, 0, 1] {
+        for dx in [-1, 0, 1] {
+            let flag = *pixels.get([dx, dy]).unwrap();
+            index = (index * 2) + (flag as usize);
         }
+    }
 
-        lookup[index]
-    })
+    lookup[index]
 }
 
-fn count_after(img: &Array2<bool>, lookup: &Lookup, iters: usize) -> usize {
-    let mut img = img.clone();
+fn count_after(img: &Grid<bool>, lookup: &Lookup, iters: usize) -> usize {
+    let mut img = DenseAutomaton::unbounded(img.clone(), false);
 
-    for iter in 0..iters {
-        img = simulate(&img, lookup, lookup[0] && (iter % 2 == 1));
+    for _ in 0..iters {
+        img = img.step(|pixels| enhance(pixels, lookup));
     }
 
-    img.iter().filter(|&&b| b).count()
+    img.count(|&b| b)
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
@@ -72,7 +67,7 @@ pub(crate) fn run(lines: Lines) -> Result {
 mod tests {
     use super::*;
 
-    fn input() -> (Lookup, Array2<bool>) {
+    fn input() -> (Lookup, Grid<bool>) {
         let lines = [
             "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.\
             ###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.\
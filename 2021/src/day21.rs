@@ -1,15 +1,25 @@
 use crate::common::*;
+use advent_common::memo::Memo;
+use advent_common::parse::{Block, Cursor};
 
 pub(crate) fn parse(lines: Lines) -> Result<[u64; 2]> {
-    let a = find_regex("Player 1 starting position: ([0-9])", lines[0])
-        .ok_or_else(|| anyhow!("invalid input"))?[1]
-        .parse()?;
+    let block = Block::new(lines);
+    ensure!(block.len() == 2, "expected two players");
 
-    let b = find_regex("Player 2 starting position: ([0-9])", lines[1])
-        .ok_or_else(|| anyhow!("invalid input"))?[1]
-        .parse()?;
+    let start = |index: usize| {
+        block.parse_line(index, |c: &mut Cursor| {
+            c.tag(&format!("Player {} starting position: ", index + 1))?;
+            let space = c.int()?;
 
-    Ok([a, b])
+            if !(1..=10).contains(&space) {
+                return Err(c.error("space must be between 1 and 10"));
+            }
+
+            Ok(space)
+        })
+    };
+
+    Ok([start(0)?, start(1)?])
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -58,7 +68,7 @@ fn play_quantum_game(spaces: [u64; 2]) -> [u64; 2] {
         player: u8,
     }
 
-    fn state_to_key(state: State) -> usize {
+    fn state_to_key(state: &State) -> usize {
         let [a, b] = [state.scores[0] as usize, state.scores[1] as usize];
         let [x, y] = [state.spaces[0] as usize, state.spaces[1] as usize];
         let p = state.player as usize;
@@ -66,16 +76,8 @@ fn play_quantum_game(spaces: [u64; 2]) -> [u64; 2] {
         (((a * MAX_SCORE + b) * MAX_SPACES + x) * MAX_SPACES + y) * 2 + p
     }
 
-    let mut cache = vec![[0, 0]; MAX_SCORE * MAX_SCORE * MAX_SPACES * MAX_SPACES * 2];
-
-    fn recur(state: State, cache: &mut [[u64; 2]]) -> [u64; 2] {
+    fn play(recur: &mut dyn FnMut(State) -> [u64; 2], &state: &State) -> [u64; 2] {
         const THROWS: [(u8, u64); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
-        let key = state_to_key(state);
-
-        if cache[key] != [0, 0] {
-            return cache[key];
-        }
-
         let mut result = [0, 0];
 
         for (total, times) in THROWS {
@@ -92,25 +94,24 @@ fn play_quantum_game(spaces: [u64; 2]) -> [u64; 2] {
                     _ => panic!("invalid player"),
                 }
             } else {
-                recur(state, cache)
+                recur(state)
             };
 
             result[0] += times * subresult[0];
             result[1] += times * subresult[1];
         }
 
-        cache[key] = result;
         result
     }
 
-    recur(
-        State {
-            scores: [0, 0],
-            spaces: [spaces[0] as u8 - 1, spaces[1] as u8 - 1],
-            player: 0,
-        },
-        &mut cache,
-    )
+    let size = MAX_SCORE * MAX_SCORE * MAX_SPACES * MAX_SPACES * 2;
+    let initial = State {
+        scores: [0, 0],
+        spaces: [spaces[0] as u8 - 1, spaces[1] as u8 - 1],
+        player: 0,
+    };
+
+    Memo::dense(size, state_to_key).call(initial, &play)
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
@@ -1,15 +1,10 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
-use std::mem::take;
-use std::ops::Range;
+use advent_common::interval::{BoxSet, Cuboid};
+use advent_common::parse::{Block, Cursor, ParseResult};
 
 type Num = i64;
 
-#[derive(Hash, Debug, Deserialize, PartialEq, Recap, Clone, Ord, PartialOrd, Eq)]
-#[recap(
-    regex = r#"(?P<action>(on|off)) x=(?P<x0>-?[0-9]+)..(?P<x1>-?[0-9]+),y=(?P<y0>-?[0-9]+)..(?P<y1>-?[0-9]+),z=(?P<z0>-?[0-9]+)..(?P<z1>-?[0-9]+)"#
-)]
+#[derive(Hash, Debug, PartialEq, Clone, Ord, PartialOrd, Eq)]
 struct Instr {
     action: String,
     x0: Num,
@@ -20,8 +15,35 @@ struct Instr {
     z1: Num,
 }
 
+fn parse_range(c: &mut Cursor, axis: &str) -> ParseResult<(Num, Num)> {
+    c.tag(axis)?;
+    c.tag("=")?;
+    let low = c.int()?;
+    c.tag("..")?;
+    let high = c.int()?;
+
+    Ok((low, high))
+}
+
+fn parse_instr(c: &mut Cursor) -> ParseResult<Instr> {
+    let action = c.one_of(&[("on", "on"), ("off", "off")])?.to_string();
+    let (x0, x1) = parse_range(c, " x")?;
+    let (y0, y1) = parse_range(c, ",y")?;
+    let (z0, z1) = parse_range(c, ",z")?;
+
+    Ok(Instr {
+        action,
+        x0,
+        y0,
+        z0,
+        x1,
+        y1,
+        z1,
+    })
+}
+
 fn parse(lines: Lines) -> Result<Vec<Instr>> {
-    lines.iter().map(|l| Ok(l.parse()?)).collect()
+    Ok(Block::new(lines).parse_lines(parse_instr)?)
 }
 
 /*
@@ -54,110 +76,46 @@ fn execute(instructions: &[Instr]) -> usize {
 }
 */
 
-#[derive(Clone, Debug)]
-struct Cube {
-    x: Range<Num>,
-    y: Range<Num>,
-    z: Range<Num>,
-}
-
-impl Cube {
-    fn from_bounds(low: Num, high: Num) -> Self {
-        Self {
-            x: low..(high + 1),
-            y: low..(high + 1),
-            z: low..(high + 1),
-        }
-    }
-
-    fn from_instr(instr: &Instr) -> Self {
-        Self {
-            x: instr.x0..(instr.x1 + 1),
-            y: instr.y0..(instr.y1 + 1),
-            z: instr.z0..(instr.z1 + 1),
-        }
-    }
-
-    fn volume(&self) -> Num {
-        (self.x.end - self.x.start) * (self.y.end - self.y.start) * (self.z.end - self.z.start)
-    }
-
-    fn intersection(&self, that: &Self) -> Option<Cube> {
-        let f = |a: &Range<Num>, b: &Range<Num>| {
-            let start = Num::max(a.start, b.start);
-            let end = Num::min(a.end, b.end);
-            (start < end).then(|| start..end)
-        };
-
-        Some(Self {
-            x: f(&self.x, &that.x)?,
-            y: f(&self.y, &that.y)?,
-            z: f(&self.z, &that.z)?,
-        })
-    }
-
-    fn intersects(&self, that: &Self) -> bool {
-        self.intersection(that).is_some()
-    }
-
-    fn split(&self, that: &Self, out: &mut Vec<Self>) {
-        let p = if let Some(p) = self.intersection(that) {
-            p
-        } else {
-            return out.push(self.clone());
-        };
+type Cube = Cuboid<3>;
 
-        let subranges = |this: &Range<Num>, that: &Range<Num>| {
-            [
-                this.start..that.start,
-                that.start..that.end,
-                that.end..this.end,
-            ]
-        };
+fn cube_from_bounds(low: Num, high: Num) -> Cube {
+    Cube::new([low..(high + 1), low..(high + 1), low..(high + 1)])
+}
 
-        for x in subranges(&self.x, &p.x) {
-            for y in subranges(&self.y, &p.y) {
-                for z in subranges(&self.z, &p.z) {
-                    if !x.is_empty() && !y.is_empty() && !z.is_empty() {
-                        out.push(Cube {
-                            x: x.clone(),
-                            y: y.clone(),
-                            z: z.clone(),
-                        });
-                    }
-                }
-            }
-        }
-    }
+fn cube_from_instr(instr: &Instr) -> Cube {
+    Cube::new([
+        instr.x0..(instr.x1 + 1),
+        instr.y0..(instr.y1 + 1),
+        instr.z0..(instr.z1 + 1),
+    ])
 }
 
 fn execute(instructions: &[Instr], world: Cube) -> usize {
-    let mut cubes = vec![world];
+    let mut active = BoxSet::new();
 
     for instr in instructions {
-        for cube in take(&mut cubes) {
-            cube.split(&Cube::from_instr(instr), &mut cubes);
-        }
-    }
+        let cube = match cube_from_instr(instr).intersection(&world) {
+            Some(cube) => cube,
+            None => continue,
+        };
 
-    sum(map(cubes, |cube| {
-        for instr in rev(instructions) {
-            if cube.intersects(&Cube::from_instr(instr)) {
-                return (instr.action == "on") as usize * cube.volume() as usize;
-            }
+        if instr.action == "on" {
+            active.insert(cube);
+        } else {
+            active.remove(&cube);
         }
+    }
 
-        0
-    }))
+    active.volume() as usize
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
     let instr = parse(lines)?;
 
-    println!("part A: {:?}", execute(&instr, Cube::from_bounds(-50, 50)));
+    println!("part A: {:?}", execute(&instr, cube_from_bounds(-50, 50)));
     println!(
         "part B: {:?}",
-        execute(&instr, Cube::from_bounds(-500000, 500000))
+        execute(&instr, cube_from_bounds(-500000, 500000))
     );
 
     Ok(())
@@ -177,7 +135,7 @@ mod tests {
         ];
 
         assert_eq!(
-            execute(&parse(&lines).unwrap(), Cube::from_bounds(-50, 50)),
+            execute(&parse(&lines).unwrap(), cube_from_bounds(-50, 50)),
             39
         );
 
@@ -207,7 +165,7 @@ mod tests {
         ];
 
         assert_eq!(
-            execute(&parse(&lines).unwrap(), Cube::from_bounds(-50, 50)),
+            execute(&parse(&lines).unwrap(), cube_from_bounds(-50, 50)),
             590784
         );
     }
@@ -278,7 +236,7 @@ mod tests {
         ];
 
         assert_eq!(
-            execute(&parse(&lines).unwrap(), Cube::from_bounds(-500000, 500000)),
+            execute(&parse(&lines).unwrap(), cube_from_bounds(-500000, 500000)),
             2758514936282235
         );
     }
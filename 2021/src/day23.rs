@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, ParseError};
 use binary_heap_plus::BinaryHeap;
 use std::cmp::Reverse;
 use std::collections::hash_map::Entry;
@@ -19,6 +20,13 @@ fn parse<const N: usize>(lines: Lines) -> Result<State<N>>
 where
     [[Option<Amphi>; 11]; N]: Default,
 {
+    let block = Block::new(lines);
+    if block.len() != N + 2 {
+        return Err(block
+            .error(0, format_args!("expected {} lines", N + 2))
+            .into());
+    }
+
     let mut state = <[[Option<Amphi>; 11]; N]>::default();
 
     for (i, line) in enumerate(lines) {
@@ -31,6 +39,15 @@ where
                 _ => continue,
             };
 
+            if !(1..=N).contains(&i) || !(1..=11).contains(&j) {
+                let error = block.error(i, format_args!("unexpected {:?}", c));
+                return Err(ParseError {
+                    column: j + 1,
+                    ..error
+                }
+                .into());
+            }
+
             state[i - 1][j - 1] = Some(a);
         }
     }
@@ -73,7 +90,7 @@ fn print_state<const N: usize>(state: &State<N>) {
     println!();
 }
 
-fn solve<const N: usize>(initial_state: State<N>) -> usize {
+fn solve<const N: usize>(initial_state: State<N>) -> Result<usize> {
     const HALLWAYS_SPOTS: [usize; 7] = [0, 1, 3, 5, 7, 9, 10];
 
     let mut previous = HashMap::<State<N>, State<N>>::default();
@@ -146,7 +163,7 @@ fn solve<const N: usize>(initial_state: State<N>) -> usize {
                 print_state(&x);
             }
 
-            return cost;
+            return Ok(cost);
         }
 
         let mut moved_into = false;
@@ -198,12 +215,12 @@ fn solve<const N: usize>(initial_state: State<N>) -> usize {
         }
     }
 
-    panic!("no solution found!");
+    bail!("no solution found!");
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
     let state = parse::<3>(lines)?;
-    println!("part A: {}", solve(state));
+    println!("part A: {}", solve(state)?);
 
     let state = parse::<5>(&[
         lines[0],
@@ -214,7 +231,7 @@ pub(crate) fn run(lines: Lines) -> Result {
         lines[3],
         lines[4],
     ])?;
-    println!("part B: {}", solve(state));
+    println!("part B: {}", solve(state)?);
 
     Ok(())
 }
@@ -233,7 +250,7 @@ mod tests {
             "  #########",
         ];
 
-        assert_eq!(solve(parse::<3>(&lines).unwrap()), 12521);
+        assert_eq!(solve(parse::<3>(&lines).unwrap()).unwrap(), 12521);
     }
 
     #[test]
@@ -248,6 +265,6 @@ mod tests {
             "  #########",
         ];
 
-        assert_eq!(solve(parse::<5>(&lines).unwrap()), 44169);
+        assert_eq!(solve(parse::<5>(&lines).unwrap()).unwrap(), 44169);
     }
 }
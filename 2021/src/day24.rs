@@ -1,5 +1,5 @@
 use crate::common::*;
-use std::collections::VecDeque;
+use std::fmt::{self, Display};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 #[repr(u8)]
@@ -26,6 +26,79 @@ enum Expr {
     Div(Var, Arg),
     Mod(Var, Arg),
     Eq(Var, Arg),
+    Set(Var, Arg),
+}
+
+impl Expr {
+    fn operands(&self) -> Option<(Var, Arg)> {
+        use Expr::*;
+
+        match *self {
+            Input(_) => None,
+            Add(a, b) | Mul(a, b) | Div(a, b) | Mod(a, b) | Eq(a, b) | Set(a, b) => Some((a, b)),
+        }
+    }
+
+    /// The value of the register for which this instruction copies its
+    /// argument, i.e., 0 for `add` and 1 for `mul`.
+    fn identity(&self) -> Num {
+        match self {
+            Expr::Mul(..) => 1,
+            _ => 0,
+        }
+    }
+
+    fn with_arg(&self, arg: Arg) -> Self {
+        use Expr::*;
+
+        match *self {
+            Input(v) => Input(v),
+            Add(a, _) => Add(a, arg),
+            Mul(a, _) => Mul(a, arg),
+            Div(a, _) => Div(a, arg),
+            Mod(a, _) => Mod(a, arg),
+            Eq(a, _) => Eq(a, arg),
+            Set(a, _) => Set(a, arg),
+        }
+    }
+}
+
+impl Display for Var {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Var::W => "w",
+            Var::X => "x",
+            Var::Y => "y",
+            Var::Z => "z",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl Display for Arg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Arg::Var(v) => write!(f, "{}", v),
+            Arg::Const(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Expr::*;
+
+        match self {
+            Input(v) => write!(f, "inp {}", v),
+            Add(a, b) => write!(f, "add {} {}", a, b),
+            Mul(a, b) => write!(f, "mul {} {}", a, b),
+            Div(a, b) => write!(f, "div {} {}", a, b),
+            Mod(a, b) => write!(f, "mod {} {}", a, b),
+            Eq(a, b) => write!(f, "eql {} {}", a, b),
+            Set(a, b) => write!(f, "set {} {}", a, b),
+        }
+    }
 }
 
 fn parse_expr(line: &str) -> Result<Expr> {
@@ -70,6 +143,7 @@ fn parse_expr(line: &str) -> Result<Expr> {
         "div" => Div(lhs, rhs),
         "mod" => Mod(lhs, rhs),
         "eql" => Eq(lhs, rhs),
+        "set" => Set(lhs, rhs),
         _ => bail!("invalid input: unknown opcode: {:?}", op),
     })
 }
@@ -79,94 +153,762 @@ fn parse(lines: Lines) -> Result<Vec<Expr>> {
         .iter()
         .map(|l| l.trim())
         .filter(|l| !l.is_empty())
-        .map(|l| parse_expr(l))
+        .map(parse_expr)
         .collect()
 }
 
-fn reorder_instructions(instr: &[Expr]) -> Vec<Expr> {
-    const VARS: [Var; 4] = [Var::W, Var::X, Var::Y, Var::Z];
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+struct Interval {
+    lo: Num,
+    hi: Num,
+}
+
+impl Interval {
+    const DIGIT: Interval = Interval { lo: 1, hi: 9 };
+    const FULL: Interval = Interval {
+        lo: Num::MIN,
+        hi: Num::MAX,
+    };
+
+    fn point(v: Num) -> Self {
+        Self { lo: v, hi: v }
+    }
+
+    fn from_values(values: impl IntoIterator<Item = Num>) -> Self {
+        let (lo, hi) = values.into_iter().minmax().into_option().unwrap_or((0, 0));
+        Self { lo, hi }
+    }
+
+    fn contains(&self, v: Num) -> bool {
+        self.lo <= v && v <= self.hi
+    }
+
+    fn as_const(&self) -> Option<Num> {
+        (self.lo == self.hi).then_some(self.lo)
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        Self {
+            lo: Num::min(self.lo, other.lo),
+            hi: Num::max(self.hi, other.hi),
+        }
+    }
+
+    // A result that does not fit in a `Num` could be anything, so arithmetic
+    // that overflows gives the full range.
+    fn add(&self, other: &Self) -> Self {
+        match (self.lo.checked_add(other.lo), self.hi.checked_add(other.hi)) {
+            (Some(lo), Some(hi)) => Self { lo, hi },
+            _ => Self::FULL,
+        }
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        [self.lo, self.hi]
+            .into_iter()
+            .cartesian_product([other.lo, other.hi])
+            .map(|(a, b)| a.checked_mul(b))
+            .collect::<Option<Vec<_>>>()
+            .map_or(Self::FULL, Self::from_values)
+    }
+
+    fn div(&self, other: &Self) -> Self {
+        // Division by zero is not allowed, so only the nonzero parts of
+        // the divisor are considered. Truncating division is monotonic in
+        // both arguments on each side of zero, so the corners suffice.
+        let corners = |b: Interval| {
+            [self.lo, self.hi]
+                .into_iter()
+                .cartesian_product([b.lo, b.hi])
+                .map(|(a, b)| a.saturating_div(b))
+        };
+
+        match (other.lo < 0, other.hi > 0) {
+            (true, true) => {
+                let neg = Self::from_values(corners(Self {
+                    lo: other.lo,
+                    hi: -1,
+                }));
+                let pos = Self::from_values(corners(Self {
+                    lo: 1,
+                    hi: other.hi,
+                }));
+                neg.union(&pos)
+            }
+            (true, false) => Self::from_values(corners(Self {
+                lo: other.lo,
+                hi: Num::min(other.hi, -1),
+            })),
+            (false, true) => Self::from_values(corners(Self {
+                lo: Num::max(other.lo, 1),
+                hi: other.hi,
+            })),
+            (false, false) => *self,
+        }
+    }
+
+    fn rem(&self, other: &Self) -> Self {
+        let m = Num::max(other.lo.saturating_abs(), other.hi.saturating_abs());
+        if m == 0 {
+            return *self;
+        }
+
+        if let (Some(a), Some(b)) = (self.as_const(), other.as_const()) {
+            return Self::point(a.wrapping_rem(b));
+        }
+
+        if self.lo >= 0 && self.hi < Num::min(other.lo.saturating_abs(), m) && other.lo > 0 {
+            return *self;
+        }
+
+        Self {
+            lo: if self.lo >= 0 {
+                0
+            } else {
+                Num::max(self.lo, 1 - m)
+            },
+            hi: if self.hi <= 0 {
+                0
+            } else {
+                Num::min(self.hi, m - 1)
+            },
+        }
+    }
+
+    fn eq(&self, other: &Self) -> Self {
+        if self.hi < other.lo || other.hi < self.lo {
+            Self::point(0)
+        } else if self.as_const().is_some() && self == other {
+            Self::point(1)
+        } else {
+            Self { lo: 0, hi: 1 }
+        }
+    }
+}
+
+fn arg_range(arg: Arg, ranges: &[Interval; 4]) -> Interval {
+    match arg {
+        Arg::Var(v) => ranges[v as usize],
+        Arg::Const(c) => Interval::point(c),
+    }
+}
+
+/// Abstract interpretation of a single instruction: updates the range of the
+/// register it writes. `input` gives the range of the next input digit.
+fn step_range(line: Expr, ranges: &mut [Interval; 4], input: Interval) {
+    use Expr::*;
+
+    let (out, arg) = match line.operands() {
+        Some(ops) => ops,
+        None => {
+            if let Input(v) = line {
+                ranges[v as usize] = input;
+            }
+
+            return;
+        }
+    };
+
+    let lhs = ranges[out as usize];
+    let rhs = arg_range(arg, ranges);
+
+    ranges[out as usize] = match line {
+        Add(..) => lhs.add(&rhs),
+        Mul(..) => lhs.mul(&rhs),
+        Div(..) => lhs.div(&rhs),
+        Mod(..) => lhs.rem(&rhs),
+        Eq(..) => lhs.eq(&rhs),
+        Set(..) => rhs,
+        Input(_) => unreachable!(),
+    };
+}
+
+/// Computes the range of every register at the end of the program, given a
+/// partial assignment of the inputs. Unassigned inputs can be any digit.
+fn analyse_ranges(instr: &[Expr], inputs: &[Option<Num>]) -> [Interval; 4] {
+    let mut ranges = [Interval::point(0); 4];
+    let mut index = 0;
+
+    for &line in instr {
+        let input = match inputs.get(index) {
+            Some(&Some(d)) => Interval::point(d),
+            _ => Interval::DIGIT,
+        };
+
+        if let Expr::Input(_) = line {
+            index += 1;
+        }
+
+        step_range(line, &mut ranges, input);
+    }
+
+    ranges
+}
+
+/// Replaces every instruction whose result is known by a `set` of that
+/// constant, substitutes registers of known value by constants, and removes
+/// instructions that do not change the value of their register.
+fn fold_constants(instr: &[Expr]) -> Vec<Expr> {
+    use Expr::*;
+
+    let mut ranges = [Interval::point(0); 4];
+    let mut output = vec![];
+
+    for &line in instr {
+        let line = match line.operands() {
+            Some((_, Arg::Var(w))) => match ranges[w as usize].as_const() {
+                Some(c) => line.with_arg(Arg::Const(c)),
+                None => line,
+            },
+            _ => line,
+        };
+
+        let (out, arg) = match line.operands() {
+            Some(ops) => ops,
+            None => {
+                step_range(line, &mut ranges, Interval::DIGIT);
+                output.push(line);
+                continue;
+            }
+        };
+
+        let before = ranges[out as usize];
+        step_range(line, &mut ranges, Interval::DIGIT);
+        let after = ranges[out as usize];
+
+        let is_noop = match (line, arg) {
+            _ if before.as_const().is_some() && before == after => true,
+            (Add(..), Arg::Const(0)) | (Mul(..), Arg::Const(1)) | (Div(..), Arg::Const(1)) => true,
+            (Mod(..), Arg::Const(m)) => m > 0 && before.lo >= 0 && before.hi < m,
+            (Set(v, Arg::Var(w)), _) => v == w,
+            _ => false,
+        };
+
+        if is_noop {
+            continue;
+        }
+
+        output.push(match (line, after.as_const()) {
+            (_, Some(c)) => Set(out, Arg::Const(c)),
+            (Add(..), _) | (Mul(..), _) if before == Interval::point(line.identity()) => {
+                Set(out, arg)
+            }
+            _ => line,
+        });
+    }
+
+    output
+}
+
+/// Replaces reads of registers that are known copies of another register by
+/// reads of the original register.
+fn propagate_copies(instr: &[Expr]) -> Vec<Expr> {
+    let mut copy_of: [Option<Var>; 4] = [None; 4];
+    let mut output = vec![];
+
+    for &line in instr {
+        let line = match line.operands() {
+            Some((_, Arg::Var(w))) => match copy_of[w as usize] {
+                Some(u) => line.with_arg(Arg::Var(u)),
+                None => line,
+            },
+            _ => line,
+        };
+
+        let out = match line {
+            Expr::Input(v) => v,
+            _ => line.operands().unwrap().0,
+        };
+
+        for v in &mut copy_of {
+            if *v == Some(out) {
+                *v = None;
+            }
+        }
+
+        copy_of[out as usize] = match line {
+            Expr::Set(v, Arg::Var(w)) if v != w => Some(w),
+            _ => None,
+        };
+
+        output.push(line);
+    }
+
+    output
+}
 
+/// Backward liveness pass that removes instructions whose result is never
+/// read. Only `z` is live at the end of the program. Input instructions are
+/// always kept since they consume a digit.
+fn eliminate_dead_code(instr: &[Expr]) -> Vec<Expr> {
     use Expr::*;
-    let mut lines = VecDeque::new();
-    let mut active = [true; 4];
+
+    let mut live = [false, false, false, true];
+    let mut output = vec![];
 
     for &line in rev(instr) {
-        for v in VARS {
-            let is_read = match line {
-                Add(a, _) | Mul(a, _) | Div(a, _) | Mod(a, _) | Eq(a, _) if a == v => true,
-                Add(_, a) | Mul(_, a) | Div(_, a) | Mod(_, a) | Eq(_, a) if a == Arg::Var(v) => {
-                    true
-                }
-                _ => false,
-            };
+        let (out, arg) = match line {
+            Input(v) => {
+                live[v as usize] = false;
+                output.push(line);
+                continue;
+            }
+            _ => line.operands().unwrap(),
+        };
+
+        if !live[out as usize] {
+            continue;
+        }
+
+        if let Set(..) = line {
+            live[out as usize] = false;
+        }
+
+        if let Arg::Var(w) = arg {
+            live[w as usize] = true;
+        }
+
+        output.push(line);
+    }
+
+    output.reverse();
+    output
+}
+
+fn optimize(instr: &[Expr]) -> Vec<Expr> {
+    let mut instr = instr.to_vec();
 
-            if is_read && !active[v as usize] {
-                lines.push_front(Mul(v, Arg::Const(0)));
-                active[v as usize] = true;
+    loop {
+        let next = fold_constants(&instr);
+        let next = propagate_copies(&next);
+        let next = eliminate_dead_code(&next);
+
+        if next == instr {
+            return instr;
+        }
+
+        instr = next;
+    }
+}
+
+fn print_program(instr: &[Expr]) {
+    for line in instr {
+        println!("{}", line);
+    }
+}
+
+/// Depth-first search over the digits in the given order, returning the
+/// first model number for which `z` can be zero. Every prefix is checked by
+/// interval analysis of the whole program, so impossible prefixes are pruned
+/// as soon as they are known.
+fn search_model_number(instr: &[Expr], digits: &[Num]) -> Option<Num> {
+    fn search(instr: &[Expr], inputs: &mut Vec<Option<Num>>, index: usize, digits: &[Num]) -> bool {
+        if index == inputs.len() {
+            return true;
+        }
+
+        for &d in digits {
+            inputs[index] = Some(d);
+
+            if analyse_ranges(instr, inputs)[Var::Z as usize].contains(0)
+                && search(instr, inputs, index + 1, digits)
+            {
+                return true;
             }
         }
 
-        lines.push_front(line);
+        inputs[index] = None;
+        false
+    }
+
+    let num_inputs = instr.iter().filter(|e| matches!(e, Expr::Input(_))).count();
+    let mut inputs = vec![None; num_inputs];
+
+    if !search(instr, &mut inputs, 0, digits) {
+        return None;
+    }
+
+    Some(inputs.into_iter().fold(0, |n, d| 10 * n + d.unwrap()))
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+struct TermId(usize);
 
-        for v in VARS {
-            if matches!(line, Input(w) | Mul(w, Arg::Const(0)) if w == v) {
-                active[v as usize] = false;
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+enum Term {
+    Const(Num),
+    Input(usize),
+    Add(TermId, TermId),
+    Mul(TermId, TermId),
+    Div(TermId, TermId),
+    Mod(TermId, TermId),
+    Eq(TermId, TermId),
+}
+
+/// Hash-consed arena of symbolic terms. Terms are only ever appended, and
+/// the children of a term are always created before the term itself, so the
+/// arena is in topological order.
+#[derive(Default)]
+struct Symbolic {
+    terms: Vec<(Term, Interval)>,
+    lookup: HashMap<Term, TermId>,
+}
+
+impl Symbolic {
+    fn term(&self, id: TermId) -> Term {
+        self.terms[id.0].0
+    }
+
+    fn range(&self, id: TermId) -> Interval {
+        self.terms[id.0].1
+    }
+
+    fn as_const(&self, id: TermId) -> Option<Num> {
+        self.range(id).as_const()
+    }
+
+    fn intern(&mut self, term: Term) -> TermId {
+        if let Some(&id) = self.lookup.get(&term) {
+            return id;
+        }
+
+        let range = self.eval_term(term, |id| self.range(id), |_| Interval::DIGIT);
+        let id = match range.as_const() {
+            Some(c) if !matches!(term, Term::Const(_)) => self.constant(c),
+            _ => {
+                self.terms.push((term, range));
+                TermId(self.terms.len() - 1)
             }
+        };
+
+        self.lookup.insert(term, id);
+        id
+    }
+
+    fn eval_term<F, G>(&self, term: Term, child: F, input: G) -> Interval
+    where
+        F: Fn(TermId) -> Interval,
+        G: Fn(usize) -> Interval,
+    {
+        use Term::*;
+
+        match term {
+            Const(c) => Interval::point(c),
+            Input(i) => input(i),
+            Add(a, b) => child(a).add(&child(b)),
+            Mul(a, b) => child(a).mul(&child(b)),
+            Div(a, b) => child(a).div(&child(b)),
+            Mod(a, b) => child(a).rem(&child(b)),
+            Eq(a, b) => child(a).eq(&child(b)),
+        }
+    }
+
+    fn constant(&mut self, c: Num) -> TermId {
+        self.intern(Term::Const(c))
+    }
+
+    fn input(&mut self, index: usize) -> TermId {
+        self.intern(Term::Input(index))
+    }
+
+    fn add(&mut self, a: TermId, b: TermId) -> TermId {
+        use Term::*;
+
+        match (self.term(a), self.term(b)) {
+            // Constants are only folded if the result does not overflow,
+            // otherwise the term is kept and its range becomes unknown.
+            (Const(x), Const(y)) => match x.checked_add(y) {
+                Some(c) => self.constant(c),
+                None => self.intern(Add(a, b)),
+            },
+            (Const(_), _) => self.add(b, a),
+            (_, Const(0)) => a,
+            (Add(x, y), Const(c)) if self.as_const(y).is_some() => {
+                match self.as_const(y).unwrap().checked_add(c) {
+                    Some(c) => {
+                        let c = self.constant(c);
+                        self.add(x, c)
+                    }
+                    None => self.intern(Add(a, b)),
+                }
+            }
+            _ => self.intern(Add(a, b)),
+        }
+    }
+
+    fn mul(&mut self, a: TermId, b: TermId) -> TermId {
+        use Term::*;
+
+        match (self.term(a), self.term(b)) {
+            (Const(x), Const(y)) => match x.checked_mul(y) {
+                Some(c) => self.constant(c),
+                None => self.intern(Mul(a, b)),
+            },
+            (Const(_), _) => self.mul(b, a),
+            (_, Const(0)) => b,
+            (_, Const(1)) => a,
+            _ => self.intern(Mul(a, b)),
+        }
+    }
+
+    /// Splits `a` into `(q, r)` such that `a == q * m + r`, with both `q`
+    /// and `r` nonnegative. This is the shape of the stack-like values built
+    /// by MONAD-style programs.
+    fn split_multiple(&self, a: TermId, m: Num) -> Option<(TermId, Option<TermId>)> {
+        let is_multiple = |t: TermId| match self.term(t) {
+            Term::Mul(q, c) if self.as_const(c) == Some(m) && self.range(q).lo >= 0 => Some(q),
+            _ => None,
+        };
+
+        if let Some(q) = is_multiple(a) {
+            return Some((q, None));
+        }
+
+        match self.term(a) {
+            Term::Add(x, r) if self.range(r).lo >= 0 => is_multiple(x).map(|q| (q, Some(r))),
+            _ => None,
+        }
+    }
+
+    fn div(&mut self, a: TermId, b: TermId) -> TermId {
+        use Term::*;
+
+        let range = self.range(a);
+
+        match self.term(b) {
+            Const(1) => a,
+            Const(m) if m > 0 && range.lo >= 0 && range.hi < m => self.constant(0),
+            Const(m) if m > 0 => match self.split_multiple(a, m) {
+                Some((q, None)) => q,
+                Some((q, Some(r))) => {
+                    let r = self.div(r, b);
+                    self.add(q, r)
+                }
+                None => self.intern(Div(a, b)),
+            },
+            _ => self.intern(Div(a, b)),
+        }
+    }
+
+    fn rem(&mut self, a: TermId, b: TermId) -> TermId {
+        use Term::*;
+
+        let range = self.range(a);
+
+        match self.term(b) {
+            Const(1) => self.constant(0),
+            Const(m) if m > 0 && range.lo >= 0 && range.hi < m => a,
+            Const(m) if m > 0 => match self.split_multiple(a, m) {
+                Some((_, None)) => self.constant(0),
+                Some((_, Some(r))) => self.rem(r, b),
+                None => self.intern(Mod(a, b)),
+            },
+            _ => self.intern(Mod(a, b)),
+        }
+    }
+
+    fn eq(&mut self, a: TermId, b: TermId) -> TermId {
+        if a == b {
+            return self.constant(1);
         }
+
+        let (a, b) = if self.as_const(a).is_some() {
+            (b, a)
+        } else {
+            (a, b)
+        };
+
+        self.intern(Term::Eq(a, b))
     }
 
-    lines.into()
+    /// Evaluates the ranges of all terms given a partial assignment of the
+    /// inputs. Unassigned inputs are assumed to be any digit.
+    fn eval_ranges(&self, inputs: &[Option<Num>]) -> Vec<Interval> {
+        let mut ranges: Vec<Interval> = Vec::with_capacity(self.terms.len());
+
+        for &(term, _) in &self.terms {
+            let range = self.eval_term(
+                term,
+                |id| ranges[id.0],
+                |i| match inputs.get(i) {
+                    Some(&Some(v)) => Interval::point(v),
+                    _ => Interval::DIGIT,
+                },
+            );
+
+            ranges.push(range);
+        }
+
+        ranges
+    }
+
+    #[cfg(test)]
+    fn format(&self, id: TermId) -> String {
+        use Term::*;
+
+        match self.term(id) {
+            Const(c) => c.to_string(),
+            Input(i) => format!("d{}", i),
+            Add(a, b) => format!("({} + {})", self.format(a), self.format(b)),
+            Mul(a, b) => format!("({} * {})", self.format(a), self.format(b)),
+            Div(a, b) => format!("({} / {})", self.format(a), self.format(b)),
+            Mod(a, b) => format!("({} % {})", self.format(a), self.format(b)),
+            Eq(a, b) => format!("({} == {})", self.format(a), self.format(b)),
+        }
+    }
 }
 
-fn execute<A>(instr: &[Expr], arbiter: A) -> Num
-where
-    A: Fn(Num, Num) -> Num,
-{
+/// One feasible execution path through the program. Each `eql` whose
+/// outcome depends on the inputs splits a path in two, and the chosen
+/// outcome is recorded as a constraint.
+#[derive(Clone, Debug)]
+struct Path {
+    state: [TermId; 4],
+    constraints: Vec<(TermId, bool)>,
+}
+
+const MAX_PATHS: usize = 1 << 12;
+
+/// Returns `None` if the program branches into more than `MAX_PATHS` paths.
+fn execute_symbolic(sym: &mut Symbolic, instr: &[Expr]) -> Option<(Vec<Path>, usize)> {
     use Expr::*;
 
-    fn evolve<'a>(
-        instr: &'a [Expr],
-        iter: impl IntoIterator<Item = (State, Num)> + 'a,
-    ) -> impl Iterator<Item = (State, Num)> + 'a {
-        iter.into_iter()
-            .cartesian_product(1..=9)
-            .map(|((state, num), i)| {
-                let new_state = eval(instr, &[i], state);
-                let new_num = 10 * num + i;
-                (new_state, new_num)
-            })
-    }
-
-    let (prelude, mut instr) = match instr.iter().position(|e| matches!(e, Input(_))) {
-        Some(i) => instr.split_at(i),
-        None => (instr, &[][..]),
-    };
+    let zero = sym.constant(0);
+    let mut num_inputs = 0;
+    let mut paths = vec![Path {
+        state: [zero; 4],
+        constraints: vec![],
+    }];
 
-    let mut new_states = HashMap::default();
-    let mut states = HashMap::default();
-    let initial_state = eval(prelude, &[], default());
-    states.insert(initial_state, 0);
+    for &line in instr {
+        let mut new_paths = vec![];
 
-    while let Some(split) = instr.iter().skip(1).position(|e| matches!(e, Input(_))) {
-        let (head, rest) = instr.split_at(split + 1);
-        instr = rest;
+        if let Input(v) = line {
+            let input = sym.input(num_inputs);
+            num_inputs += 1;
 
-        new_states.clear();
+            for path in &mut paths {
+                path.state[v as usize] = input;
+            }
 
-        for (state, num) in evolve(head, states.drain()) {
-            new_states
-                .entry(state)
-                .and_modify(|v| *v = arbiter(*v, num))
-                .or_insert(num);
+            continue;
         }
 
-        swap(&mut states, &mut new_states);
+        for mut path in paths.drain(..) {
+            let (out, arg) = line.operands().unwrap();
+
+            let lhs = path.state[out as usize];
+            let rhs = match arg {
+                Arg::Var(i) => path.state[i as usize],
+                Arg::Const(c) => sym.constant(c),
+            };
+
+            let result = match line {
+                Add(..) => sym.add(lhs, rhs),
+                Mul(..) => sym.mul(lhs, rhs),
+                Div(..) => sym.div(lhs, rhs),
+                Mod(..) => sym.rem(lhs, rhs),
+                Eq(..) => sym.eq(lhs, rhs),
+                Set(..) => rhs,
+                Input(_) => unreachable!(),
+            };
+
+            if matches!(line, Eq(..)) && sym.as_const(result).is_none() {
+                if new_paths.len() + 2 > MAX_PATHS {
+                    return None;
+                }
+
+                for outcome in [true, false] {
+                    let mut path = path.clone();
+                    path.state[out as usize] = sym.constant(outcome as Num);
+                    path.constraints.push((result, outcome));
+                    new_paths.push(path);
+                }
+            } else {
+                path.state[out as usize] = result;
+                new_paths.push(path);
+            }
+        }
+
+        paths = new_paths;
     }
 
-    evolve(instr, states.drain())
-        .filter_map(|(state, num)| (state[Var::Z as usize] == 0).then(|| num))
-        .reduce(arbiter)
-        .unwrap()
+    paths.retain(|p| sym.range(p.state[Var::Z as usize]).contains(0));
+    Some((paths, num_inputs))
+}
+
+/// Finds the assignment of digits along the given path that is largest (or
+/// smallest, if `digits` is ascending) in lexicographic order. Branches are
+/// pruned by evaluating the ranges of all constraints under the partial
+/// assignment.
+fn solve_path(sym: &Symbolic, path: &Path, num_inputs: usize, digits: &[Num]) -> Option<Num> {
+    fn is_feasible(sym: &Symbolic, path: &Path, inputs: &[Option<Num>]) -> bool {
+        let ranges = sym.eval_ranges(inputs);
+
+        ranges[path.state[Var::Z as usize].0].contains(0)
+            && all(&path.constraints, |&(c, b)| ranges[c.0].contains(b as Num))
+    }
+
+    fn search(
+        sym: &Symbolic,
+        path: &Path,
+        inputs: &mut Vec<Option<Num>>,
+        index: usize,
+        digits: &[Num],
+    ) -> bool {
+        if index == inputs.len() {
+            return true;
+        }
+
+        for &d in digits {
+            inputs[index] = Some(d);
+
+            if is_feasible(sym, path, inputs) && search(sym, path, inputs, index + 1, digits) {
+                return true;
+            }
+        }
+
+        inputs[index] = None;
+        false
+    }
+
+    let mut inputs = vec![None; num_inputs];
+    if !search(sym, path, &mut inputs, 0, digits) {
+        return None;
+    }
+
+    Some(inputs.into_iter().fold(0, |n, d| 10 * n + d.unwrap()))
+}
+
+fn find_model_number(instr: &[Expr], largest: bool) -> Result<Num> {
+    let digits = if largest {
+        (1..=9).rev().collect_vec()
+    } else {
+        (1..=9).collect_vec()
+    };
+
+    // Fall back to a pruned brute-force search if symbolic execution would
+    // branch into too many paths.
+    let mut sym = Symbolic::default();
+    let (paths, num_inputs) = match execute_symbolic(&mut sym, instr) {
+        Some(result) => result,
+        None => {
+            return search_model_number(instr, &digits)
+                .ok_or_else(|| anyhow!("no valid model number exists"))
+        }
+    };
+
+    let numbers = paths
+        .iter()
+        .filter_map(|path| solve_path(&sym, path, num_inputs, &digits));
+
+    let result = if largest {
+        numbers.max()
+    } else {
+        numbers.min()
+    };
+
+    result.ok_or_else(|| anyhow!("no valid model number exists"))
 }
 
 #[inline(always)]
@@ -198,6 +940,7 @@ fn eval(lines: &[Expr], mut inputs: &[Num], mut state: State) -> State {
             Div(a, b) => evolve(a, b, |x, y| x / y, &mut state),
             Mod(a, b) => evolve(a, b, |x, y| x % y, &mut state),
             Eq(a, b) => evolve(a, b, |x, y| (x == y) as _, &mut state),
+            Set(a, b) => evolve(a, b, |_, y| y, &mut state),
         }
     }
 
@@ -206,10 +949,27 @@ fn eval(lines: &[Expr], mut inputs: &[Num], mut state: State) -> State {
 
 pub(crate) fn run(lines: Lines) -> Result {
     let lines = parse(lines)?;
-    let lines = reorder_instructions(&lines);
+    let optimized = optimize(&lines);
+
+    println!(
+        "optimized program ({} -> {} instructions):",
+        lines.len(),
+        optimized.len()
+    );
+    print_program(&optimized);
+
+    for (part, largest) in [("A", true), ("B", false)] {
+        let number = find_model_number(&optimized, largest)?;
+        let digits = number
+            .to_string()
+            .bytes()
+            .map(|b| (b - b'0') as Num)
+            .collect_vec();
+        ensure!(eval(&lines, &digits, default())[Var::Z as usize] == 0);
+
+        println!("part {}: {:?}", part, number);
+    }
 
-    println!("part A: {:?}", execute(&lines, |a, b| Num::max(a, b)));
-    println!("part B: {:?}", execute(&lines, |a, b| Num::min(a, b)));
     Ok(())
 }
 
@@ -217,13 +977,167 @@ pub(crate) fn run(lines: Lines) -> Result {
 mod tests {
     use super::*;
 
+    // A MONAD-shaped program on four digits: the first two digits are
+    // pushed onto a base-26 stack and the last two must pop them again.
+    const MONAD: &[(Num, Num, Num)] = &[(1, 12, 4), (1, 11, 7), (26, -5, 3), (26, -9, 2)];
+
+    fn monad_program() -> Vec<Expr> {
+        let mut lines = vec![];
+
+        for &(div, check, offset) in MONAD {
+            lines.extend([
+                "inp w".to_string(),
+                "mul x 0".to_string(),
+                "add x z".to_string(),
+                "mod x 26".to_string(),
+                format!("div z {}", div),
+                format!("add x {}", check),
+                "eql x w".to_string(),
+                "eql x 0".to_string(),
+                "mul y 0".to_string(),
+                "add y 25".to_string(),
+                "mul y x".to_string(),
+                "add y 1".to_string(),
+                "mul z y".to_string(),
+                "mul y 0".to_string(),
+                "add y w".to_string(),
+                format!("add y {}", offset),
+                "mul y x".to_string(),
+                "add z y".to_string(),
+            ]);
+        }
+
+        parse(&lines.iter().map(|s| s.as_str()).collect_vec()).unwrap()
+    }
+
+    fn brute_force(instr: &[Expr], n: usize) -> Vec<Num> {
+        (0..n)
+            .map(|_| 1..=9)
+            .multi_cartesian_product()
+            .filter(|digits| eval(instr, digits, default())[Var::Z as usize] == 0)
+            .map(|digits| digits.into_iter().fold(0, |n, d| 10 * n + d))
+            .collect()
+    }
+
     #[test]
     fn test_a() {
-        //
+        let instr = monad_program();
+        let expected = brute_force(&instr, 4);
+
+        assert_eq!(
+            find_model_number(&instr, true).unwrap(),
+            *expected.iter().max().unwrap()
+        );
     }
 
     #[test]
     fn test_b() {
-        //
+        let instr = monad_program();
+        let expected = brute_force(&instr, 4);
+
+        assert_eq!(
+            find_model_number(&instr, false).unwrap(),
+            *expected.iter().min().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_constraints() {
+        let instr = parse(&["inp x", "add x 3", "inp w", "eql x w", "eql x 0", "add z x"]).unwrap();
+
+        let mut sym = Symbolic::default();
+        let (paths, num_inputs) = execute_symbolic(&mut sym, &instr).unwrap();
+        assert_eq!(num_inputs, 2);
+        assert_eq!(paths.len(), 1);
+
+        let constraints = paths[0]
+            .constraints
+            .iter()
+            .map(|&(c, b)| (sym.format(c), b))
+            .collect_vec();
+        assert_eq!(constraints, [("((d0 + 3) == d1)".to_string(), true)]);
+
+        assert_eq!(find_model_number(&instr, true).unwrap(), 69);
+        assert_eq!(find_model_number(&instr, false).unwrap(), 14);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut sym = Symbolic::default();
+        let max = sym.constant(Num::MAX);
+        let one = sym.constant(1);
+        let two = sym.constant(2);
+
+        let sum = sym.add(max, one);
+        assert_eq!(sym.as_const(sum), None);
+        assert_eq!(sym.range(sum), Interval::FULL);
+
+        let product = sym.mul(max, two);
+        assert_eq!(sym.as_const(product), None);
+
+        let d0 = sym.input(0);
+        let x = sym.add(d0, max);
+        let y = sym.add(x, one);
+        assert_eq!(sym.format(y), "((d0 + 9223372036854775807) + 1)");
+    }
+
+    #[test]
+    fn test_monad_constraints() {
+        let mut sym = Symbolic::default();
+        let (paths, _) = execute_symbolic(&mut sym, &monad_program()).unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(sym.as_const(paths[0].state[Var::Z as usize]), Some(0));
+
+        let constraints = paths[0]
+            .constraints
+            .iter()
+            .map(|&(c, b)| (sym.format(c), b))
+            .collect_vec();
+
+        assert_eq!(
+            constraints,
+            [
+                ("((d1 + 2) == d2)".to_string(), true),
+                ("((d0 + -5) == d3)".to_string(), true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_optimize() {
+        let instr = monad_program();
+        let optimized = optimize(&instr);
+        assert!(optimized.len() < instr.len());
+
+        for digits in (0..4).map(|_| 1..=9).multi_cartesian_product() {
+            assert_eq!(
+                eval(&instr, &digits, default())[Var::Z as usize],
+                eval(&optimized, &digits, default())[Var::Z as usize]
+            );
+        }
+
+        let program = optimized
+            .iter()
+            .take(4)
+            .map(|e| e.to_string())
+            .collect_vec();
+        assert_eq!(program, ["inp w", "set y w", "add y 4", "set z y"]);
+    }
+
+    #[test]
+    fn test_search() {
+        let instr = optimize(&monad_program());
+        let expected = brute_force(&instr, 4);
+
+        let digits = (1..=9).rev().collect_vec();
+        let largest = search_model_number(&instr, &digits);
+        assert_eq!(largest, expected.iter().max().copied());
+
+        let digits = (1..=9).collect_vec();
+        let smallest = search_model_number(&instr, &digits);
+        assert_eq!(smallest, expected.iter().min().copied());
+
+        let ranges = analyse_ranges(&instr, &[Some(9), Some(9)]);
+        assert!(!ranges[Var::Z as usize].contains(0));
     }
 }
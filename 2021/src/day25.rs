@@ -1,53 +1,34 @@
 use crate::common::*;
-use ndarray::{Array2, ArrayView2};
+use advent_common::grid::Grid;
 
-fn parse(lines: Lines) -> Result<Array2<char>> {
-    let height = lines.len();
-    let width = lines[0].len();
-    let mut map = Array2::from_elem((height, width), '.');
-
-    for (i, line) in enumerate(lines) {
-        ensure!(line.len() == width, "invalid input");
-
-        for (j, c) in enumerate(line.chars()) {
-            map[[i, j]] = c;
-        }
-    }
-
-    Ok(map)
+fn parse(lines: Lines) -> Result<Grid<char>> {
+    Ok(Grid::parse(lines)?)
 }
 
-fn evolve(input: ArrayView2<char>) -> Array2<char> {
-    let (height, width) = input.dim();
-    let mut output = input.to_owned();
+fn evolve_herd(input: &Grid<char>, herd: char, delta: [isize; 2]) -> Grid<char> {
+    let mut output = input.clone();
 
-    for i in 0..height {
-        for j in 0..width {
-            if input[[i, j]] == '>' && input[[i, (j + 1) % width]] == '.' {
-                output[[i, j]] = '.';
-                output[[i, (j + 1) % width]] = '>';
-            }
-        }
-    }
-
-    let input = output.clone();
+    for (pos, &c) in input.enumerate() {
+        let next = input.wrapping_offset(pos, delta);
 
-    for i in 0..height {
-        for j in 0..width {
-            if input[[i, j]] == 'v' && input[[(i + 1) % height, j]] == '.' {
-                output[[i, j]] = '.';
-                output[[(i + 1) % height, j]] = 'v';
-            }
+        if c == herd && input[next] == '.' {
+            output[pos] = '.';
+            output[next] = herd;
         }
     }
 
     output
 }
 
-fn evolve_forever(mut current: Array2<char>) -> usize {
+fn evolve(input: &Grid<char>) -> Grid<char> {
+    let output = evolve_herd(input, '>', [1, 0]);
+    evolve_herd(&output, 'v', [0, 1])
+}
+
+fn evolve_forever(mut current: Grid<char>) -> usize {
     for steps in 1.. {
         let prev = current;
-        current = evolve(prev.view());
+        current = evolve(&prev);
 
         if current == prev {
             return steps;
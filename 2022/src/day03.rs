@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::Block;
 use std::collections::HashSet;
 
 fn priority(c: char) -> i32 {
@@ -34,6 +35,8 @@ fn find_common_badge(lines: &[&str]) -> Result<char> {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
+    let lines = Block::new(lines).parse_lines(|c| Ok(c.take_while(|c| c.is_ascii_alphabetic())))?;
+
     let total = lines
         .iter()
         .map(|line| find_common_item(line))
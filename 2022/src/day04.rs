@@ -1,9 +1,7 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
-#[derive(Debug, Deserialize, PartialEq, Recap)]
-#[recap(regex = r#"(?P<a0>[0-9]+)-(?P<a1>[0-9]+),(?P<b0>[0-9]+)-(?P<b1>[0-9]+)"#)]
+#[derive(Debug, PartialEq)]
 struct Line {
     a0: i32,
     a1: i32,
@@ -11,6 +9,18 @@ struct Line {
     b1: i32,
 }
 
+fn parse_line(c: &mut Cursor) -> ParseResult<Line> {
+    let a0 = c.int()?;
+    c.tag("-")?;
+    let a1 = c.int()?;
+    c.tag(",")?;
+    let b0 = c.int()?;
+    c.tag("-")?;
+    let b1 = c.int()?;
+
+    Ok(Line { a0, a1, b0, b1 })
+}
+
 impl Line {
     fn is_fully_contained(&self) -> bool {
         self.a0 >= self.b0 && self.a1 <= self.b1 || self.b0 >= self.a0 && self.b1 <= self.a1
@@ -22,10 +32,7 @@ impl Line {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let lines = lines
-        .iter()
-        .map(|&l| Line::try_from(l))
-        .collect::<Result<Vec<_>, _>>()?;
+    let lines = Block::new(lines).parse_lines(parse_line)?;
 
     let count = lines.iter().filter(|l| l.is_fully_contained()).count();
     println!("part A: {}", count);
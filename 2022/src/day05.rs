@@ -1,35 +1,57 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
+use advent_common::parse::{Block, Cursor, ParseResult, Sections};
 use std::collections::VecDeque;
 
-#[derive(Debug, Deserialize, PartialEq, Recap)]
-#[recap(regex = r#"move (?P<count>[0-9]+) from (?P<src>[1-9]) to (?P<dst>[1-9])"#)]
+#[derive(Debug, PartialEq)]
 struct Instruction {
     count: i32,
     src: usize,
     dst: usize,
 }
 
-fn parse_instructions(lines: &[&str]) -> Result<Vec<Instruction>> {
-    lines
-        .iter()
-        .map(|&l| Instruction::try_from(l).context("invalid line"))
-        .collect()
+fn parse_stack_index(c: &mut Cursor, ncols: usize) -> ParseResult<usize> {
+    let start = *c;
+    let index: usize = c.int()?;
+
+    if !(1..=ncols).contains(&index) {
+        return Err(start.error(format_args!("stack must be between 1 and {}", ncols)));
+    }
+
+    Ok(index)
 }
 
-fn parse_stacks(lines: &[&str], ncols: usize) -> Vec<VecDeque<char>> {
+fn parse_instruction(c: &mut Cursor, ncols: usize) -> ParseResult<Instruction> {
+    c.tag("move ")?;
+    let count = c.int()?;
+    c.tag(" from ")?;
+    let src = parse_stack_index(c, ncols)?;
+    c.tag(" to ")?;
+    let dst = parse_stack_index(c, ncols)?;
+
+    Ok(Instruction { count, src, dst })
+}
+
+fn parse_stacks(block: Block<&str>) -> ParseResult<Vec<VecDeque<char>>> {
+    let (labels, crates) = match block.lines.split_last() {
+        Some(split) => split,
+        None => return Err(block.error(0, "missing stacks")),
+    };
+
+    let ncols = labels.split_whitespace().count();
     let mut queues = vec![VecDeque::new(); ncols];
 
-    for line in lines {
+    for (index, line) in enumerate(crates) {
         for (i, c) in line.char_indices() {
-            if ('A'..='Z').contains(&c) {
-                queues[i / 4].push_front(c);
+            if c.is_ascii_uppercase() {
+                let queue = queues.get_mut(i / 4).ok_or_else(|| {
+                    block.error(index, format_args!("crate {:?} outside of the stacks", c))
+                })?;
+                queue.push_front(c);
             }
         }
     }
 
-    queues
+    Ok(queues)
 }
 
 fn top_crates(queues: &[VecDeque<char>]) -> String {
@@ -40,8 +62,9 @@ fn top_crates(queues: &[VecDeque<char>]) -> String {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let queues = parse_stacks(&lines[..8], 9);
-    let instrs = parse_instructions(&lines[10..])?;
+    let [stacks, instrs] = Sections::new(lines).blocks()?;
+    let queues = parse_stacks(stacks)?;
+    let instrs = instrs.parse_lines(|c| parse_instruction(c, queues.len()))?;
 
     let mut result = queues.clone();
     for instr in &instrs {
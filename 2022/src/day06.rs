@@ -1,7 +1,8 @@
 use crate::common::*;
+use advent_common::parse::Block;
 use std::collections::{HashSet, VecDeque};
 
-fn find_marker_position(line: &str, n: usize) -> usize {
+fn find_marker_position(line: &str, n: usize) -> Option<usize> {
     let mut window = VecDeque::new();
 
     for (index, c) in line.chars().enumerate() {
@@ -16,17 +17,20 @@ fn find_marker_position(line: &str, n: usize) -> usize {
         }
 
         if HashSet::<&char>::from_iter(&window).len() == n {
-            return index + 1;
+            return Some(index + 1);
         }
     }
 
-    panic!("no marker found");
+    None
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let line = lines[0];
-    println!("part A: {}", find_marker_position(line, 4));
-    println!("part B: {}", find_marker_position(line, 14));
+    let line = Block::new(lines).parse_single(|c| Ok(c.take_while(|_| true)))?;
+    let a = find_marker_position(line, 4).context("no packet marker found")?;
+    println!("part A: {}", a);
+
+    let b = find_marker_position(line, 14).context("no message marker found")?;
+    println!("part B: {}", b);
 
     Ok(())
 }
@@ -37,15 +41,21 @@ mod tests {
 
     #[test]
     fn test_a() {
-        assert_eq!(find_marker_position("bvwbjplbgvbhsrlpgdmjqwftvncz", 4), 5);
-        assert_eq!(find_marker_position("nppdvjthqldpwncqszvftbrmjlhg", 4), 6);
+        assert_eq!(
+            find_marker_position("bvwbjplbgvbhsrlpgdmjqwftvncz", 4),
+            Some(5)
+        );
+        assert_eq!(
+            find_marker_position("nppdvjthqldpwncqszvftbrmjlhg", 4),
+            Some(6)
+        );
         assert_eq!(
             find_marker_position("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 4),
-            10
+            Some(10)
         );
         assert_eq!(
             find_marker_position("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 4),
-            11
+            Some(11)
         );
     }
 
@@ -53,17 +63,23 @@ mod tests {
     fn test_b() {
         assert_eq!(
             find_marker_position("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 14),
-            19
+            Some(19)
+        );
+        assert_eq!(
+            find_marker_position("bvwbjplbgvbhsrlpgdmjqwftvncz", 14),
+            Some(23)
+        );
+        assert_eq!(
+            find_marker_position("nppdvjthqldpwncqszvftbrmjlhg", 14),
+            Some(23)
         );
-        assert_eq!(find_marker_position("bvwbjplbgvbhsrlpgdmjqwftvncz", 14), 23);
-        assert_eq!(find_marker_position("nppdvjthqldpwncqszvftbrmjlhg", 14), 23);
         assert_eq!(
             find_marker_position("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 14),
-            29
+            Some(29)
         );
         assert_eq!(
             find_marker_position("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 14),
-            26
+            Some(26)
         );
     }
 }
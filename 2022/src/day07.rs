@@ -1,21 +1,45 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
+
+enum Line<'a> {
+    Cd(&'a str),
+    Ls,
+    Dir,
+    File(i32),
+}
+
+fn parse_line<'a>(c: &mut Cursor<'a>) -> ParseResult<Line<'a>> {
+    Ok(if c.eat("$ cd ") {
+        Line::Cd(c.take_while(|_| true))
+    } else if c.eat("$ ls") {
+        Line::Ls
+    } else if c.eat("dir ") {
+        c.take_while(|_| true);
+        Line::Dir
+    } else {
+        let size = c.int()?;
+        c.tag(" ")?;
+        c.take_while(|_| true);
+        Line::File(size)
+    })
+}
 
 pub(crate) fn run(lines: Lines) -> Result {
     let mut path = vec![];
     let mut sizes = HashMap::<_, i32>::default();
 
-    for &line in lines {
-        if line == "$ cd /" {
-            path = vec![];
-        } else if line == "$ cd .." {
-            path.pop();
-        } else if let Some(matches) = find_regex("\\$ cd ([a-z]+)", line) {
-            path.push(matches[1].to_string());
-        } else if let Some(matches) = find_regex("([0-9]+) [a-z.]+", line) {
-            let size = matches[1].parse::<i32>().unwrap();
-
-            for i in 0..=path.len() {
-                *sizes.entry(path[..i].to_vec()).or_default() += size;
+    for line in Block::new(lines).parse_lines(parse_line)? {
+        match line {
+            Line::Cd("/") => path.clear(),
+            Line::Cd("..") => {
+                path.pop();
+            }
+            Line::Cd(name) => path.push(name),
+            Line::Ls | Line::Dir => {}
+            Line::File(size) => {
+                for i in 0..=path.len() {
+                    *sizes.entry(path[..i].to_vec()).or_default() += size;
+                }
             }
         }
     }
@@ -25,7 +49,7 @@ pub(crate) fn run(lines: Lines) -> Result {
 
     let capacity = 70000000;
     let required = 30000000;
-    let used = sizes[&Vec::<String>::new()];
+    let used = sizes.get(&vec![]).copied().unwrap_or_default();
     let threshold = required + used - capacity;
 
     let b = sizes.values().filter(|&&e| e >= threshold).min();
@@ -1,18 +1,14 @@
 use crate::common::*;
+use advent_common::parse::Block;
 use ndarray::{Array2, ArrayView2, ArrayViewMut2, Axis};
 
-fn parse_grid(lines: Lines) -> Array2<i32> {
-    let rows = lines.len();
-    let cols = lines[0].len();
-    let mut grid = Array2::from_elem((rows, cols), 0);
+fn parse_grid(lines: Lines) -> Result<Array2<i32>> {
+    let grid = Block::new(lines).parse_grid(|c| c.to_digit(10))?;
+    let (rows, cols) = (grid.height(), grid.width());
 
-    for (i, line) in enumerate(lines) {
-        for (j, c) in enumerate(line.chars()) {
-            grid[[i, j]] = c.to_digit(10).unwrap() as i32;
-        }
-    }
-
-    grid
+    Ok(Array2::from_shape_fn((rows, cols), |(i, j)| {
+        grid[[j, i]] as i32
+    }))
 }
 
 fn scan_visible(grid: ArrayView2<i32>, mut visible: ArrayViewMut2<bool>) {
@@ -67,7 +63,7 @@ fn score_spot(grid: ArrayView2<i32>, [i, j]: [usize; 2]) -> usize {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let grid = parse_grid(lines);
+    let grid = parse_grid(lines)?;
     let mut visible = Array2::from_elem(grid.dim(), false);
 
     scan_visible(grid.view(), visible.view_mut());
@@ -100,7 +96,7 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let grid = parse_grid(&["30373", "25512", "65332", "33549", "35390"]);
+        let grid = parse_grid(&["30373", "25512", "65332", "33549", "35390"]).unwrap();
 
         assert_eq!(score_spot(grid.view(), [1, 2]), 5);
     }
@@ -1,30 +1,30 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use std::collections::HashSet;
 
 type Pos = [i32; 2];
 
-fn parse_moves(lines: Lines) -> Vec<Pos> {
+fn parse_move(c: &mut Cursor) -> ParseResult<(Pos, usize)> {
+    let dir = c.one_of(&[("R", [1, 0]), ("L", [-1, 0]), ("U", [0, 1]), ("D", [0, -1])])?;
+    c.tag(" ")?;
+    let steps = c.int()?;
+
+    Ok((dir, steps))
+}
+
+fn parse_moves(lines: Lines) -> Result<Vec<Pos>> {
     let [mut x, mut y] = [0, 0];
     let mut pos = vec![[x, y]];
 
-    for line in lines {
-        let matches = find_regex("([RLUD]) ([0-9]+)", line).unwrap();
-        let steps = matches[2].parse().unwrap();
-
+    for ([dx, dy], steps) in Block::new(lines).parse_lines(parse_move)? {
         for _ in 0..steps {
-            match &matches[1] {
-                "R" => x += 1,
-                "L" => x -= 1,
-                "U" => y += 1,
-                "D" => y -= 1,
-                _ => unreachable!(),
-            }
-
+            x += dx;
+            y += dy;
             pos.push([x, y]);
         }
     }
 
-    pos
+    Ok(pos)
 }
 
 fn follow_path(head: &[Pos]) -> Vec<Pos> {
@@ -53,7 +53,7 @@ fn follow_path(head: &[Pos]) -> Vec<Pos> {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let head = parse_moves(lines);
+    let head = parse_moves(lines)?;
     let mut tail = follow_path(&head);
 
     println!("part A: {}", tail.iter().collect::<HashSet<_>>().len());
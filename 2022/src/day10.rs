@@ -1,27 +1,38 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use ndarray::Array2;
 
-fn simulate(lines: Lines) -> Vec<i32> {
+fn parse_instr(c: &mut Cursor) -> ParseResult<Option<i32>> {
+    if c.eat("noop") {
+        Ok(None)
+    } else {
+        c.tag("addx ")?;
+        Ok(Some(c.int()?))
+    }
+}
+
+fn simulate(lines: Lines) -> Result<Vec<i32>> {
     let mut reg = 1;
     let mut cycles = vec![];
 
-    for &line in lines {
-        if line == "noop" {
-            cycles.push(reg);
-        } else if let Some(matches) = find_regex("addx (-?[0-9]+)", line) {
-            cycles.push(reg);
+    for instr in Block::new(lines).parse_lines(parse_instr)? {
+        cycles.push(reg);
+
+        if let Some(value) = instr {
             cycles.push(reg);
-            reg += matches[1].parse::<i32>().unwrap();
-        } else {
-            panic!("invalid line: {}", line);
+            reg += value;
         }
     }
 
-    cycles
+    Ok(cycles)
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let cycles = simulate(lines);
+    let cycles = simulate(lines)?;
+    ensure!(
+        cycles.len() >= 240,
+        "program runs for fewer than 240 cycles"
+    );
 
     let indices = [20, 60, 100, 140, 180, 220];
     let sum = indices
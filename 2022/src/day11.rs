@@ -1,14 +1,17 @@
 use crate::common::*;
+use advent_common::math::lcm;
+use advent_common::parse::{Block, Cursor, ParseResult, Sections};
 use std::cmp::Reverse;
 use std::collections::VecDeque;
 use std::mem::take;
-use std::str::FromStr;
 
+#[derive(Copy, Clone)]
 enum Operation {
     Add,
     Mul,
 }
 
+#[derive(Copy, Clone)]
 enum Operand {
     Constant(i64),
     Old,
@@ -37,69 +40,85 @@ impl Monkey {
     }
 }
 
-fn regex_number<T: FromStr>(pattern: &str, input: &str) -> Result<T> {
-    Ok(find_regex(pattern, input)
-        .ok_or_else(|| anyhow!("line {:?} failed regex {:?}", input, pattern))?
-        .get(1)
-        .unwrap()
-        .as_str()
-        .parse()
-        .unwrap_or_else(|_| unreachable!()))
+fn parse_target(c: &mut Cursor, outcome: &str) -> ParseResult<usize> {
+    c.tag("    If ")?;
+    c.tag(outcome)?;
+    c.tag(": throw to monkey ")?;
+    c.int()
 }
 
-fn parse_monkeys(lines: Lines) -> Result<Vec<Monkey>> {
-    let mut i = 0;
-    let mut monkeys = vec![];
-
-    while i < lines.len() {
-        let divisible = regex_number("divisible by ([0-9]+)", lines[i + 3])?;
-        let if_true = regex_number("If true: throw to monkey ([0-9]+)", lines[i + 4])?;
-        let if_false = regex_number("If false: throw to monkey ([0-9]+)", lines[i + 5])?;
-
-        let starting_items = find_regex_all("[0-9]+", lines[i + 1])
-            .into_iter()
-            .map(|e| e[0].parse::<i64>())
-            .try_collect()?;
-
-        let line = &lines[i + 2];
-        let matches = find_regex("Operation: new = old ([*+]) ([0-9]+|old)", line).unwrap();
-
-        let operation = match &matches[1] {
-            "*" => Operation::Mul,
-            "+" => Operation::Add,
-            _ => unreachable!(),
-        };
+fn parse_monkey(index: usize, block: Block<&str>) -> ParseResult<Monkey> {
+    if block.len() != 6 {
+        return Err(block.error(0, "expected 6 lines"));
+    }
+
+    block.parse_line(0, |c| {
+        c.tag("Monkey ")?;
+        let start = *c;
 
-        let operand = match &matches[2] {
-            "old" => Operand::Old,
-            v => Operand::Constant(v.parse().unwrap()),
+        if c.int::<usize>()? != index {
+            return Err(start.error(format_args!("expected monkey {}", index)));
+        }
+
+        c.tag(":")
+    })?;
+
+    let starting_items = block.parse_line(1, |c| {
+        c.tag("  Starting items: ")?;
+        c.list(", ", Cursor::int)
+    })?;
+
+    let (operation, operand) = block.parse_line(2, |c| {
+        c.tag("  Operation: new = old ")?;
+        let operation = c.one_of(&[("*", Operation::Mul), ("+", Operation::Add)])?;
+        c.tag(" ")?;
+        let operand = match c.attempt(Cursor::int) {
+            Some(v) => Operand::Constant(v),
+            None => c.one_of(&[("old", Operand::Old)])?,
         };
 
-        monkeys.push(Monkey {
-            starting_items,
-            operation,
-            operand,
-            divisible,
-            if_true,
-            if_false,
-        });
+        Ok((operation, operand))
+    })?;
 
-        i += 7;
-    }
+    let divisible = block.parse_line(3, |c| {
+        c.tag("  Test: divisible by ")?;
+        let start = *c;
+        let divisible = c.int()?;
 
-    Ok(monkeys)
+        if divisible <= 0 {
+            return Err(start.error("divisor must be positive"));
+        }
+
+        Ok(divisible)
+    })?;
+
+    let if_true = block.parse_line(4, |c| parse_target(c, "true"))?;
+    let if_false = block.parse_line(5, |c| parse_target(c, "false"))?;
+
+    Ok(Monkey {
+        starting_items,
+        operation,
+        operand,
+        divisible,
+        if_true,
+        if_false,
+    })
 }
 
-fn lcm(first: i64, second: i64) -> i64 {
-    fn gcd(lhs: i64, rhs: i64) -> i64 {
-        if lhs == 0 {
-            rhs
-        } else {
-            gcd(rhs % lhs, lhs)
-        }
+fn parse_monkeys(lines: Lines) -> Result<Vec<Monkey>> {
+    let monkeys = enumerate(Sections::new(lines).iter())
+        .map(|(index, block)| parse_monkey(index, block))
+        .collect::<ParseResult<Vec<_>>>()?;
+
+    for (index, monkey) in enumerate(&monkeys) {
+        ensure!(
+            monkey.if_true < monkeys.len() && monkey.if_false < monkeys.len(),
+            "monkey {} throws to an unknown monkey",
+            index
+        );
     }
 
-    first * second / gcd(first, second)
+    Ok(monkeys)
 }
 
 fn simulate(monkeys: &[Monkey], rounds: usize, divide: i64) -> usize {
@@ -1,70 +1,70 @@
 use crate::common::*;
-use std::collections::VecDeque;
+use advent_common::parse::Block;
+use advent_common::search::{bfs, Paths};
 
 type Pos = [i32; 2];
 
-fn parse_grid(lines: Lines) -> (HashMap<Pos, i32>, Pos, Pos) {
-    let mut grid = HashMap::default();
-    let mut start = Pos::default();
-    let mut end = Pos::default();
-
-    for (i, &line) in enumerate(lines) {
-        for (j, c) in line.chars().enumerate() {
-            let p = [i as i32, j as i32];
-
-            let height = match c {
-                'a'..='z' => c as i32 - 'a' as i32,
-                'S' => {
-                    start = p;
-                    0
-                }
-                'E' => {
-                    end = p;
-                    25
-                }
-                _ => panic!("invalid character: {:?}", c),
-            };
-
-            grid.insert(p, height);
-        }
-    }
-
-    (grid, start, end)
-}
-
-fn calculate_dists(heights: &HashMap<Pos, i32>, start: Pos) -> HashMap<Pos, i32> {
-    let mut dists = HashMap::default();
-    let mut queue = VecDeque::new();
+fn parse_grid(lines: Lines) -> Result<(HashMap<Pos, i32>, Pos, Pos)> {
+    let chars = Block::new(lines).parse_grid(|c| match c {
+        'a'..='z' | 'S' | 'E' => Some(c),
+        _ => None,
+    })?;
 
-    dists.insert(start, 0);
-    queue.push_back(start);
+    let mut grid = HashMap::default();
+    let mut start = None;
+    let mut end = None;
 
-    while let Some(pos) = queue.pop_front() {
-        for [dx, dy] in [[-1, 0], [1, 0], [0, -1], [0, 1]] {
-            let neighbor = [pos[0] + dx, pos[1] + dy];
+    for ([x, y], &c) in chars.enumerate() {
+        let p = [y as i32, x as i32];
 
-            if heights.contains_key(&neighbor) && !dists.contains_key(&neighbor) {
-                if heights[&pos] - heights[&neighbor] <= 1 {
-                    dists.insert(neighbor, dists[&pos] + 1);
-                    queue.push_back(neighbor);
-                }
+        let height = match c {
+            'S' => {
+                start = Some(p);
+                0
+            }
+            'E' => {
+                end = Some(p);
+                25
             }
-        }
+            _ => c as i32 - 'a' as i32,
+        };
+
+        grid.insert(p, height);
     }
 
-    dists
+    let start = start.context("missing start position")?;
+    let end = end.context("missing end position")?;
+    Ok((grid, start, end))
+}
+
+fn calculate_dists(heights: &HashMap<Pos, i32>, start: Pos) -> Paths<Pos, usize> {
+    // Walks backwards from `start`, which is why the climbing rule is reversed.
+    let successors = |&pos: &Pos| {
+        [[-1, 0], [1, 0], [0, -1], [0, 1]]
+            .into_iter()
+            .map(move |[dx, dy]| [pos[0] + dx, pos[1] + dy])
+            .filter(move |neighbor| {
+                heights
+                    .get(neighbor)
+                    .is_some_and(|h| heights[&pos] - h <= 1)
+            })
+    };
+
+    bfs([start], successors, |_| false)
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let (heights, start, end) = parse_grid(lines);
-    let dists = calculate_dists(&heights, end);
-    println!("part A: {:?}", dists[&start]);
-
-    let (_, best_dist) = dists
-        .iter()
-        .filter(|(&k, _)| heights[&k] == 0)
-        .min_by_key(|(_, &v)| v)
-        .unwrap();
+    let (heights, start, end) = parse_grid(lines)?;
+    let paths = calculate_dists(&heights, end);
+    let dist = paths.distance(&start).context("no path to the end")?;
+    println!("part A: {:?}", dist);
+
+    let best_dist = paths
+        .distances()
+        .filter(|&(k, _)| heights[k] == 0)
+        .map(|(_, v)| v)
+        .min()
+        .context("no path to the end")?;
 
     println!("part B: {:?}", best_dist);
 
@@ -1,9 +1,9 @@
 use crate::common::*;
+use advent_common::parse::{parse_line, Cursor, ParseResult, Sections};
 use itertools::cloned;
 use std::cmp::Ordering;
 use std::fmt;
 use std::iter::zip;
-use std::mem::take;
 
 #[derive(Clone, Eq, PartialEq)]
 enum Item {
@@ -48,52 +48,43 @@ impl PartialOrd for Item {
     }
 }
 
-fn parse_item(line: &str) -> Item {
-    let mut stack: Vec<Vec<Item>> = default();
-    let mut current: Vec<Item> = default();
-    let mut chars = line.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        if c == '[' {
-            stack.push(take(&mut current));
-        } else if c.is_digit(10) {
-            let mut i = c.to_digit(10).unwrap() as i64;
-            while let Some(c) = chars.next_if(|c| c.is_digit(10)) {
-                i = (i * 10) + c.to_digit(10).unwrap() as i64;
-            }
+fn parse_item(c: &mut Cursor) -> ParseResult<Item> {
+    if !c.eat("[") {
+        return Ok(Item::Int(c.int()?));
+    }
 
-            current.push(Item::Int(i));
-        } else if c == ']' {
-            let mut last = stack.pop().unwrap();
-            last.push(Item::List(current));
-            current = last;
-        }
+    let mut items = vec![];
+    if !c.eat("]") {
+        items = c.list(",", parse_item)?;
+        c.tag("]")?;
     }
 
-    Item::List(current)
+    Ok(Item::List(items))
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
     let mut count = 0;
+    let mut packets = vec![];
 
-    for i in (0..lines.len()).step_by(3) {
-        let left = parse_item(lines[i]);
-        let right = parse_item(lines[i + 1]);
+    for (i, block) in enumerate(Sections::new(lines).iter()) {
+        let pair = block.parse_lines(parse_item)?;
+        let [left, right] = &pair[..] else {
+            return Err(block.error(0, "expected a pair of packets").into());
+        };
 
         if left < right {
-            count += i / 3 + 1;
+            count += i + 1;
         }
+
+        packets.extend(pair);
     }
 
     println!("part A: {:?}", count);
 
-    let mut packets = lines
-        .iter()
-        .filter(|line| !line.is_empty())
-        .map(|line| parse_item(line))
-        .collect_vec();
-
-    let decoders = [parse_item("[[2]]"), parse_item("[[6]]")];
+    let decoders = [
+        parse_line(1, "[[2]]", parse_item)?,
+        parse_line(1, "[[6]]", parse_item)?,
+    ];
     packets.extend(cloned(&decoders));
     packets.sort();
 
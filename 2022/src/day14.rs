@@ -1,52 +1,56 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
+use advent_common::sparse::{Point, SparseGrid};
 use std::cmp;
 
-type Point = [i32; 2];
+type Cave = SparseGrid<char>;
 
-fn parse_points(line: &str) -> impl Iterator<Item = Point> + '_ {
-    find_regex_all("([0-9]+),([0-9]+)", line)
-        .into_iter()
-        .map(|m| [m[1].parse().unwrap(), m[2].parse().unwrap()])
+fn parse_point(c: &mut Cursor) -> ParseResult<Point> {
+    let x = c.int()?;
+    c.tag(",")?;
+    let y = c.int()?;
+
+    Ok([x, y])
 }
 
-fn build_cave(lines: Lines) -> HashSet<Point> {
-    let mut rocks = HashSet::default();
+fn build_cave(lines: Lines) -> Result<Cave> {
+    let mut cave = Cave::new('.');
 
-    for &line in lines {
-        for (a, b) in parse_points(line).tuple_windows() {
+    for path in Block::new(lines).parse_lines(|c| c.list(" -> ", parse_point))? {
+        for (a, b) in path.into_iter().tuple_windows() {
             if a[0] == b[0] {
                 let x = a[0];
                 for y in cmp::min(a[1], b[1])..=cmp::max(a[1], b[1]) {
-                    rocks.insert([x, y]);
+                    cave.insert([x, y], '#');
                 }
             } else if a[1] == b[1] {
                 let y = a[1];
                 for x in cmp::min(a[0], b[0])..=cmp::max(a[0], b[0]) {
-                    rocks.insert([x, y]);
+                    cave.insert([x, y], '#');
                 }
             }
         }
     }
 
-    rocks
+    Ok(cave)
 }
 
-fn drop_sand(objects: &HashSet<Point>, floor: i32, has_void: bool) -> Option<Point> {
+fn drop_sand(cave: &Cave, floor: i64, has_void: bool) -> Option<Point> {
     let [mut x, mut y] = [500, 0];
 
-    if objects.contains(&[x, y]) {
+    if cave.contains([x, y]) {
         return None;
     }
 
     loop {
         if y + 1 == floor {
             break if has_void { None } else { Some([x, y]) };
-        } else if !objects.contains(&[x, y + 1]) {
+        } else if !cave.contains([x, y + 1]) {
             y += 1;
-        } else if !objects.contains(&[x - 1, y + 1]) {
+        } else if !cave.contains([x - 1, y + 1]) {
             x -= 1;
             y += 1;
-        } else if !objects.contains(&[x + 1, y + 1]) {
+        } else if !cave.contains([x + 1, y + 1]) {
             x += 1;
             y += 1;
         } else {
@@ -55,23 +59,25 @@ fn drop_sand(objects: &HashSet<Point>, floor: i32, has_void: bool) -> Option<Poi
     }
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let rocks = build_cave(lines);
-    let floor = rocks.iter().map(|&[_, y]| y).max().unwrap() + 2;
+fn fill_sand(rocks: &Cave, floor: i64, has_void: bool) -> Cave {
+    let mut cave = rocks.clone();
 
-    let mut objects = rocks.clone();
-    while let Some(p) = drop_sand(&objects, floor, true) {
-        objects.insert(p);
+    while let Some(p) = drop_sand(&cave, floor, has_void) {
+        cave.insert(p, 'o');
     }
 
-    println!("part A: {}", objects.len() - rocks.len());
+    cave
+}
+
+pub(crate) fn run(lines: Lines) -> Result {
+    let rocks = build_cave(lines)?;
+    let floor = rocks.bounds().context("empty cave")?.max[1] + 2;
 
-    let mut objects = rocks.clone();
-    while let Some(p) = drop_sand(&objects, floor, false) {
-        objects.insert(p);
-    }
+    let cave = fill_sand(&rocks, floor, true);
+    println!("part A: {}", cave.len() - rocks.len());
 
-    println!("part B: {}", objects.len() - rocks.len());
+    let cave = fill_sand(&rocks, floor, false);
+    println!("part B: {}", cave.len() - rocks.len());
 
     Ok(())
 }
@@ -1,95 +1,74 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
-use std::ops::RangeInclusive;
-
-#[derive(Recap, Deserialize)]
-#[recap(
-    regex = r#"Sensor at x=(?P<sx>-?[0-9]+), y=(?P<sy>-?[0-9]+): closest beacon is at x=(?P<bx>-?[0-9]+), y=(?P<by>-?[0-9]+)"#
-)]
-struct Line {
-    sx: i64,
-    sy: i64,
-    bx: i64,
-    by: i64,
-}
+use advent_common::interval::{Cuboid, IntervalSet};
+use advent_common::manhattan::{distance, find_uncovered, Ball};
+use advent_common::parse::{Block, Cursor, ParseResult};
 
 #[derive(Debug)]
 struct Sensor {
-    x: i64,
-    y: i64,
-    radius: i64,
+    area: Ball<2>,
+    beacon: [i64; 2],
 }
 
-fn parse_sensors(lines: Lines) -> Vec<Sensor> {
-    lines
-        .iter()
-        .map(|&l| l.parse::<Line>().unwrap())
-        .map(|l| Sensor {
-            x: l.sx,
-            y: l.sy,
-            radius: (l.sx - l.bx).abs() + (l.sy - l.by).abs(),
-        })
-        .collect()
-}
-
-fn nonbeacon_position(sensors: &[Sensor], y: i64) -> (i64, Vec<RangeInclusive<i64>>) {
-    let mut d = vec![];
-    d.reserve(sensors.len() * 2);
+fn parse_position(c: &mut Cursor) -> ParseResult<[i64; 2]> {
+    c.tag("x=")?;
+    let x = c.int()?;
+    c.tag(", y=")?;
+    let y = c.int()?;
 
-    for sensor in sensors {
-        let dy = (y - sensor.y).abs();
-        let dx = sensor.radius - dy;
-
-        if dx > 0 {
-            d.push((sensor.x - dx, 1));
-            d.push((sensor.x + dx, -1));
-        }
-    }
+    Ok([x, y])
+}
 
-    d.sort();
+fn parse_sensor(c: &mut Cursor) -> ParseResult<Sensor> {
+    c.tag("Sensor at ")?;
+    let sensor = parse_position(c)?;
+    c.tag(": closest beacon is at ")?;
+    let beacon = parse_position(c)?;
 
-    if d.is_empty() {
-        return (0, vec![]);
-    }
+    Ok(Sensor {
+        area: Ball::new(sensor, distance(sensor, beacon)),
+        beacon,
+    })
+}
 
-    let mut overlaps = 0;
-    let mut last_x = d[0].0;
-    let mut total = 0;
-    let mut segments = Vec::with_capacity(1);
-    let mut segment_start = 0;
+fn parse_sensors(lines: Lines) -> Result<Vec<Sensor>> {
+    Ok(Block::new(lines).parse_lines(parse_sensor)?)
+}
 
-    for &(x, p) in &d {
-        if overlaps > 0 {
-            total += x - last_x;
-            last_x = x;
-        } else {
-            segment_start = x;
-        }
+fn covered_positions(sensors: &[Sensor], y: i64) -> IntervalSet {
+    sensors
+        .iter()
+        .filter_map(|sensor| {
+            let Ball { center, radius } = sensor.area;
+            let dx = radius - (y - center[1]).abs();
+            (dx >= 0).then(|| (center[0] - dx)..(center[0] + dx + 1))
+        })
+        .collect()
+}
 
-        overlaps += p;
+fn nonbeacon_positions(sensors: &[Sensor], y: i64) -> i64 {
+    let covered = covered_positions(sensors, y);
+    let beacons = sensors
+        .iter()
+        .map(|s| s.beacon)
+        .filter(|&[bx, by]| by == y && covered.contains(bx))
+        .unique()
+        .count();
 
-        if overlaps == 0 {
-            segments.push(segment_start..=x);
-        }
-    }
+    covered.len() - beacons as i64
+}
 
-    (total, segments)
+fn find_distress_beacon(sensors: &[Sensor], size: i64) -> Option<[i64; 2]> {
+    let areas = sensors.iter().map(|s| s.area).collect_vec();
+    find_uncovered(&areas, &Cuboid::new([0..(size + 1), 0..(size + 1)]))
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let sensors = parse_sensors(lines);
-
-    println!("part A: {:?}", nonbeacon_position(&sensors, 2000000));
+    let sensors = parse_sensors(lines)?;
 
-    for y in 0..=4000000 {
-        let (_, segments) = nonbeacon_position(&sensors, y);
+    println!("part A: {}", nonbeacon_positions(&sensors, 2000000));
 
-        if segments.len() > 1 {
-            let x = (segments[0].end() + segments[1].start()) / 2;
-            println!("part B: x={:?} y={:?} freq={}", x, y, x * 4000000 + y);
-        }
-    }
+    let [x, y] = find_distress_beacon(&sensors, 4000000).context("no position found")?;
+    println!("part B: x={:?} y={:?} freq={}", x, y, x * 4000000 + y);
 
     Ok(())
 }
@@ -1,17 +1,44 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
-use std::collections::hash_map::Entry;
+use advent_common::bitset::BitSet;
+use advent_common::parse::{Block, Cursor, ParseResult};
+use itertools::Either;
 use std::collections::VecDeque;
 
-#[derive(Debug, Recap, Deserialize)]
-#[recap(
-    regex = r#"Valve (?P<name>[A-Z]{2}) has flow rate[=](?P<rate>[0-9]+); tunnels? leads? to valves? (?P<neighbors>.*)"#
-)]
-struct Line {
-    name: String,
+#[derive(Debug)]
+struct Line<'a> {
+    name: &'a str,
     rate: i64,
-    neighbors: String,
+    neighbors: Vec<&'a str>,
+}
+
+/// Valve names are two capital letters.
+fn parse_valve<'a>(c: &mut Cursor<'a>) -> ParseResult<&'a str> {
+    let start = *c;
+    let name = c.take_while(|c| c.is_ascii_uppercase());
+
+    if name.len() != 2 {
+        return Err(start.error("expected a valve name of two capital letters"));
+    }
+
+    Ok(name)
+}
+
+fn parse_line<'a>(c: &mut Cursor<'a>) -> ParseResult<Line<'a>> {
+    c.tag("Valve ")?;
+    let name = parse_valve(c)?;
+    c.tag(" has flow rate=")?;
+    let rate = c.int()?;
+    c.one_of(&[
+        ("; tunnels lead to valves ", ()),
+        ("; tunnel leads to valve ", ()),
+    ])?;
+    let neighbors = c.list(", ", parse_valve)?;
+
+    Ok(Line {
+        name,
+        rate,
+        neighbors,
+    })
 }
 
 #[derive(Debug)]
@@ -21,17 +48,35 @@ struct Node {
     dists: Vec<i64>,
 }
 
-fn parse_cave(lines: Lines) -> Vec<Node> {
-    let lines = lines
-        .iter()
-        .map(|&line| line.parse::<Line>().unwrap())
-        .map(|l| (l.name.clone(), l))
-        .collect::<HashMap<String, _>>();
+#[derive(Debug)]
+struct Cave {
+    nodes: Vec<Node>,
+    start: usize,
+}
+
+fn parse_cave(lines: Lines) -> Result<Cave> {
+    let lines = Block::new(lines)
+        .parse_lines(parse_line)?
+        .into_iter()
+        .map(|l| (l.name, l))
+        .collect::<HashMap<&str, _>>();
+
+    for line in lines.values() {
+        for neighbor in &line.neighbors {
+            ensure!(
+                lines.contains_key(neighbor),
+                "unknown valve: {:?}",
+                neighbor
+            );
+        }
+    }
+
+    ensure!(lines.contains_key("AA"), "missing valve \"AA\"");
 
     let node_names = lines
         .values()
         .filter(|l| l.rate > 0 || l.name == "AA")
-        .map(|l| l.name.as_str())
+        .map(|l| l.name)
         .sorted()
         .collect_vec();
     let mut nodes = vec![];
@@ -51,7 +96,11 @@ fn parse_cave(lines: Lines) -> Vec<Node> {
                 dists[index] = dist;
             }
 
-            for neighbor in lines[&*other].neighbors.split(", ") {
+            let valve = lines
+                .get(other)
+                .with_context(|| format!("unknown valve {:?}", other))?;
+
+            for &neighbor in &valve.neighbors {
                 queue.push_back((neighbor, dist + 1));
             }
         }
@@ -63,25 +112,27 @@ fn parse_cave(lines: Lines) -> Vec<Node> {
         });
     }
 
-    nodes
+    let start = node_names
+        .iter()
+        .position(|&n| n == "AA")
+        .context("no valve AA")?;
+    Ok(Cave { nodes, start })
 }
 
-const N: usize = 16;
-
 #[derive(Debug, Copy, Clone)]
-struct State {
+struct State<const W: usize> {
     position: usize,
     time: i64,
-    is_opened: u64,
+    is_opened: BitSet<W>,
     total_flow: i64,
 }
 
-fn compute_states(nodes: &[Node], max_time: i64) -> Vec<State> {
-    assert_eq!(nodes.len(), N);
+fn compute_states<const W: usize>(cave: &Cave, max_time: i64) -> Vec<State<W>> {
+    let nodes = &cave.nodes;
     let initial_state = State {
-        position: 0,
+        position: cave.start,
         time: 0,
-        is_opened: 0,
+        is_opened: BitSet::new(),
         total_flow: 0,
     };
 
@@ -91,19 +142,19 @@ fn compute_states(nodes: &[Node], max_time: i64) -> Vec<State> {
     while let Some(&state) = options.get(index) {
         index += 1;
 
-        for i in 0..N {
-            let mask = 1 << i;
+        for (i, node) in enumerate(nodes) {
+            let dist = nodes[state.position].dists[i];
 
-            if (state.is_opened) & mask == 0 {
-                let new_time = state.time + nodes[state.position].dists[i] + 1;
+            if node.rate > 0 && dist >= 0 && !state.is_opened.contains(i) {
+                let new_time = state.time + dist + 1;
 
                 if new_time < max_time {
-                    let mut new_state = state.clone();
-                    new_state.position = i;
-                    new_state.time = new_time;
-                    new_state.is_opened |= mask;
-                    new_state.total_flow += (max_time - new_time) * nodes[i].rate;
-                    options.push(new_state);
+                    options.push(State {
+                        position: i,
+                        time: new_time,
+                        is_opened: state.is_opened.with(i),
+                        total_flow: state.total_flow + (max_time - new_time) * node.rate,
+                    });
                 }
             }
         }
@@ -112,44 +163,79 @@ fn compute_states(nodes: &[Node], max_time: i64) -> Vec<State> {
     options
 }
 
-fn solve(nodes: &[Node], max_time: i64) -> i64 {
-    compute_states(nodes, max_time)
-        .iter()
-        .map(|s| s.total_flow)
-        .max()
-        .unwrap()
-}
+fn solve_with_agents<const W: usize>(cave: &Cave, max_time: i64, agents: usize) -> i64 {
+    // The best flow a single agent achieves for each set of opened valves.
+    let mut best_states = HashMap::<BitSet<W>, i64>::default();
 
-fn solve_with_elephant(nodes: &[Node], max_time: i64) -> i64 {
-    let mut best_states = HashMap::default();
+    for state in compute_states::<W>(cave, max_time) {
+        let flow = best_states.entry(state.is_opened).or_default();
+        *flow = i64::max(*flow, state.total_flow);
+    }
 
-    for state in compute_states(nodes, max_time) {
-        match best_states.entry(state.is_opened) {
-            Entry::Vacant(e) => {
-                e.insert(state);
-            }
-            Entry::Occupied(mut e) if state.total_flow > e.get().total_flow => {
-                e.insert(state);
+    // Each additional agent opens valves disjoint from those opened so far,
+    // so its options are the subsets of the valves that remain. Enumerate
+    // these subsets when there are fewer of them than sets in `best_states`.
+    let reachable = best_states
+        .keys()
+        .fold(BitSet::new(), |acc, &set| acc | set);
+    let mut combined = best_states.clone();
+
+    for _ in 1..agents {
+        let mut next = HashMap::<BitSet<W>, i64>::default();
+
+        for (&a, &flow_a) in &combined {
+            let remaining = reachable - a;
+            let options = if remaining.len() < 64 && 1 << remaining.len() <= best_states.len() {
+                Either::Left(
+                    remaining
+                        .subsets()
+                        .filter_map(|b| Some((b, *best_states.get(&b)?))),
+                )
+            } else {
+                Either::Right(
+                    best_states
+                        .iter()
+                        .filter(|(b, _)| a.is_disjoint(b))
+                        .map(|(&b, &flow_b)| (b, flow_b)),
+                )
+            };
+
+            for (b, flow_b) in options {
+                let flow = next.entry(a.union(b)).or_default();
+                *flow = i64::max(*flow, flow_a + flow_b);
             }
-            _ => {}
         }
+
+        combined = next;
     }
 
-    itertools::iproduct!(best_states.values(), best_states.values())
-        .filter(|(a, b)| a.is_opened & b.is_opened == 0)
-        .map(|(a, b)| a.total_flow + b.total_flow)
-        .max()
-        .unwrap()
+    combined.into_values().max().unwrap_or_default()
+}
+
+fn solve(cave: &Cave, max_time: i64, agents: usize) -> Result<i64> {
+    let n = cave.nodes.len();
+    ensure!(agents > 0, "at least one agent is required");
+
+    // Use the narrowest bitset that fits. The names of the valves are two
+    // letters, so there can be no more than 26 * 26 of them.
+    Ok(if n <= BitSet::<1>::CAPACITY {
+        solve_with_agents::<1>(cave, max_time, agents)
+    } else if n <= BitSet::<2>::CAPACITY {
+        solve_with_agents::<2>(cave, max_time, agents)
+    } else if n <= BitSet::<4>::CAPACITY {
+        solve_with_agents::<4>(cave, max_time, agents)
+    } else {
+        solve_with_agents::<{ usize::div_ceil(26 * 26, 64) }>(cave, max_time, agents)
+    })
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let cave = parse_cave(lines);
+    let cave = parse_cave(lines)?;
 
-    let flow = solve(&cave, 30);
+    let flow = solve(&cave, 30, 1)?;
     println!("part A: {}", flow);
 
-    //2090 -> to low
-    let flow = solve_with_elephant(&cave, 26);
+    let flow = solve(&cave, 26, 2)?;
     println!("part B: {}", flow);
 
     Ok(())
@@ -159,13 +245,58 @@ pub(crate) fn run(lines: Lines) -> Result {
 mod tests {
     use super::*;
 
+    const EXAMPLE: &[&str] = &[
+        "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB",
+        "Valve BB has flow rate=13; tunnels lead to valves CC, AA",
+        "Valve CC has flow rate=2; tunnels lead to valves DD, BB",
+        "Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE",
+        "Valve EE has flow rate=3; tunnels lead to valves FF, DD",
+        "Valve FF has flow rate=0; tunnels lead to valves EE, GG",
+        "Valve GG has flow rate=0; tunnels lead to valves FF, HH",
+        "Valve HH has flow rate=22; tunnel leads to valve GG",
+        "Valve II has flow rate=0; tunnels lead to valves AA, JJ",
+        "Valve JJ has flow rate=21; tunnel leads to valve II",
+    ];
+
     #[test]
     fn test_a() {
-        //
+        let cave = parse_cave(EXAMPLE).unwrap();
+        assert_eq!(solve(&cave, 30, 1).unwrap(), 1651);
     }
 
     #[test]
     fn test_b() {
-        //
+        let cave = parse_cave(EXAMPLE).unwrap();
+        assert_eq!(solve(&cave, 26, 2).unwrap(), 1707);
+        assert_eq!(solve_with_agents::<2>(&cave, 26, 2), 1707);
+    }
+
+    #[test]
+    fn test_many_valves() {
+        // Valve AA leads to 150 valves whose rate equals their index.
+        let name = |i: usize| {
+            format!(
+                "{}{}",
+                (b'A' + (i / 26) as u8) as char,
+                (b'A' + (i % 26) as u8) as char
+            )
+        };
+        let mut lines = vec![format!(
+            "Valve AA has flow rate=0; tunnels lead to valves {}",
+            (1..=150).map(name).join(", ")
+        )];
+
+        for i in 1..=150 {
+            lines.push(format!(
+                "Valve {} has flow rate={}; tunnel leads to valve AA",
+                name(i),
+                i
+            ));
+        }
+
+        let lines = lines.iter().map(|l| l.as_str()).collect_vec();
+        let cave = parse_cave(&lines).unwrap();
+        assert_eq!(solve(&cave, 4, 1).unwrap(), 2 * 150);
+        assert_eq!(solve(&cave, 4, 2).unwrap(), 2 * 150 + 2 * 149);
     }
 }
@@ -1,4 +1,6 @@
 use crate::common::*;
+use advent_common::cycle::CycleDetector;
+use advent_common::parse::Block;
 
 const CAVE_WIDTH: i64 = 7;
 const ROCK_SIZE: i64 = 4;
@@ -112,49 +114,59 @@ fn drop_rock(
     }
 }
 
+/// Depth below the highest rock that is considered when comparing the
+/// shape of the top of the tower.
+const SKYLINE_DEPTH: i64 = 64;
+
+fn skyline(solid: &HashSet<(i64, i64)>, highest_y: i64) -> [i64; CAVE_WIDTH as usize] {
+    let mut result = [SKYLINE_DEPTH; CAVE_WIDTH as usize];
+
+    for (x, depth) in enumerate(&mut result) {
+        if let Some(d) = (0..SKYLINE_DEPTH).find(|d| solid.contains(&(x as i64, highest_y - d))) {
+            *depth = d;
+        }
+    }
+
+    result
+}
+
 pub(crate) fn simulate(jets: &[char], nrocks: usize) -> i64 {
     let mut solid = HashSet::<(i64, i64)>::default();
     let mut highest_y = -1;
     let mut jet_index = 0;
-    let mut states = vec![];
-    let mut rock_index = 0;
+    let mut detector = CycleDetector::new();
 
-    let cycle = 'outer: loop {
-        let rock = &ROCKS[rock_index % ROCKS.len()];
-        let (x, y) = drop_rock(2, highest_y + 4, jets, &mut jet_index, rock, &solid);
-        let new_highest_y = insert_rock(x, y, rock, &mut solid);
-        let delta = (new_highest_y - highest_y).max(0);
-        highest_y += delta;
+    // heights[i] is the height of the tower after dropping i rocks
+    let mut heights = vec![0];
 
-        states.push((jet_index % jets.len(), delta));
-        let n = states.len();
+    for rock_index in 0..nrocks {
+        let key = (
+            rock_index % ROCKS.len(),
+            jet_index % jets.len(),
+            skyline(&solid, highest_y),
+        );
 
-        for cycle in (0..states.len() / 2).step_by(ROCKS.len()).skip(1) {
-            if states[n - cycle..] == states[n - 2 * cycle..n - cycle] {
-                break 'outer cycle;
-            }
+        if let Some(cycle) = detector.push(key) {
+            return cycle.extrapolate(&heights, nrocks);
         }
 
-        rock_index += 1;
-    };
-
-    let ncycles = nrocks / cycle;
-    let cycle_height = states[states.len() - cycle..]
-        .iter()
-        .map(|(_, delta)| delta)
-        .sum::<i64>();
-
-    let remainder = nrocks % cycle;
-    let remainder_height = states[..remainder]
-        .iter()
-        .map(|(_, delta)| delta)
-        .sum::<i64>();
+        let rock = &ROCKS[rock_index % ROCKS.len()];
+        let (x, y) = drop_rock(2, highest_y + 4, jets, &mut jet_index, rock, &solid);
+        let new_highest_y = insert_rock(x, y, rock, &mut solid);
+        highest_y = highest_y.max(new_highest_y);
+        heights.push(highest_y + 1);
+    }
 
-    ncycles as i64 * cycle_height + remainder_height
+    highest_y + 1
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let jets = lines[0].chars().collect_vec();
+    let jets = Block::new(lines)
+        .parse_single(|c| Ok(c.take_while(|c| c == '<' || c == '>')))?
+        .chars()
+        .collect_vec();
+    ensure!(!jets.is_empty(), "no jets");
+
     let height = simulate(&jets, 2022);
     println!("part A: {:?}", height);
 
@@ -167,13 +179,17 @@ pub(crate) fn run(lines: Lines) -> Result {
 mod tests {
     use super::*;
 
+    const JETS: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
     #[test]
     fn test_a() {
-        //
+        let jets = JETS.chars().collect_vec();
+        assert_eq!(simulate(&jets, 2022), 3068);
     }
 
     #[test]
     fn test_b() {
-        //
+        let jets = JETS.chars().collect_vec();
+        assert_eq!(simulate(&jets, 1000000000000), 1514285714288);
     }
 }
@@ -1,15 +1,23 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
-#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug, Recap, Deserialize)]
-#[recap(regex = r#"(?P<x>[0-9]+),(?P<y>[0-9]+),(?P<z>[0-9]+)"#)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 struct Cube {
     x: i64,
     y: i64,
     z: i64,
 }
 
+fn parse_cube(c: &mut Cursor) -> ParseResult<Cube> {
+    let x = c.int()?;
+    c.tag(",")?;
+    let y = c.int()?;
+    c.tag(",")?;
+    let z = c.int()?;
+
+    Ok(Cube { x, y, z })
+}
+
 const FACES: [[i64; 3]; 6] = [
     [1, 0, 0],
     [-1, 0, 0],
@@ -103,10 +111,7 @@ fn count_exterior_faces(cubes: &[Cube]) -> i64 {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let cubes = lines
-        .iter()
-        .map(|l| l.parse::<Cube>().unwrap())
-        .collect_vec();
+    let cubes = Block::new(lines).parse_lines(parse_cube)?;
 
     println!("part A: {}", count_faces(&cubes));
     println!("part B: {}", count_exterior_faces(&cubes));
@@ -1,49 +1,37 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
-use std::str::FromStr;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
 const ORE: usize = 0;
 const CLAY: usize = 1;
 const OBSIDIAN: usize = 2;
 const GEODE: usize = 3;
 
-#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug, Recap, Deserialize)]
-#[recap(
-    regex = r#"Blueprint (?P<id>[0-9]+): Each ore robot costs (?P<ore_robot_ore_required>[0-9]+) ore. Each clay robot costs (?P<clay_robot_ore_required>[0-9]+) ore. Each obsidian robot costs (?P<obsidian_robot_ore_required>[0-9]+) ore and (?P<obsidian_robot_clay_required>[0-9]+) clay. Each geode robot costs (?P<geode_robot_ore_required>[0-9]+) ore and (?P<geode_robot_obsidian_required>[0-9]+) obsidian."#
-)]
-struct RawBlueprint {
-    id: i32,
-    ore_robot_ore_required: i32,
-    clay_robot_ore_required: i32,
-    obsidian_robot_ore_required: i32,
-    obsidian_robot_clay_required: i32,
-    geode_robot_ore_required: i32,
-    geode_robot_obsidian_required: i32,
-}
-
 #[derive(Default, Debug)]
 struct Blueprint {
     id: i32,
     robots: [[i32; 4]; 4],
 }
 
-impl FromStr for Blueprint {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let input = s.parse::<RawBlueprint>()?;
-        let mut result = Blueprint::default();
-
-        result.id = input.id;
-        result.robots[ORE][ORE] = input.ore_robot_ore_required;
-        result.robots[CLAY][ORE] = input.clay_robot_ore_required;
-        result.robots[OBSIDIAN][ORE] = input.obsidian_robot_ore_required;
-        result.robots[OBSIDIAN][CLAY] = input.obsidian_robot_clay_required;
-        result.robots[GEODE][ORE] = input.geode_robot_ore_required;
-        result.robots[GEODE][OBSIDIAN] = input.geode_robot_obsidian_required;
-        Ok(result)
-    }
+fn parse_blueprint(c: &mut Cursor) -> ParseResult<Blueprint> {
+    let mut result = Blueprint::default();
+
+    c.tag("Blueprint ")?;
+    result.id = c.int()?;
+    c.tag(": Each ore robot costs ")?;
+    result.robots[ORE][ORE] = c.int()?;
+    c.tag(" ore. Each clay robot costs ")?;
+    result.robots[CLAY][ORE] = c.int()?;
+    c.tag(" ore. Each obsidian robot costs ")?;
+    result.robots[OBSIDIAN][ORE] = c.int()?;
+    c.tag(" ore and ")?;
+    result.robots[OBSIDIAN][CLAY] = c.int()?;
+    c.tag(" clay. Each geode robot costs ")?;
+    result.robots[GEODE][ORE] = c.int()?;
+    c.tag(" ore and ")?;
+    result.robots[GEODE][OBSIDIAN] = c.int()?;
+    c.tag(" obsidian.")?;
+
+    Ok(result)
 }
 
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -116,10 +104,7 @@ fn simulate(blueprint: &Blueprint, max_time: i32) -> i32 {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let blueprints = lines
-        .iter()
-        .map(|l| l.parse::<Blueprint>().unwrap())
-        .collect_vec();
+    let blueprints = Block::new(lines).parse_lines(parse_blueprint)?;
 
     let max = blueprints
         .iter()
@@ -127,8 +112,9 @@ pub(crate) fn run(lines: Lines) -> Result {
         .sum::<i32>();
     println!("part A: {:?}", max);
 
-    let result = blueprints[..3]
+    let result = blueprints
         .iter()
+        .take(3)
         .map(|b| simulate(b, 32) as i64)
         .product::<i64>();
     println!("part B: {:?}", result);
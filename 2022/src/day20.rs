@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor};
 
 fn mix_numbers(numbers: &[i64], times: usize) -> Vec<i64> {
     let n = numbers.len();
@@ -45,10 +46,9 @@ fn find_thousands(nums: &[i64]) -> [i64; 3] {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let numbers = lines
-        .iter()
-        .map(|x| x.parse::<i64>())
-        .collect::<Result<Vec<_>, _>>()?;
+    let numbers = Block::new(lines).parse_lines(Cursor::int)?;
+    ensure!(numbers.len() > 1, "need at least two numbers");
+    ensure!(numbers.contains(&0), "no zero in the list");
 
     let [a, b, c] = find_thousands(&mix_numbers(&numbers, 1));
     println!("part A: {}", a + b + c);
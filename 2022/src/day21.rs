@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
 type Val = i64;
 
@@ -16,34 +17,33 @@ enum Expr {
     Binary(String, BinOp, String),
 }
 
-fn parse_expressions(lines: Lines) -> HashMap<String, Expr> {
-    let mut result = HashMap::default();
-
-    for line in lines {
-        let mut parts = line.splitn(2, ": ");
-        let name = parts.next().unwrap();
-        let expr = parts.next().unwrap();
-
-        let expr = if let Some(matches) = find_regex("[0-9]+", expr) {
-            Expr::Constant(matches[0].parse().unwrap())
-        } else if let Some(matches) = find_regex("([a-z]{4}) ([+-/*]) ([a-z]{4})", expr) {
-            let op = match &matches[2] {
-                "+" => BinOp::Add,
-                "-" => BinOp::Sub,
-                "/" => BinOp::Div,
-                "*" => BinOp::Mul,
-                _ => unreachable!(),
-            };
-
-            Expr::Binary(matches[1].to_string(), op, matches[3].to_string())
-        } else {
-            panic!("invalid line: {}", line);
-        };
-
-        result.insert(name.to_string(), expr);
-    }
+fn parse_expression(c: &mut Cursor) -> ParseResult<(String, Expr)> {
+    let name = c.word()?.to_string();
+    c.tag(": ")?;
+
+    let expr = if let Some(v) = c.attempt(Cursor::int) {
+        Expr::Constant(v)
+    } else {
+        let lhs = c.word()?.to_string();
+        c.tag(" ")?;
+        let op = c.one_of(&[
+            ("+", BinOp::Add),
+            ("-", BinOp::Sub),
+            ("*", BinOp::Mul),
+            ("/", BinOp::Div),
+        ])?;
+        c.tag(" ")?;
+        let rhs = c.word()?.to_string();
+
+        Expr::Binary(lhs, op, rhs)
+    };
+
+    Ok((name, expr))
+}
 
-    result
+fn parse_expressions(lines: Lines) -> Result<HashMap<String, Expr>> {
+    let exprs = Block::new(lines).parse_lines(parse_expression)?;
+    Ok(exprs.into_iter().collect())
 }
 
 fn evaluate(key: &str, monkeys: &HashMap<String, Expr>) -> Option<Val> {
@@ -63,14 +63,14 @@ fn evaluate(key: &str, monkeys: &HashMap<String, Expr>) -> Option<Val> {
     })
 }
 
-fn solve<'a>(mut var: &'a str, val: Val, monkeys: &'a HashMap<String, Expr>) -> (&'a str, Val) {
+fn solve<'a>(var: &'a str, val: Val, monkeys: &'a HashMap<String, Expr>) -> Result<(&'a str, Val)> {
     let Some(expr) = monkeys.get(var) else {
-        return (var, val);
+        return Ok((var, val));
     };
 
     let Expr::Binary(lhs, op, rhs) = expr else {
-            panic!("invalid expression");
-        };
+        bail!("monkey {:?} has a known value", var);
+    };
 
     match (evaluate(lhs, monkeys), op, evaluate(rhs, monkeys)) {
         (Some(lhs), BinOp::Add, None) => solve(rhs, val - lhs, monkeys),
@@ -82,26 +82,28 @@ fn solve<'a>(mut var: &'a str, val: Val, monkeys: &'a HashMap<String, Expr>) ->
         (Some(lhs), BinOp::Div, None) => solve(rhs, lhs / val, monkeys),
         (None, BinOp::Div, Some(rhs)) => solve(lhs, val * rhs, monkeys),
         other => {
-            panic!("invalid expression: {:?}", other);
+            bail!("cannot solve for {:?}: {:?}", var, other);
         }
     }
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let mut monkeys = parse_expressions(lines);
+    let mut monkeys = parse_expressions(lines)?;
     println!("part A: {:?}", evaluate("root", &monkeys));
 
     // Remove human monkey
     monkeys.remove("humn");
 
     // Solve `var == constant` or `constant == var` for root
-    let answer = match &monkeys["root"] {
-        Expr::Binary(lhs, _, rhs) => match (evaluate(lhs, &monkeys), evaluate(rhs, &monkeys)) {
-            (Some(lhs), None) => solve(rhs, lhs, &monkeys),
-            (None, Some(rhs)) => solve(lhs, rhs, &monkeys),
-            _ => unreachable!(),
-        },
-        Expr::Constant(_) => unreachable!(),
+    let answer = match monkeys.get("root") {
+        Some(Expr::Binary(lhs, _, rhs)) => {
+            match (evaluate(lhs, &monkeys), evaluate(rhs, &monkeys)) {
+                (Some(lhs), None) => solve(rhs, lhs, &monkeys)?,
+                (None, Some(rhs)) => solve(lhs, rhs, &monkeys)?,
+                _ => bail!("exactly one side of root must depend on humn"),
+            }
+        }
+        _ => bail!("root must be a binary expression"),
     };
 
     println!("part B: {:?}", answer);
@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use ndarray::prelude::*;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -8,33 +9,18 @@ enum Step {
     Forward,
 }
 
-fn parse_steps(line: &str) -> Vec<Step> {
+fn parse_steps(c: &mut Cursor) -> ParseResult<Vec<Step>> {
     let mut output = vec![];
-    let mut n = 0;
 
-    for c in line.chars() {
-        if let Some(i) = c.to_digit(10) {
-            n = n * 10 + i;
+    while !c.is_empty() {
+        if let Some(n) = c.attempt(Cursor::int::<usize>) {
+            output.extend([Step::Forward].repeat(n));
         } else {
-            while n > 0 {
-                output.push(Step::Forward);
-                n -= 1;
-            }
-
-            match c {
-                'L' => output.push(Step::TurnLeft),
-                'R' => output.push(Step::TurnRight),
-                _ => panic!("invalid character: {:?}", c),
-            }
+            output.push(c.one_of(&[("L", Step::TurnLeft), ("R", Step::TurnRight)])?);
         }
     }
 
-    while n > 0 {
-        output.push(Step::Forward);
-        n -= 1;
-    }
-
-    output
+    Ok(output)
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -44,30 +30,40 @@ enum Tile {
     Open,
 }
 
-fn parse_map(lines: &[&str]) -> Array2<Tile> {
-    let height = lines.len();
-    let width = lines.iter().map(|line| line.chars().count()).max().unwrap();
+fn parse_map(block: Block<&str>) -> ParseResult<Array2<Tile>> {
+    let rows = block.parse_lines(|c| {
+        let mut row = vec![];
 
+        while !c.is_empty() {
+            row.push(c.one_of(&[(" ", Tile::Empty), (".", Tile::Open), ("#", Tile::Wall)])?);
+        }
+
+        Ok(row)
+    })?;
+
+    let height = rows.len();
+    let width = rows.iter().map(Vec::len).max().unwrap_or_default();
     let mut grid = Array2::from_elem((height, width), Tile::Empty);
 
-    for (i, line) in enumerate(lines) {
-        for (j, c) in enumerate(line.chars()) {
-            grid[[i, j]] = match c {
-                ' ' => Tile::Empty,
-                '.' => Tile::Open,
-                '#' => Tile::Wall,
-                c => panic!("invalid character: {:?}", c),
-            };
+    for (i, row) in enumerate(rows) {
+        for (j, tile) in enumerate(row) {
+            grid[[i, j]] = tile;
         }
     }
 
-    grid
+    Ok(grid)
 }
 
-fn walk_map(map: ArrayView2<Tile>, steps: &[Step]) -> (usize, usize, usize) {
+fn walk_map(map: ArrayView2<Tile>, steps: &[Step]) -> Result<(usize, usize, usize)> {
     let (height, width) = map.dim();
+    ensure!(height > 1, "map is too small");
+
     let mut i = 1;
-    let mut j = map.row(i).iter().position(|&t| t == Tile::Open).unwrap();
+    let mut j = map
+        .row(i)
+        .iter()
+        .position(|&t| t == Tile::Open)
+        .context("no open tile to start from")?;
     let mut dir = 0;
 
     for &step in steps {
@@ -107,15 +103,18 @@ fn walk_map(map: ArrayView2<Tile>, steps: &[Step]) -> (usize, usize, usize) {
         }
     }
 
-    (i, j, dir)
+    Ok((i, j, dir))
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let n = lines.len();
-    let map = parse_map(&lines[..n - 1]);
-    let steps = parse_steps(lines[n - 1]);
+    let block = Block::new(lines);
+    let n = block.len();
+    ensure!(n > 0, "empty input");
+
+    let map = parse_map(Block::new(&lines[..n - 1]))?;
+    let steps = block.parse_line(n - 1, parse_steps)?;
 
-    let (i, j, dir) = walk_map(map.view(), &steps);
+    let (i, j, dir) = walk_map(map.view(), &steps)?;
     println!("part A: {:?}", 1000 * i + 4 * j + dir);
 
     Ok(())
@@ -1,22 +1,22 @@
 use crate::common::*;
+use advent_common::parse::Block;
+use advent_common::sparse::{Point, SparseGrid};
 
-type Pos = [i64; 2];
+type Elves = SparseGrid<char>;
 
-fn parse_elves(lines: Lines) -> HashSet<Pos> {
-    let mut output = HashSet::default();
+fn parse_elves(lines: Lines) -> Result<Elves> {
+    let grid = Block::new(lines).parse_grid(|c| matches!(c, '#' | '.').then_some(c))?;
+    let mut elves = Elves::new('.');
 
-    for (y, line) in enumerate(lines) {
-        for (x, c) in enumerate(line.chars()) {
-            if c == '#' {
-                output.insert([x as i64, y as i64]);
-            }
-        }
+    for ([x, y], &c) in grid.enumerate() {
+        elves.insert([x as i64, y as i64], c);
     }
 
-    output
+    ensure!(!elves.is_empty(), "no elves");
+    Ok(elves)
 }
 
-fn simulate(input: &HashSet<Pos>, rounds: usize) -> HashSet<Pos> {
+fn simulate(input: &Elves, rounds: usize) -> Elves {
     let mut elves = input.clone();
 
     for round in 0..rounds {
@@ -26,7 +26,7 @@ fn simulate(input: &HashSet<Pos>, rounds: usize) -> HashSet<Pos> {
     elves
 }
 
-fn simulate_convergence(input: &HashSet<Pos>) -> usize {
+fn simulate_convergence(input: &Elves) -> Option<usize> {
     let mut elves = input.clone();
 
     for round in 0..10000000 {
@@ -34,24 +34,24 @@ fn simulate_convergence(input: &HashSet<Pos>) -> usize {
         elves = simulate_once(&old_elves, round);
 
         if elves == old_elves {
-            return round + 1;
+            return Some(round + 1);
         }
     }
 
-    panic!("did not converge");
+    None
 }
 
-fn simulate_once(elves: &HashSet<Pos>, round: usize) -> HashSet<Pos> {
+fn simulate_once(elves: &Elves, round: usize) -> Elves {
     let dirs = ['N', 'S', 'W', 'E'];
     let mut proposes = HashMap::default();
-    let mut occupied = HashMap::<Pos, usize>::default();
+    let mut occupied = HashMap::<Point, usize>::default();
 
-    for &[x, y] in elves {
+    for [x, y] in elves.points() {
         let mut neighbors = 0;
 
         for dx in [-1, 0, 1] {
             for dy in [-1, 0, 1] {
-                if elves.contains(&[x + dx, y + dy]) {
+                if elves.contains([x + dx, y + dy]) {
                     neighbors += 1;
                 }
             }
@@ -70,9 +70,9 @@ fn simulate_once(elves: &HashSet<Pos>, round: usize) -> HashSet<Pos> {
                     _ => unreachable!(),
                 };
 
-                if !elves.contains(&[x + dx, y + dy])
-                    && !elves.contains(&[x + dx + dy, y + dy - dx])
-                    && !elves.contains(&[x + dx - dy, y + dy + dx])
+                if !elves.contains([x + dx, y + dy])
+                    && !elves.contains([x + dx + dy, y + dy - dx])
+                    && !elves.contains([x + dx - dy, y + dy + dx])
                 {
                     nx += dx;
                     ny += dy;
@@ -85,63 +85,35 @@ fn simulate_once(elves: &HashSet<Pos>, round: usize) -> HashSet<Pos> {
         *occupied.entry([nx, ny]).or_default() += 1;
     }
 
-    proposes
-        .into_iter()
-        .map(|([x, y], [nx, ny])| {
-            if occupied[&[nx, ny]] == 1 {
-                [nx, ny]
-            } else {
-                [x, y]
-            }
-        })
-        .collect()
-}
+    let mut output = SparseGrid::new('.');
+
+    for ([x, y], [nx, ny]) in proposes {
+        if occupied[&[nx, ny]] == 1 {
+            output.insert([nx, ny], '#');
+        } else {
+            output.insert([x, y], '#');
+        }
+    }
 
-fn compute_bounds(elves: &HashSet<Pos>) -> ([i64; 2], [i64; 2]) {
-    let (min_x, max_x) = elves
-        .iter()
-        .map(|&[x, _]| x)
-        .minmax()
-        .into_option()
-        .unwrap();
-
-    let (min_y, max_y) = elves
-        .iter()
-        .map(|&[_, y]| y)
-        .minmax()
-        .into_option()
-        .unwrap();
-
-    ([min_x, max_x], [min_y, max_y])
+    output
 }
 
-fn compute_empty_tiles(elves: &HashSet<Pos>) -> i64 {
-    let ([x0, x1], [y0, y1]) = compute_bounds(&elves);
-    let area = (x1 - x0 + 1) * (y1 - y0 + 1);
-    area - (elves.len() as i64)
+fn compute_empty_tiles(elves: &Elves) -> i64 {
+    let bounds = elves.bounds().unwrap();
+    bounds.area() - (elves.len() as i64)
 }
 
 #[allow(dead_code)]
-fn visualize(elves: &HashSet<Pos>) {
-    let ([min_x, max_x], [min_y, max_y]) = compute_bounds(elves);
-
-    for y in (min_y - 1)..=(max_y + 1) {
-        for x in (min_x - 1)..=(max_x + 1) {
-            if elves.contains(&[x, y]) {
-                print!("#");
-            } else {
-                print!(".");
-            }
-        }
-
-        println!();
-    }
+fn visualize(elves: &Elves) {
+    let bounds = elves.bounds().unwrap().expand(1);
+    println!("{}", elves.render(bounds, |&c| c));
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let elves = parse_elves(lines);
-
+    let elves = parse_elves(lines)?;
     println!("part A: {}", compute_empty_tiles(&simulate(&elves, 10)));
-    println!("part B: {}", simulate_convergence(&elves));
+
+    let rounds = simulate_convergence(&elves).context("did not converge")?;
+    println!("part B: {}", rounds);
     Ok(())
 }
@@ -1,6 +1,9 @@
 use crate::common::*;
+use advent_common::math::lcm;
+use advent_common::parse::Block;
+use advent_common::search::bfs_distance;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 struct Blizzard {
     sx: i64,
     sy: i64,
@@ -8,23 +11,23 @@ struct Blizzard {
     dy: i64,
 }
 
-fn parse_blizzards(lines: Lines) -> ([i64; 2], Vec<Blizzard>) {
+fn parse_blizzards(lines: Lines) -> Result<([i64; 2], Vec<Blizzard>)> {
+    let grid = Block::new(lines).parse_grid(|c| match c {
+        '<' => Some(Some([-1, 0])),
+        '>' => Some(Some([1, 0])),
+        '^' => Some(Some([0, -1])),
+        'v' => Some(Some([0, 1])),
+        '.' | '#' => Some(None),
+        _ => None,
+    })?;
+
+    ensure!(grid.width() > 2 && grid.height() > 2, "valley is too small");
+    let height = grid.height() - 2;
+    let width = grid.width() - 2;
     let mut blizzards = vec![];
-    let height = lines.len() - 2;
-    let width = lines[0].chars().count() - 2;
-
-    for (y, line) in enumerate(lines) {
-        for (x, c) in enumerate(line.chars()) {
-            let [dx, dy] = match c {
-                '<' => [-1, 0],
-                '>' => [1, 0],
-                '^' => [0, -1],
-                'v' => [0, 1],
-                '.' => continue,
-                '#' => continue,
-                _ => panic!("invalid character: {:?}", c),
-            };
 
+    for ([x, y], &dir) in grid.enumerate() {
+        if let Some([dx, dy]) = dir {
             blizzards.push(Blizzard {
                 sx: x as i64 - 1,
                 sy: y as i64 - 1,
@@ -34,70 +37,83 @@ fn parse_blizzards(lines: Lines) -> ([i64; 2], Vec<Blizzard>) {
         }
     }
 
-    ([width as i64, height as i64], blizzards)
+    Ok(([width as i64, height as i64], blizzards))
 }
 
-fn search_path(
-    start: [i64; 2],
-    goal: [i64; 2],
+/// The cells covered by blizzards at every time step. Blizzards return to
+/// their starting positions after `lcm(width, height)` steps, so only that
+/// many steps need to be stored.
+struct Valley {
     dims: [i64; 2],
-    time_start: i64,
-    blizzards: &[Blizzard],
-) -> Option<i64> {
-    let [width, height] = dims;
-    let mut pos = HashSet::default();
-    pos.insert(start);
-    let mut t = time_start;
-
-    loop {
-        let mut obstacles = HashSet::default();
-        for b in blizzards {
-            obstacles.insert((
-                (b.sx + t * b.dx).rem_euclid(width),
-                (b.sy + t * b.dy).rem_euclid(height),
-            ));
-        }
-
-        let mut new_pos = HashSet::default();
-        for [x, y] in pos {
-            for (dx, dy) in [(0, 0), (0, 1), (0, -1), (1, 0), (-1, 0)] {
-                let (nx, ny) = (x + dx, y + dy);
-
-                if nx < 0 || ny < 0 || nx >= width || ny >= height {
-                    continue;
-                }
+    period: i64,
+    blocked: Vec<bool>,
+}
 
-                if !obstacles.contains(&(nx, ny)) {
-                    new_pos.insert([nx, ny]);
-                }
+impl Valley {
+    fn new(dims: [i64; 2], blizzards: &[Blizzard]) -> Self {
+        let [width, height] = dims;
+        let period = lcm(width, height);
+        let mut valley = Self {
+            dims,
+            period,
+            blocked: vec![false; (period * width * height) as usize],
+        };
+
+        for t in 0..period {
+            for b in blizzards {
+                let x = (b.sx + t * b.dx).rem_euclid(width);
+                let y = (b.sy + t * b.dy).rem_euclid(height);
+                let index = valley.index([x, y], t);
+                valley.blocked[index] = true;
             }
         }
 
-        if let Some(_) = new_pos.get(&goal) {
-            return Some(t);
-        }
+        valley
+    }
 
-        if new_pos.is_empty() {
-            return None;
-        }
+    fn index(&self, [x, y]: [i64; 2], t: i64) -> usize {
+        let [width, height] = self.dims;
+        ((t.rem_euclid(self.period) * height + y) * width + x) as usize
+    }
 
-        pos = new_pos;
-        t += 1;
+    fn is_blocked(&self, pos: [i64; 2], t: i64) -> bool {
+        self.blocked[self.index(pos, t)]
     }
 }
 
+fn search_path(valley: &Valley, start: [i64; 2], goal: [i64; 2], time_start: i64) -> Option<i64> {
+    let [width, height] = valley.dims;
+
+    // States are positions after a move at a given time, modulo the period
+    // of the blizzards. The expedition enters `start` during the first move.
+    let successors = |&([x, y], t): &([i64; 2], i64)| {
+        [(0, 0), (0, 1), (0, -1), (1, 0), (-1, 0)]
+            .into_iter()
+            .map(move |(dx, dy)| ([x + dx, y + dy], (t + 1) % valley.period))
+            .filter(|&([nx, ny], t)| {
+                nx >= 0 && ny >= 0 && nx < width && ny < height && !valley.is_blocked([nx, ny], t)
+            })
+    };
+
+    let initial = (start, (time_start - 1).rem_euclid(valley.period));
+    let steps = bfs_distance([initial], successors, |&(pos, _)| pos == goal)?;
+
+    Some(time_start - 1 + steps as i64)
+}
+
 pub(crate) fn run(lines: Lines) -> Result {
-    let (dims, blizzards) = parse_blizzards(lines);
+    let (dims, blizzards) = parse_blizzards(lines)?;
+    let valley = Valley::new(dims, &blizzards);
     let start = [0, 0];
     let goal = [dims[0] - 1, dims[1] - 1];
 
     // Walk from start to goal
-    let mut t = search_path(start, goal, dims, 0, &blizzards).unwrap() + 1;
+    let mut t = search_path(&valley, start, goal, 0).context("goal cannot be reached")? + 1;
     println!("part A: {}", t);
 
     // Walk from goal back to start
     let mut t = loop {
-        if let Some(t) = search_path(goal, start, dims, t, &blizzards) {
+        if let Some(t) = search_path(&valley, goal, start, t) {
             break t + 1;
         }
 
@@ -106,7 +122,7 @@ pub(crate) fn run(lines: Lines) -> Result {
 
     // Walk from start back to goal
     let t = loop {
-        if let Some(t) = search_path(start, goal, dims, t, &blizzards) {
+        if let Some(t) = search_path(&valley, start, goal, t) {
             break t + 1;
         }
 
@@ -1,21 +1,15 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
-fn parse_snafu(input: &str) -> i64 {
+fn parse_snafu(c: &mut Cursor) -> ParseResult<i64> {
     let mut output = 0;
 
-    for c in input.chars() {
+    while !c.is_empty() {
         output *= 5;
-        output += match c {
-            '2' => 2,
-            '1' => 1,
-            '0' => 0,
-            '-' => -1,
-            '=' => -2,
-            _ => panic!("invalid character: {:?}", c),
-        };
+        output += c.one_of(&[("2", 2), ("1", 1), ("0", 0), ("-", -1), ("=", -2)])?;
     }
 
-    output
+    Ok(output)
 }
 
 fn generate_snafu(mut input: i64) -> String {
@@ -52,7 +46,7 @@ fn generate_snafu(mut input: i64) -> String {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let numbers = lines.iter().map(|l| parse_snafu(l)).collect_vec();
+    let numbers = Block::new(lines).parse_lines(parse_snafu)?;
 
     let total = numbers.iter().sum::<i64>();
 
@@ -64,16 +58,21 @@ pub(crate) fn run(lines: Lines) -> Result {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_common::parse::parse_line;
+
+    fn snafu(input: &str) -> i64 {
+        parse_line(1, input, parse_snafu).unwrap()
+    }
 
     #[test]
     fn test_a() {
-        assert_eq!(parse_snafu("2=-01"), 976);
-        assert_eq!(parse_snafu("1-0---0"), 12345);
-        assert_eq!(parse_snafu("1121-1110-1=0"), 314159265);
+        assert_eq!(snafu("2=-01"), 976);
+        assert_eq!(snafu("1-0---0"), 12345);
+        assert_eq!(snafu("1121-1110-1=0"), 314159265);
 
         for i in 1..100 {
             assert_eq!(
-                parse_snafu(&generate_snafu(i)),
+                snafu(&generate_snafu(i)),
                 i,
                 "invalid snafu number {}: {}",
                 i,
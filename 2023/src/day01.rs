@@ -1,9 +1,10 @@
 use crate::common::*;
 
-fn find_calibration_value(line: &str) -> u32 {
-    let digits = line.chars().filter_map(|c| c.to_digit(10));
-    let (a, b) = (digits.clone().next().unwrap(), digits.last().unwrap());
-    a * 10 + b
+fn find_calibration_value(line: &str) -> Option<u32> {
+    let mut digits = line.chars().filter_map(|c| c.to_digit(10));
+    let a = digits.next()?;
+    let b = digits.last().unwrap_or(a);
+    Some(a * 10 + b)
 }
 
 fn replace_digit_words(line: &str) -> String {
@@ -33,16 +34,16 @@ fn replace_digit_words(line: &str) -> String {
 pub(crate) fn run(lines: Lines) -> Result {
     let sum: u32 = lines
         .into_iter()
-        .map(|line| find_calibration_value(line))
-        .sum();
+        .map(|line| find_calibration_value(line).context("no digits in line"))
+        .sum::<Result<_>>()?;
 
     println!("part A: {}", sum);
 
     let sum: u32 = lines
         .into_iter()
         .map(|line| replace_digit_words(line))
-        .map(|line| find_calibration_value(&line))
-        .sum();
+        .map(|line| find_calibration_value(&line).context("no digits in line"))
+        .sum::<Result<_>>()?;
 
     println!("part B: {}", sum);
 
@@ -55,30 +56,33 @@ mod tests {
 
     #[test]
     fn test_b() {
-        assert_eq!(find_calibration_value(&replace_digit_words("two1nine")), 29);
+        assert_eq!(
+            find_calibration_value(&replace_digit_words("two1nine")),
+            Some(29)
+        );
         assert_eq!(
             find_calibration_value(&replace_digit_words("eightwothree")),
-            83
+            Some(83)
         );
         assert_eq!(
             find_calibration_value(&replace_digit_words("abcone2threexyz")),
-            13
+            Some(13)
         );
         assert_eq!(
             find_calibration_value(&replace_digit_words("xtwone3four")),
-            24
+            Some(24)
         );
         assert_eq!(
             find_calibration_value(&replace_digit_words("4nineeightseven2")),
-            42
+            Some(42)
         );
         assert_eq!(
             find_calibration_value(&replace_digit_words("zoneight234")),
-            14
+            Some(14)
         );
         assert_eq!(
             find_calibration_value(&replace_digit_words("7pqrstsixteen")),
-            76
+            Some(76)
         );
     }
 }
@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
 type CubeSet = [i32; 3];
 
@@ -16,36 +17,34 @@ impl Game {
     }
 }
 
-fn parse_game(line: &str) -> Game {
-    let sets = line
-        .split(": ")
-        .nth(1)
-        .unwrap_or_default()
-        .split("; ")
-        .map(|round| {
-            let mut set = CubeSet::default();
-
-            for cubes in round.split(", ") {
-                let (count, color) = cubes.split_once(" ").unwrap();
-                let count = count.parse::<i32>().unwrap();
-
-                match color {
-                    "red" => set[0] += count,
-                    "green" => set[1] += count,
-                    "blue" => set[2] += count,
-                    _ => panic!("invalid color: `{color}`"),
-                }
-            }
-
-            set
-        })
-        .collect();
-
-    Game(sets)
+fn parse_cubes(c: &mut Cursor) -> ParseResult<(i32, usize)> {
+    let count = c.int()?;
+    c.tag(" ")?;
+    let color = c.one_of(&[("red", 0), ("green", 1), ("blue", 2)])?;
+
+    Ok((count, color))
+}
+
+fn parse_game(c: &mut Cursor) -> ParseResult<Game> {
+    c.tag("Game ")?;
+    c.int::<usize>()?;
+    c.tag(": ")?;
+
+    let sets = c.list("; ", |c| {
+        let mut set = CubeSet::default();
+
+        for (count, color) in c.list(", ", parse_cubes)? {
+            set[color] += count;
+        }
+
+        Ok(set)
+    })?;
+
+    Ok(Game(sets))
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let games = lines.iter().map(|line| parse_game(line)).collect_vec();
+    let games = Block::new(lines).parse_lines(parse_game)?;
     let target = [12, 13, 14];
 
     let sum: usize = games
@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
 struct Card {
     winning: Vec<u32>,
@@ -21,30 +22,45 @@ impl Card {
     }
 }
 
-fn parse_card(line: &str) -> Card {
-    let (_, line) = line.split_once(": ").unwrap();
-    let (left, right) = line.split_once(" | ").unwrap();
+// Numbers that are each preceded by one or more spaces.
+fn parse_numbers(c: &mut Cursor) -> Vec<u32> {
+    let mut numbers = vec![];
 
-    Card {
-        winning: parse_list::<u32>(left, ' ').unwrap(),
-        scratched: parse_list::<u32>(right, ' ').unwrap(),
+    while let Some(v) = c.attempt(|c| {
+        c.tag(" ")?;
+        c.take_while(|c| c == ' ');
+        c.int()
+    }) {
+        numbers.push(v);
     }
+
+    numbers
+}
+
+fn parse_card(c: &mut Cursor) -> ParseResult<Card> {
+    c.tag("Card")?;
+    c.take_while(|c| c == ' ');
+    c.int::<usize>()?;
+    c.tag(":")?;
+    let winning = parse_numbers(c);
+    c.tag(" |")?;
+    let scratched = parse_numbers(c);
+
+    Ok(Card { winning, scratched })
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let sum: u32 = lines
-        .iter()
-        .map(|line| parse_card(line).calculate_points())
-        .sum();
+    let cards = Block::new(lines).parse_lines(parse_card)?;
+    let sum: u32 = cards.iter().map(|card| card.calculate_points()).sum();
 
     println!("part A: {sum}");
 
-    let mut count = vec![1; lines.len()];
-    for (index, line) in enumerate(lines) {
-        let matches = parse_card(line).calculate_matches() as usize;
+    let mut count = vec![1; cards.len()];
+    for (index, card) in enumerate(&cards) {
+        let matches = card.calculate_matches() as usize;
         let n = count[index];
 
-        for v in &mut count[index..][1..=matches] {
+        for v in count[index..].iter_mut().skip(1).take(matches) {
             *v += n;
         }
     }
@@ -57,31 +73,36 @@ pub(crate) fn run(lines: Lines) -> Result {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_common::parse::parse_line;
+
+    fn parse(line: &str) -> Card {
+        parse_line(1, line, parse_card).unwrap()
+    }
 
     #[test]
     fn test_a() {
         assert_eq!(
-            parse_card("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53").calculate_points(),
+            parse("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53").calculate_points(),
             8
         );
         assert_eq!(
-            parse_card("Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19").calculate_points(),
+            parse("Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19").calculate_points(),
             2
         );
         assert_eq!(
-            parse_card("Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1").calculate_points(),
+            parse("Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1").calculate_points(),
             2
         );
         assert_eq!(
-            parse_card("Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83").calculate_points(),
+            parse("Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83").calculate_points(),
             1
         );
         assert_eq!(
-            parse_card("Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36").calculate_points(),
+            parse("Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36").calculate_points(),
             0
         );
         assert_eq!(
-            parse_card("Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11").calculate_points(),
+            parse("Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11").calculate_points(),
             0
         );
     }
@@ -1,11 +1,25 @@
 use crate::common::*;
+use advent_common::parse::Block;
 use std::iter::zip;
 
-fn parse_races(times: &str, dists: &str) -> Vec<(i64, i64)> {
-    let times = parse_list(times.strip_prefix("Time:").unwrap(), ' ').unwrap();
-    let dists = parse_list(dists.strip_prefix("Distance:").unwrap(), ' ').unwrap();
-
-    zip(times, dists).collect()
+fn parse_races<S: AsRef<str>>(lines: &[S]) -> Result<Vec<(i64, i64)>> {
+    let block = Block::new(lines);
+    ensure!(block.len() == 2, "expected two lines");
+
+    let times = block.parse_line(0, |c| {
+        c.tag("Time:")?;
+        c.ints()
+    })?;
+    let dists = block.parse_line(1, |c| {
+        c.tag("Distance:")?;
+        c.ints()
+    })?;
+    ensure!(
+        times.len() == dists.len(),
+        "every race needs a time and a distance"
+    );
+
+    Ok(zip(times, dists).collect())
 }
 
 fn number_of_ways(total_time: i64, distance: i64) -> usize {
@@ -24,13 +38,14 @@ fn number_of_ways(total_time: i64, distance: i64) -> usize {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let races = parse_races(lines[0], lines[1]);
+    let races = parse_races(lines)?;
 
     let product: usize = races.iter().map(|&(t, d)| number_of_ways(t, d)).product();
     println!("part A: {}", product);
 
     // remove whitespaces between times
-    let races = parse_races(&lines[0].replace(' ', ""), &lines[1].replace(' ', ""));
+    let lines = lines.iter().map(|line| line.replace(' ', "")).collect_vec();
+    let races = parse_races(&lines)?;
     let product: usize = races.iter().map(|&(t, d)| number_of_ways(t, d)).product();
     println!("part B: {}", product);
 
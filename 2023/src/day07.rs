@@ -1,16 +1,26 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use std::array;
 
 type Hand = [char; 5];
 const SYMBOLS: &str = "AKQJT98765432";
 
-fn parse_hand(line: &str) -> (Hand, i64) {
-    let (hand, bid) = line.split_once(' ').unwrap();
+fn parse_hand(c: &mut Cursor) -> ParseResult<(Hand, i64)> {
+    let mut hand = Hand::default();
 
-    let hand = hand.chars().collect_vec().try_into().unwrap();
+    for card in &mut hand {
+        let start = *c;
+        *card = c.next_char()?;
 
-    let bid = bid.parse().unwrap();
-    (hand, bid)
+        if !SYMBOLS.contains(*card) {
+            return Err(start.error(format_args!("invalid card {:?}", card)));
+        }
+    }
+
+    c.tag(" ")?;
+    let bid = c.int()?;
+
+    Ok((hand, bid))
 }
 
 fn strength(hand: Hand) -> [usize; 5] {
@@ -90,7 +100,7 @@ fn calculate_winnings(hands: &[(Hand, i64)]) -> i64 {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let mut hands = lines.iter().map(|line| parse_hand(line)).collect_vec();
+    let mut hands = Block::new(lines).parse_lines(parse_hand)?;
 
     hands.sort_by_key(|&(hand, _)| (kind(hand), strength(hand)));
     hands.reverse();
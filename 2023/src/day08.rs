@@ -1,29 +1,53 @@
 use crate::common::*;
-use num::integer::lcm;
+use advent_common::cycle::{find_cycle, Cycle};
+use advent_common::math::{crt, gcd};
+use advent_common::parse::{Block, Cursor, ParseResult, Sections};
 
 struct Graph<'a> {
     nodes: Vec<&'a str>,
     edges: Vec<(usize, usize)>,
 }
 
-fn parse_graph<'a>(lines: &[&'a str]) -> Graph<'a> {
-    let nodes = lines.iter().map(|l| &l[..3]).collect_vec();
+fn parse_node<'a>(c: &mut Cursor<'a>) -> ParseResult<[&'a str; 3]> {
+    let name = c.word()?;
+    c.tag(" = (")?;
+    let left = c.word()?;
+    c.tag(", ")?;
+    let right = c.word()?;
+    c.tag(")")?;
+
+    Ok([name, left, right])
+}
+
+fn parse_graph<'a>(block: Block<'a, &'a str>) -> Result<Graph<'a>> {
+    let lines = block.parse_lines(parse_node)?;
+    let nodes = lines.iter().map(|&[name, _, _]| name).collect_vec();
+    let index = |name| {
+        nodes
+            .iter()
+            .position(|&n| n == name)
+            .with_context(|| format!("unknown node {:?}", name))
+    };
+
     let edges = lines
         .iter()
-        .map(|line| {
-            (
-                nodes.iter().position(|&n| n == &line[7..10]).unwrap(),
-                nodes.iter().position(|&n| n == &line[12..15]).unwrap(),
-            )
-        })
-        .collect_vec();
+        .map(|&[_, left, right]| Ok((index(left)?, index(right)?)))
+        .collect::<Result<Vec<_>>>()?;
 
-    Graph { nodes, edges }
+    Ok(Graph { nodes, edges })
 }
 
-fn navigate(directions: &[char], g: &Graph) -> usize {
-    let mut current = g.nodes.iter().position(|&x| x == "AAA").unwrap();
-    let end = g.nodes.iter().position(|&x| x == "ZZZ").unwrap();
+fn navigate(directions: &[char], g: &Graph) -> Result<usize> {
+    let mut current = g
+        .nodes
+        .iter()
+        .position(|&x| x == "AAA")
+        .context("no node AAA")?;
+    let end = g
+        .nodes
+        .iter()
+        .position(|&x| x == "ZZZ")
+        .context("no node ZZZ")?;
 
     let mut steps = 0;
     while current != end {
@@ -37,55 +61,165 @@ fn navigate(directions: &[char], g: &Graph) -> usize {
 
         steps += 1;
     }
-    steps
+    Ok(steps)
 }
 
-fn navigate_ghost(directions: &[char], g: &Graph) -> usize {
-    let mut result = 1;
+/// Path of a single ghost. After `cycle.start` steps the ghost repeats the
+/// same `cycle.len` steps forever, `is_z[i]` tells if it is at a Z node
+/// after `i` steps for every step before the first repetition.
+struct Ghost {
+    cycle: Cycle,
+    is_z: Vec<bool>,
+}
 
-    for (index, node) in enumerate(&g.nodes) {
-        if !node.ends_with('A') {
-            continue;
-        }
+impl Ghost {
+    fn at_z(&self, steps: usize) -> bool {
+        self.is_z[self.cycle.reduce(steps)]
+    }
+}
 
-        let mut steps = 0;
-        let mut visited = HashSet::default();
-        let mut path = vec![];
-        let mut current = index;
+fn trace_ghost(directions: &[char], g: &Graph, start: usize) -> Ghost {
+    let (cycle, path) = find_cycle((start, 0), |&(current, steps)| {
+        let next = if directions[steps] == 'L' {
+            g.edges[current].0
+        } else {
+            g.edges[current].1
+        };
 
-        while visited.insert((current, steps)) {
-            path.push((current, steps));
+        (next, (steps + 1) % directions.len())
+    });
 
-            current = if directions[steps] == 'L' {
-                g.edges[current].0
-            } else {
-                g.edges[current].1
-            };
+    let is_z = path
+        .iter()
+        .map(|&(n, _)| g.nodes[n].ends_with('Z'))
+        .collect();
+    Ghost { cycle, is_z }
+}
 
-            steps = (steps + 1) % directions.len();
-        }
+// Give up if the ghosts are at Z nodes at more than this many combinations
+// of offsets within their cycles.
+const MAX_SOLUTIONS: usize = 1_000_000;
+
+fn navigate_ghost(directions: &[char], g: &Graph) -> Result<usize> {
+    let ghosts = g
+        .nodes
+        .iter()
+        .positions(|node| node.ends_with('A'))
+        .map(|start| trace_ghost(directions, g, start))
+        .collect_vec();
 
-        let length = path.len() - steps;
+    // Check every step until all ghosts have entered their cycle.
+    let prefix = ghosts
+        .iter()
+        .map(|ghost| ghost.cycle.start)
+        .max()
+        .unwrap_or(0);
 
-        // It seems to be always that the node at path.len() - steps ends with Z
-        let last_node = g.nodes[path[length].0];
-        assert!(last_node.ends_with('Z'));
+    if let Some(steps) = (0..prefix).find(|&s| ghosts.iter().all(|ghost| ghost.at_z(s))) {
+        return Ok(steps);
+    }
 
-        result = lcm(result, length);
+    // The combined system repeats after the lcm of the cycle lengths. Check
+    // that it fits up front, so that `crt` only fails if there is no solution.
+    ghosts
+        .iter()
+        .map(|ghost| ghost.cycle.len as i64)
+        .try_fold(1i64, |l, len| l.checked_mul(len / gcd(l, len)))
+        .context("the combined cycle length of the ghosts overflows i64")?;
+
+    // From then on, a ghost is at a Z node if the number of steps is
+    // congruent to one of the offsets of the Z nodes within its cycle.
+    let mut solutions = vec![(0, 1)];
+
+    for ghost in &ghosts {
+        let Cycle { start, len } = ghost.cycle;
+        let offsets = (start..start + len)
+            .filter(|&i| ghost.is_z[i])
+            .collect_vec();
+
+        // Every combination of a solution and an offset is a candidate.
+        ensure!(
+            solutions.len() * offsets.len() <= MAX_SOLUTIONS,
+            "more than {} combinations of Z offsets",
+            MAX_SOLUTIONS
+        );
+
+        solutions = solutions
+            .iter()
+            .cartesian_product(&offsets)
+            .filter_map(|(&s, &offset)| crt(&[s, (offset as i64, len as i64)]))
+            .unique()
+            .collect();
     }
 
-    result
+    let prefix = prefix as i64;
+    let steps = solutions
+        .into_iter()
+        .map(|(x, l)| {
+            if x >= prefix {
+                x
+            } else {
+                x + (prefix - x + l - 1) / l * l
+            }
+        })
+        .min()
+        .context("ghosts are never at Z nodes at the same time")?;
+
+    Ok(steps as usize)
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let directions = lines[0].chars().collect_vec();
-    let graph = parse_graph(&lines[2..]);
+    let [directions, nodes] = Sections::new(lines).blocks()?;
+    let directions = directions
+        .parse_single(|c| Ok(c.take_while(|c| c == 'L' || c == 'R')))?
+        .chars()
+        .collect_vec();
+    ensure!(!directions.is_empty(), "no directions");
 
-    let steps = navigate(&directions, &graph);
+    let graph = parse_graph(nodes)?;
+    let steps = navigate(&directions, &graph)?;
     println!("part A: {}", steps);
 
-    let steps = navigate_ghost(&directions, &graph);
+    let steps = navigate_ghost(&directions, &graph)?;
     println!("part B: {}", steps);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_b() {
+        let lines = [
+            "11A = (11B, XXX)",
+            "11B = (XXX, 11Z)",
+            "11Z = (11B, XXX)",
+            "22A = (22B, XXX)",
+            "22B = (22C, 22C)",
+            "22C = (22Z, 22Z)",
+            "22Z = (22B, 22B)",
+            "XXX = (XXX, XXX)",
+        ];
+        let graph = parse_graph(Block::new(&lines)).unwrap();
+        assert_eq!(navigate_ghost(&['L', 'R'], &graph).unwrap(), 6);
+
+        // The ghosts are at Z nodes after 2 + 3k and 1 + 5k steps, so they
+        // meet after 11 steps instead of the 15 that lcm would give.
+        let lines = [
+            "11A = (11B, 11B)",
+            "11B = (11Z, 11Z)",
+            "11Z = (11C, 11C)",
+            "11C = (11B, 11B)",
+            "22A = (22Z, 22Z)",
+            "22Z = (22B, 22B)",
+            "22B = (22C, 22C)",
+            "22C = (22D, 22D)",
+            "22D = (22E, 22E)",
+            "22E = (22Z, 22Z)",
+        ];
+        let graph = parse_graph(Block::new(&lines)).unwrap();
+        assert_eq!(navigate_ghost(&['L'], &graph).unwrap(), 11);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor};
 
 fn predict_next(numbers: &[i64]) -> i64 {
     if let Some(last) = numbers.last() {
@@ -27,10 +28,7 @@ fn predict_prev(numbers: &[i64]) -> i64 {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let numbers = lines
-        .iter()
-        .map(|l| parse_list::<i64>(l, ' ').unwrap())
-        .collect_vec();
+    let numbers: Vec<Vec<i64>> = Block::new(lines).parse_lines(|c| c.list(" ", Cursor::int))?;
 
     println!("part A: {}", sum(map(&numbers, |n| predict_next(&n))));
     println!("part B: {}", sum(map(&numbers, |n| predict_prev(&n))));
@@ -1,39 +1,36 @@
 use crate::common::*;
+use advent_common::grid::{Grid, Pos};
+use advent_common::polygon::Polygon;
 use std::collections::VecDeque;
 
-type Pos = [i64; 2];
-type Map = HashMap<Pos, char>;
+type Map = Grid<char>;
 
-fn parse_map(lines: Lines) -> (Map, Pos) {
-    let mut map = HashMap::default();
-
-    for (y, line) in enumerate(lines) {
-        for (x, c) in line.chars().enumerate() {
-            map.insert([x as i64, y as i64], c);
-        }
-    }
-
-    let (&start, _) = find(&map, |(_, &c)| c == 'S').unwrap();
+fn parse_map(lines: Lines) -> Result<(Map, Pos)> {
+    let mut map = Grid::parse(lines)?;
+    let start = map
+        .find(|&c| c == 'S')
+        .ok_or_else(|| anyhow!("no start position found"))?;
 
     // Find the symbol for start
-    for symbol in ['|', '-', 'L', 'J', '7', 'F'] {
-        map.insert(start, symbol);
-
-        if neighbors(&map, start).all(|nb| neighbors(&map, nb).contains(&start)) {
-            break;
-        }
-    }
-
-    (map, start)
+    let symbol = ['|', '-', 'L', 'J', '7', 'F']
+        .into_iter()
+        .find(|&symbol| {
+            map[start] = symbol;
+            neighbors(&map, start).all(|nb| neighbors(&map, nb).contains(&start))
+        })
+        .context("start is not connected to a loop")?;
+    map[start] = symbol;
+
+    Ok((map, start))
 }
 
-fn neighbors(map: &Map, [x, y]: Pos) -> impl Iterator<Item = Pos> {
-    const NORTH: Pos = [0, -1];
-    const SOUTH: Pos = [0, 1];
-    const WEST: Pos = [-1, 0];
-    const EAST: Pos = [1, 0];
+fn neighbors(map: &Map, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+    const NORTH: [isize; 2] = [0, -1];
+    const SOUTH: [isize; 2] = [0, 1];
+    const WEST: [isize; 2] = [-1, 0];
+    const EAST: [isize; 2] = [1, 0];
 
-    let delta = match map.get(&[x, y]).copied().unwrap_or_default() {
+    let delta = match map.get(pos).copied().unwrap_or_default() {
         '|' => &[NORTH, SOUTH] as &[_],
         '-' => &[EAST, WEST],
         'L' => &[NORTH, EAST],
@@ -43,7 +40,7 @@ fn neighbors(map: &Map, [x, y]: Pos) -> impl Iterator<Item = Pos> {
         _ => &[],
     };
 
-    delta.iter().map(move |[dx, dy]| [x + dx, y + dy])
+    delta.iter().filter_map(move |&d| map.offset(pos, d))
 }
 
 fn visit_map(map: &Map, start: Pos) -> HashMap<Pos, usize> {
@@ -62,36 +59,41 @@ fn visit_map(map: &Map, start: Pos) -> HashMap<Pos, usize> {
     visited
 }
 
-fn find_area(map: &Map, path: &HashMap<Pos, usize>) -> HashSet<Pos> {
-    let (x_min, x_max) = map.keys().map(|p| p[0]).minmax().into_option().unwrap();
-    let (y_min, y_max) = map.keys().map(|p| p[1]).minmax().into_option().unwrap();
-
-    let mut inside = HashSet::default();
+fn trace_loop(map: &Map, start: Pos) -> Result<Vec<Pos>> {
+    let mut path = vec![start];
+    let mut prev = start;
+    let mut current = neighbors(map, start).next().context("start has no pipe")?;
 
-    for y in y_min..=y_max {
-        let mut is_inside = false;
+    while current != start {
+        path.push(current);
 
-        for x in x_min..=x_max {
-            if path.contains_key(&[x, y]) {
-                let c = map.get(&[x, y]).unwrap_or(&'.');
-                is_inside = is_inside ^ ['|', 'L', 'J'].contains(c);
-            } else if is_inside {
-                inside.insert([x, y]);
-            }
-        }
+        let next = neighbors(map, current)
+            .find(|&nb| nb != prev)
+            .with_context(|| format!("pipe loop is broken at {:?}", current))?;
+        prev = current;
+        current = next;
     }
 
-    inside
+    Ok(path)
+}
+
+fn find_area(map: &Map, start: Pos) -> Result<i64> {
+    let vertices = trace_loop(map, start)?
+        .into_iter()
+        .map(|[x, y]| [x as i64, y as i64])
+        .collect();
+
+    Ok(Polygon::new(vertices).interior_points())
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let (map, start) = parse_map(lines);
+    let (map, start) = parse_map(lines)?;
 
     let path = visit_map(&map, start);
     println!("part A: {:?}", path.values().max());
 
-    let inside = find_area(&map, &path);
-    println!("part B: {}", inside.len());
+    let inside = find_area(&map, start)?;
+    println!("part B: {}", inside);
 
     Ok(())
 }
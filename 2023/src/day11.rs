@@ -1,24 +1,25 @@
 use crate::common::*;
+use advent_common::parse::Block;
 
 type Pos = [i64; 2];
 
-fn parse_galaxies(lines: Lines) -> HashSet<Pos> {
-    let mut galaxies = HashSet::default();
+fn parse_galaxies(lines: Lines) -> Result<HashSet<Pos>> {
+    let grid = Block::new(lines).parse_grid(|c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })?;
 
-    for (y, &line) in enumerate(lines) {
-        for (x, c) in enumerate(line.chars()) {
-            if c == '#' {
-                galaxies.insert([x as i64, y as i64]);
-            }
-        }
-    }
-
-    galaxies
+    Ok(grid
+        .enumerate()
+        .filter(|(_, &galaxy)| galaxy)
+        .map(|([x, y], _)| [x as i64, y as i64])
+        .collect())
 }
 
 fn expand_and_sum_1d(galaxies: &HashSet<Pos>, axis: usize, factor: i64) -> i64 {
     let mut xs = galaxies.iter().map(|p| p[axis]).collect::<HashSet<_>>();
-    let max_x = *xs.iter().max().unwrap();
+    let max_x = xs.iter().copied().max().unwrap_or_default();
     let mut map = vec![0i64];
     let mut current = 0;
 
@@ -43,7 +44,7 @@ fn expand_and_sum(galaxies: &HashSet<Pos>, factor: i64) -> i64 {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let g = parse_galaxies(lines);
+    let g = parse_galaxies(lines)?;
 
     println!("part A: {}", expand_and_sum(&g, 2));
     println!("part B: {}", expand_and_sum(&g, 1000000));
@@ -68,7 +69,7 @@ mod tests {
 
     #[test]
     fn test() {
-        let g = parse_galaxies(&LINES);
+        let g = parse_galaxies(&LINES).unwrap();
         assert_eq!(expand_and_sum(&g, 2), 374);
         assert_eq!(expand_and_sum(&g, 10), 1030);
         assert_eq!(expand_and_sum(&g, 100), 8410);
@@ -1,4 +1,6 @@
 use crate::common::*;
+use advent_common::memo::memoize;
+use advent_common::parse::{Block, Cursor, ParseResult};
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 struct Record {
@@ -6,53 +8,45 @@ struct Record {
     groups: Vec<usize>,
 }
 
-fn parse_record(line: &str) -> Record {
-    let (condition, groups) = line.split_once(' ').unwrap();
+fn parse_record(c: &mut Cursor) -> ParseResult<Record> {
+    let condition = c.take_while(|c| matches!(c, '.' | '#' | '?'));
+    c.tag(" ")?;
+    let groups = c.list(",", Cursor::int)?;
 
-    Record {
+    Ok(Record {
         condition: condition.chars().collect(),
-        groups: parse_list(groups, ',').unwrap(),
-    }
+        groups,
+    })
 }
 
-fn count_arrangements_recur<'a>(
-    condition: &'a [char],
-    groups: &'a [usize],
-    cache: &mut HashMap<(&'a [char], &'a [usize]), usize>,
-) -> usize {
-    if condition.is_empty() {
-        return groups.is_empty() as usize;
-    }
+fn count_arrangements(record: &Record) -> usize {
+    let key = (&record.condition[..], &record.groups[..]);
 
-    let key = (condition, groups);
-    if let Some(&result) = cache.get(&key) {
-        return result;
-    }
+    memoize(key, |recur, &(condition, groups)| {
+        if condition.is_empty() {
+            return groups.is_empty() as usize;
+        }
 
-    let mut result = 0;
+        let mut result = 0;
 
-    if condition[0] != '#' {
-        result += count_arrangements_recur(&condition[1..], groups, cache);
-    }
+        if condition[0] != '#' {
+            result += recur((&condition[1..], groups));
+        }
 
-    if condition[0] != '.' {
-        if let Some(&n) = groups.get(0) {
-            if (0..n).all(|i| condition.get(i).unwrap_or(&'.') != &'.') {
-                result += match condition.get(n) {
-                    None => (groups.len() == 1) as usize,
-                    Some('#') => 0,
-                    Some(_) => count_arrangements_recur(&condition[n + 1..], &groups[1..], cache),
-                };
+        if condition[0] != '.' {
+            if let Some(&n) = groups.get(0) {
+                if (0..n).all(|i| condition.get(i).unwrap_or(&'.') != &'.') {
+                    result += match condition.get(n) {
+                        None => (groups.len() == 1) as usize,
+                        Some('#') => 0,
+                        Some(_) => recur((&condition[n + 1..], &groups[1..])),
+                    };
+                }
             }
         }
-    }
 
-    cache.insert(key, result);
-    result
-}
-
-fn count_arrangements(record: &Record) -> usize {
-    count_arrangements_recur(&record.condition, &record.groups, &mut default())
+        result
+    })
 }
 
 fn unfold_record(record: &Record) -> Record {
@@ -68,7 +62,7 @@ fn unfold_record(record: &Record) -> Record {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let records = lines.iter().map(|line| parse_record(line)).collect_vec();
+    let records = Block::new(lines).parse_lines(parse_record)?;
 
     let total: usize = records.iter().map(count_arrangements).sum();
 
@@ -87,10 +81,15 @@ pub(crate) fn run(lines: Lines) -> Result {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_common::parse::parse_line;
+
+    fn record(line: &str) -> Record {
+        parse_line(1, line, parse_record).unwrap()
+    }
 
     #[test]
     fn test_a() {
-        let f = |l| count_arrangements(&parse_record(l));
+        let f = |l| count_arrangements(&record(l));
 
         assert_eq!(f("???.### 1,1,3"), 1);
         assert_eq!(f(".??..??...?##. 1,1,3"), 4);
@@ -102,7 +101,7 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let f = |l| count_arrangements(&unfold_record(&parse_record(l)));
+        let f = |l| count_arrangements(&unfold_record(&record(l)));
 
         assert_eq!(f("???.### 1,1,3"), 1);
         assert_eq!(f(".??..??...?##. 1,1,3"), 16384);
@@ -1,29 +1,19 @@
 use crate::common::*;
+use advent_common::parse::Sections;
 use ndarray::{Array2, ArrayView2};
 
-fn parse_maps(lines: Lines) -> Vec<Array2<char>> {
-    let mut maps = vec![];
-    let mut index = 0;
+fn parse_maps(lines: Lines) -> Result<Vec<Array2<char>>> {
+    Sections::new(lines)
+        .iter()
+        .map(|block| {
+            let grid = block.parse_grid(|c| matches!(c, '#' | '.').then_some(c))?;
+            let (width, height) = (grid.width(), grid.height());
 
-    while index < lines.len() {
-        let height = lines[index..]
-            .iter()
-            .position(|l| l.is_empty())
-            .unwrap_or(lines.len() - index);
-        let width = lines[index].chars().count();
-        let mut map = Array2::<char>::from_elem((width, height), ' ');
-
-        for (y, line) in enumerate(&lines[index..][..height]) {
-            for (x, c) in enumerate(line.chars()) {
-                map[[x, y]] = c;
-            }
-        }
-
-        maps.push(map);
-        index += height + 1;
-    }
-
-    maps
+            Ok(Array2::from_shape_fn((width, height), |(x, y)| {
+                grid[[x, y]]
+            }))
+        })
+        .collect()
 }
 
 fn find_vertical_reflection(map: ArrayView2<char>, smudges: usize) -> Option<usize> {
@@ -58,16 +48,20 @@ fn find_reflection(map: ArrayView2<char>, smudges: usize) -> Option<usize> {
     }
 }
 
-fn summarize_maps(maps: &[Array2<char>], smudges: usize) -> usize {
+fn summarize_maps(maps: &[Array2<char>], smudges: usize) -> Result<usize> {
     maps.iter()
-        .map(|m| find_reflection(m.view(), smudges).unwrap())
+        .enumerate()
+        .map(|(i, m)| {
+            find_reflection(m.view(), smudges)
+                .with_context(|| format!("map {} has no reflection", i + 1))
+        })
         .sum()
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let maps = parse_maps(lines);
+    let maps = parse_maps(lines)?;
 
-    println!("part A: {}", summarize_maps(&maps, 0));
-    println!("part A: {}", summarize_maps(&maps, 1));
+    println!("part A: {}", summarize_maps(&maps, 0)?);
+    println!("part A: {}", summarize_maps(&maps, 1)?);
     Ok(())
 }
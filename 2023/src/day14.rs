@@ -1,18 +1,9 @@
 use crate::common::*;
-use ndarray::{Array2, ArrayView2};
+use advent_common::cycle::nth_state;
+use advent_common::grid::{Grid, Pos};
 
-fn parse_field(lines: Lines) -> Array2<char> {
-    let nrows = lines.len();
-    let ncols = lines[0].chars().count();
-    let mut grid = Array2::from_elem((ncols, nrows), '.');
-
-    for (y, line) in enumerate(lines) {
-        for (x, c) in enumerate(line.chars()) {
-            grid[[x, y]] = c;
-        }
-    }
-
-    grid
+fn parse_field(lines: Lines) -> Result<Grid<char>> {
+    Ok(Grid::parse(lines)?)
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -23,21 +14,22 @@ enum Direction {
     West,
 }
 
-fn neighbor([x, y]: [usize; 2], dir: Direction, (nx, ny): (usize, usize)) -> Option<[usize; 2]> {
+fn neighbor(grid: &Grid<char>, p: Pos, dir: Direction) -> Option<Pos> {
     use Direction::*;
 
-    match dir {
-        North if y >= 1 => Some([x, y - 1]),
-        South if y + 1 < ny => Some([x, y + 1]),
-        East if x + 1 < nx => Some([x + 1, y]),
-        West if x >= 1 => Some([x - 1, y]),
-        _ => None,
-    }
+    let delta = match dir {
+        North => [0, -1],
+        South => [0, 1],
+        East => [1, 0],
+        West => [-1, 0],
+    };
+
+    grid.offset(p, delta)
 }
 
-fn shift(grid: ArrayView2<char>, direction: Direction) -> Array2<char> {
-    let mut grid = grid.into_owned();
-    let (ncols, nrows) = grid.dim();
+fn shift(grid: &Grid<char>, direction: Direction) -> Grid<char> {
+    let mut grid = grid.clone();
+    let [ncols, nrows] = grid.dim();
 
     loop {
         let mut updated = false;
@@ -45,7 +37,7 @@ fn shift(grid: ArrayView2<char>, direction: Direction) -> Array2<char> {
         for x in 0..ncols {
             for y in 0..nrows {
                 let p = [x, y];
-                if let Some(q) = neighbor(p, direction, grid.dim()) {
+                if let Some(q) = neighbor(&grid, p, direction) {
                     if grid[p] == 'O' && grid[q] == '.' {
                         grid[q] = 'O';
                         grid[p] = '.';
@@ -61,8 +53,8 @@ fn shift(grid: ArrayView2<char>, direction: Direction) -> Array2<char> {
     }
 }
 
-fn calculate_load(grid: ArrayView2<char>) -> usize {
-    let (ncols, nrows) = grid.dim();
+fn calculate_load(grid: &Grid<char>) -> usize {
+    let [ncols, nrows] = grid.dim();
     let mut total = 0;
 
     for y in 0..nrows {
@@ -75,36 +67,27 @@ fn calculate_load(grid: ArrayView2<char>) -> usize {
     total
 }
 
-fn simulate_cycles(grid: ArrayView2<char>, ncycles: usize) -> Array2<char> {
-    let mut seen = vec![];
-    let mut grid = grid.to_owned();
-
-    for cycle in 1..=ncycles {
-        seen.push(grid.clone());
-        grid = shift(grid.view(), Direction::North);
-        grid = shift(grid.view(), Direction::West);
-        grid = shift(grid.view(), Direction::South);
-        grid = shift(grid.view(), Direction::East);
-
-        if let Some(cycle_start) = seen.iter().position(|p| p == grid) {
-            let cycle_len = cycle - cycle_start;
-            return seen.swap_remove(cycle_start + (ncycles - cycle_start) % cycle_len);
-        }
-    }
+fn spin_cycle(grid: &Grid<char>) -> Grid<char> {
+    let grid = shift(grid, Direction::North);
+    let grid = shift(&grid, Direction::West);
+    let grid = shift(&grid, Direction::South);
+    shift(&grid, Direction::East)
+}
 
-    grid
+fn simulate_cycles(grid: &Grid<char>, ncycles: usize) -> Grid<char> {
+    nth_state(grid.clone(), spin_cycle, ncycles)
 }
 
 // high: 85203
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let grid = parse_field(lines);
-    let grid_shifted = shift(grid.view(), Direction::North);
+    let grid = parse_field(lines)?;
+    let grid_shifted = shift(&grid, Direction::North);
 
-    println!("part A: {}", calculate_load(grid_shifted.view()));
+    println!("part A: {}", calculate_load(&grid_shifted));
 
-    let result = simulate_cycles(grid.view(), 1000000000);
-    println!("part B: {}", calculate_load(result.view()));
+    let result = simulate_cycles(&grid, 1000000000);
+    println!("part B: {}", calculate_load(&result));
 
     //89554
     Ok(())
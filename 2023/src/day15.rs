@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use std::ops::Index;
 
 fn hash(input: &str) -> u64 {
@@ -13,25 +14,43 @@ fn hash(input: &str) -> u64 {
     hash
 }
 
-fn simulate_boxed<'a>(inputs: &[&'a str]) -> [Vec<(&'a str, u8)>; 256] {
+/// A single step of the initialization sequence, the text it was parsed
+/// from, the label, and the focal length to insert or `None` to remove.
+struct Step<'a> {
+    text: &'a str,
+    label: &'a str,
+    focal: Option<u8>,
+}
+
+fn parse_step<'a>(c: &mut Cursor<'a>) -> ParseResult<Step<'a>> {
+    let rest = c.rest();
+    let label = c.word()?;
+    let focal = if c.eat("-") {
+        None
+    } else {
+        c.tag("=")?;
+        Some(c.int()?)
+    };
+
+    let text = &rest[..rest.len() - c.rest().len()];
+    Ok(Step { text, label, focal })
+}
+
+fn simulate_boxed<'a>(steps: &[Step<'a>]) -> [Vec<(&'a str, u8)>; 256] {
     let mut boxes = [(); 256].map(|_| Vec::<(&str, u8)>::new());
 
-    for input in inputs {
-        if let Some(m) = find_regex("([a-z]+)=([0-9]+)", input) {
-            let label = m.get(1).unwrap().as_str();
-            let id = hash(label) as u8 as usize;
-            let focal: u8 = m[2].parse().unwrap();
+    for step in steps {
+        let label = step.label;
+        let id = hash(label) as u8 as usize;
 
+        if let Some(focal) = step.focal {
             if let Some(index) = boxes[id].iter().position(|&(l, _)| l == label) {
                 boxes[id][index] = (label, focal);
             } else {
                 boxes[id].push((label, focal));
             }
-        } else if let Some(m) = find_regex("([a-z]+)[-]", input) {
-            let id = hash(&m[1]) as u8 as usize;
-            boxes[id].retain(|&(label, _)| label != &m[1]);
         } else {
-            panic!("invalid input: {input}");
+            boxes[id].retain(|&(l, _)| l != label);
         }
     }
 
@@ -51,12 +70,12 @@ fn score_boxes(boxes: &[Vec<(&str, u8)>]) -> u64 {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let codes = lines[0].split(',').collect_vec();
+    let steps = Block::new(lines).parse_single(|c| c.list(",", parse_step))?;
 
-    let result: u64 = codes.iter().map(|s| hash(s)).sum();
+    let result: u64 = steps.iter().map(|s| hash(s.text)).sum();
     println!("part A: {}", result);
 
-    let score = score_boxes(&simulate_boxed(&codes));
+    let score = score_boxes(&simulate_boxed(&steps));
     println!("part B: {score}");
 
     Ok(())
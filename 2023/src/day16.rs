@@ -1,19 +1,15 @@
 use crate::common::*;
+use advent_common::parse::Block;
 use ndarray::{Array2, ArrayView2, Axis};
 use std::collections::VecDeque;
 
-fn parse_map(lines: Lines) -> Array2<char> {
-    let width = lines[0].chars().count();
-    let height = lines.len();
-    let mut map = Array2::from_elem((width, height), ' ');
+fn parse_map(lines: Lines) -> Result<Array2<char>> {
+    let grid = Block::new(lines).parse_grid(|c| "./\\|-".contains(c).then_some(c))?;
+    let (width, height) = (grid.width(), grid.height());
 
-    for (y, line) in enumerate(lines) {
-        for (x, c) in enumerate(line.chars()) {
-            map[[x, y]] = c;
-        }
-    }
-
-    map
+    Ok(Array2::from_shape_fn((width, height), |(x, y)| {
+        grid[[x, y]]
+    }))
 }
 
 #[derive(Hash, Debug, PartialEq, Eq, Copy, Clone)]
@@ -55,7 +51,7 @@ fn count_energized(map: ArrayView2<char>, start: State) -> usize {
     visited.into_iter().map(|State(xy, _)| xy).unique().count()
 }
 
-fn find_optimal_start(map: ArrayView2<char>) -> State {
+fn find_optimal_start(map: ArrayView2<char>) -> Option<State> {
     let width = map.len_of(Axis(0)) as i64;
     let height = map.len_of(Axis(1)) as i64;
 
@@ -66,16 +62,15 @@ fn find_optimal_start(map: ArrayView2<char>) -> State {
             [State([0, y], [1, 0]), State([height - 1, 0], [-1, 0])]),
     )
     .max_by_key(|&s| count_energized(map, s))
-    .unwrap()
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let map = parse_map(lines);
+    let map = parse_map(lines)?;
     let start = State([0, 0], [1, 0]);
 
     println!("part A: {}", count_energized(map.view(), start));
 
-    let start = find_optimal_start(map.view());
+    let start = find_optimal_start(map.view()).context("map is empty")?;
     println!("part B: {}", count_energized(map.view(), start));
 
     Ok(())
@@ -1,20 +1,17 @@
 use crate::common::*;
-use std::cmp::Reverse;
-use std::collections::VecDeque;
+use advent_common::parse::Block;
+use advent_common::search::dijkstra_distance;
 
 type Pos = [i64; 2];
 type Map = HashMap<Pos, i64>;
 
-fn parse_map(lines: Lines) -> Map {
-    let mut map = HashMap::default();
+fn parse_map(lines: Lines) -> Result<Map> {
+    let grid = Block::new(lines).parse_grid(|c| c.to_digit(10))?;
 
-    for (y, line) in enumerate(lines) {
-        for (x, c) in enumerate(line.chars()) {
-            map.insert([x as i64, y as i64], c.to_digit(10).unwrap() as i64);
-        }
-    }
-
-    map
+    Ok(grid
+        .enumerate()
+        .map(|([x, y], &heat)| ([x as i64, y as i64], heat as i64))
+        .collect())
 }
 
 const ADJACENT: [Pos; 4] = [[0, 1], [1, 0], [0, -1], [-1, 0]];
@@ -31,97 +28,85 @@ enum CartType {
     Ultra,
 }
 
-fn find_min_heat(start: Pos, end: Pos, map: &Map, cart: CartType) -> i64 {
-    let mut entries = HashMap::default();
-    let mut queue = binary_heap_plus::BinaryHeap::new_by_key(|(_, l)| Reverse(*l));
+fn next_states<'a>(
+    state: &State,
+    map: &'a Map,
+    cart: &'a CartType,
+) -> impl Iterator<Item = (State, i64)> + 'a {
+    let state = *state;
+    let [x, y] = state.position;
 
-    let key = State {
-        position: start,
-        prev_direction: None,
-        num_steps: 100,
-    };
-    entries.insert(key, 0);
-    queue.push((key, 0));
+    enumerate(ADJACENT).filter_map(move |(dir, [dx, dy])| {
+        let position = [x + dx, y + dy];
 
-    while let Some((state, current_loss)) = queue.pop() {
-        let [x, y] = state.position;
-        if [x, y] == end {
-            return current_loss;
+        // We cannot go back
+        if state.prev_direction == Some((dir + 2) % 4) {
+            return None;
         }
 
-        for (dir, [dx, dy]) in enumerate(ADJACENT) {
-            let position = [x + dx, y + dy];
+        let num_steps = match cart {
+            CartType::Regular => {
+                if state.prev_direction == Some(dir) {
+                    if state.num_steps + 1 > 3 {
+                        return None;
+                    }
 
-            // We cannot go back
-            if state.prev_direction == Some((dir + 2) % 4) {
-                continue;
+                    state.num_steps + 1
+                } else {
+                    1
+                }
             }
-
-            let num_steps = match cart {
-                CartType::Regular => {
-                    if state.prev_direction == Some(dir) {
-                        if state.num_steps + 1 > 3 {
-                            continue;
-                        }
-
-                        state.num_steps + 1
-                    } else {
-                        1
+            CartType::Ultra => {
+                if state.prev_direction == Some(dir) {
+                    if state.num_steps + 1 > 10 {
+                        return None;
                     }
-                }
-                CartType::Ultra => {
-                    if state.prev_direction == Some(dir) {
-                        if state.num_steps + 1 > 10 {
-                            continue;
-                        }
-
-                        state.num_steps + 1
-                    } else {
-                        if state.num_steps < 4 && state.prev_direction.is_some() {
-                            continue;
-                        }
-
-                        1
+
+                    state.num_steps + 1
+                } else {
+                    if state.num_steps < 4 && state.prev_direction.is_some() {
+                        return None;
                     }
-                }
-            };
-
-            if let Some(delta) = map.get(&position) {
-                let new_state = State {
-                    position,
-                    prev_direction: Some(dir),
-                    num_steps,
-                };
-
-                let new_loss = current_loss + delta;
-
-                if entries
-                    .get(&new_state)
-                    .map_or(true, |&old_loss| old_loss > new_loss)
-                {
-                    entries.insert(new_state, new_loss);
-                    queue.push((new_state, new_loss));
-                    continue;
+
+                    1
                 }
             }
-        }
-    }
+        };
 
-    panic!("end was not reached!")
+        let delta = map.get(&position)?;
+        let new_state = State {
+            position,
+            prev_direction: Some(dir),
+            num_steps,
+        };
+
+        Some((new_state, *delta))
+    })
+}
+
+fn find_min_heat(start: Pos, end: Pos, map: &Map, cart: CartType) -> Option<i64> {
+    let key = State {
+        position: start,
+        prev_direction: None,
+        num_steps: 100,
+    };
+
+    dijkstra_distance(
+        [key],
+        |state| next_states(state, map, &cart),
+        |state| state.position == end,
+    )
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let map = parse_map(lines);
-    let (&start, &end) = map.keys().minmax().into_option().unwrap();
-
-    println!(
-        "part A: {}",
-        find_min_heat(start, end, &map, CartType::Regular)
-    );
-    println!(
-        "part B: {}",
-        find_min_heat(start, end, &map, CartType::Ultra)
-    );
+    let map = parse_map(lines)?;
+    let (&start, &end) = map.keys().minmax().into_option().context("map is empty")?;
+
+    let heat = find_min_heat(start, end, &map, CartType::Regular);
+    println!("part A: {}", heat.context("end was not reached")?);
+
+    let heat = find_min_heat(start, end, &map, CartType::Ultra);
+    println!("part B: {}", heat.context("end was not reached")?);
 
     Ok(())
 }
@@ -1,5 +1,6 @@
 use crate::common::*;
-use ndarray::Array2;
+use advent_common::parse::{Block, Cursor, ParseResult};
+use advent_common::polygon::Polygon;
 
 struct Instruction {
     direction: char,
@@ -7,32 +8,48 @@ struct Instruction {
     color: String,
 }
 
-fn parse_direction(line: &str) -> Instruction {
-    let m = find_regex("([LDRU]) ([0-9]+) [(]#([0-9a-f]{6})[)]", line).unwrap();
-
-    Instruction {
-        direction: m[1].chars().next().unwrap(),
-        count: m[2].parse().unwrap(),
-        color: m[3].to_string(),
+/// Parses a line into the instruction as it was written and the corrected
+/// instruction hidden in the color code.
+fn parse_line(c: &mut Cursor) -> ParseResult<(Instruction, Instruction)> {
+    let direction = c.one_of(&[("R", 'R'), ("D", 'D'), ("L", 'L'), ("U", 'U')])?;
+    c.tag(" ")?;
+    let count = c.int()?;
+    c.tag(" (#")?;
+
+    let start = *c;
+    let color = c.take_while(|c| c.is_ascii_hexdigit());
+    if color.len() != 6 {
+        return Err(start.error("expected six hex digits"));
     }
-}
-
-fn parse_correct_direction(line: &str) -> Instruction {
-    let m = find_regex("[LDRU] [0-9]+ [(]#([0-9a-f]{5})([0-3])[)]", line).unwrap();
 
-    Instruction {
-        direction: b"RDLU"[m[2].parse::<usize>().unwrap()] as char,
-        count: i64::from_str_radix(&m[1], 16).unwrap(),
+    let (distance, turn) = color.split_at(5);
+    let corrected = Instruction {
+        direction: match turn {
+            "0" => 'R',
+            "1" => 'D',
+            "2" => 'L',
+            "3" => 'U',
+            _ => return Err(start.error("invalid direction in color")),
+        },
+        count: i64::from_str_radix(distance, 16)
+            .map_err(|_| start.error("invalid distance in color"))?,
         color: String::new(),
-    }
-}
+    };
 
-fn dig_trench(instrs: &[Instruction]) -> Vec<[i64; 2]> {
-    let [mut x, mut y] = [0, 0];
-    let mut path = vec![[x, y]];
+    c.tag(")")?;
 
-    for instr in instrs {
-        let [dx, dy] = match instr.direction {
+    let written = Instruction {
+        direction,
+        count,
+        color: color.to_string(),
+    };
+
+    Ok((written, corrected))
+}
+
+fn dig_trench(instrs: &[Instruction]) -> Polygon {
+    let steps = instrs.iter().map(|instr| {
+        let direction = match instr.direction {
             'D' => [0, 1],
             'U' => [0, -1],
             'L' => [-1, 0],
@@ -40,45 +57,24 @@ fn dig_trench(instrs: &[Instruction]) -> Vec<[i64; 2]> {
             _ => unreachable!(),
         };
 
-        x += dx * instr.count;
-        y += dy * instr.count;
-        path.push([x, y]);
-    }
+        (direction, instr.count)
+    });
 
-    path
-}
-
-fn dig_lagoon(trench: &[[i64; 2]]) -> i64 {
-    let mut area = 0;
-    let mut border = 0;
-
-    for i in 0..trench.len() {
-        let p = trench[i];
-        let q = trench[(i + 1) % trench.len()];
-
-        let [x, y] = p;
-        let [dx, dy] = [q[0] - p[0], q[1] - p[1]];
-
-        area += y * dx;
-        border += dx.abs() + dy.abs();
-    }
-
-    area.abs() + border / 2 + 1
+    Polygon::from_steps([0, 0], steps)
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let instr = lines.iter().copied().map(parse_direction).collect_vec();
-    let trench = dig_trench(&instr);
-    let lagoon = dig_lagoon(&trench);
+    let (written, corrected): (Vec<_>, Vec<_>) = Block::new(lines)
+        .parse_lines(parse_line)?
+        .into_iter()
+        .unzip();
+
+    let trench = dig_trench(&written);
+    let lagoon = trench.lattice_points();
     println!("part A: {}", lagoon);
 
-    let instr = lines
-        .iter()
-        .copied()
-        .map(parse_correct_direction)
-        .collect_vec();
-    let trench = dig_trench(&instr);
-    let lagoon = dig_lagoon(&trench);
+    let trench = dig_trench(&corrected);
+    let lagoon = trench.lattice_points();
     println!("part B: {}", lagoon);
 
     Ok(())
@@ -1,7 +1,8 @@
 use crate::common::*;
+use advent_common::interval::{BoxSet, Cuboid};
+use advent_common::parse::{Cursor, ParseResult, Sections};
 use std::array;
 use std::cmp::Ordering;
-use std::ops::{Range, RangeInclusive};
 
 #[derive(Debug)]
 struct Part {
@@ -16,47 +17,83 @@ struct Rule {
     target: String,
 }
 
-fn parse_rule(line: &str) -> Rule {
-    let Some(m) = find_regex("([a-z])([<>])(\\d+):([a-zAR]+)", line) else {
-        return Rule {
-            property: 0,
-            ordering: Ordering::Greater,
-            value: 0,
-            target: line.to_string(),
-        };
-    };
-
-    let property = "xmas".find(m[1].as_bytes()[0] as char).unwrap();
-    let ordering = match &m[2] {
-        "<" => Ordering::Less,
-        ">" => Ordering::Greater,
-        o => panic!("invalid ordering: {o}"),
-    };
-    let value = m[3].parse().unwrap();
-    let target = m[4].to_string();
-
-    Rule {
+fn parse_condition(c: &mut Cursor) -> ParseResult<Rule> {
+    let property = c.one_of(&[("x", 0), ("m", 1), ("a", 2), ("s", 3)])?;
+    let ordering = c.one_of(&[("<", Ordering::Less), (">", Ordering::Greater)])?;
+    let value = c.int()?;
+    c.tag(":")?;
+    let target = c.word()?.to_string();
+
+    Ok(Rule {
         property,
         ordering,
         value,
         target,
+    })
+}
+
+fn parse_workflow(c: &mut Cursor) -> ParseResult<(String, Vec<Rule>)> {
+    let name = c.word()?.to_string();
+    c.tag("{")?;
+
+    let mut rules = vec![];
+    while let Some(rule) = c.attempt(|c| {
+        let rule = parse_condition(c)?;
+        c.tag(",")?;
+        Ok(rule)
+    }) {
+        rules.push(rule);
     }
+
+    // Every rating is positive, so the last rule always matches.
+    rules.push(Rule {
+        property: 0,
+        ordering: Ordering::Greater,
+        value: 0,
+        target: c.word()?.to_string(),
+    });
+
+    c.tag("}")?;
+
+    Ok((name, rules))
 }
 
-fn parse_workflow(line: &str) -> (String, Vec<Rule>) {
-    let m = find_regex("([a-z]+)[{]([^}]+)[}]", line).unwrap();
-    let name = m[1].to_string();
-    let rules = m[2].split(",").map(|l| parse_rule(l)).collect();
+fn parse_part(c: &mut Cursor) -> ParseResult<Part> {
+    let start = *c;
+    c.tag("{")?;
+    let ratings = c.record(",", "=", Cursor::int)?;
+    c.tag("}")?;
+
+    let keys = ratings.iter().map(|&(key, _)| key).collect_vec();
+    if keys != ["x", "m", "a", "s"] {
+        return Err(start.error("expected ratings for x, m, a and s"));
+    }
 
-    (name, rules)
+    if ratings.iter().any(|&(_, value)| value <= 0) {
+        return Err(start.error("ratings must be positive"));
+    }
+
+    Ok(Part {
+        xmas: array::from_fn(|i| ratings[i].1),
+    })
 }
 
-fn parse_part(line: &str) -> Part {
-    let m = find_regex("[{]x=(\\d+),m=(\\d+),a=(\\d+),s=(\\d+)[}]", line).unwrap();
+fn check_workflows(workflows: &HashMap<String, Vec<Rule>>) -> Result {
+    ensure!(workflows.contains_key("in"), "missing workflow \"in\"");
 
-    Part {
-        xmas: array::from_fn(|i| m[1 + i].parse().unwrap()),
+    for (name, rules) in workflows {
+        for rule in rules {
+            let target = rule.target.as_str();
+            ensure!(
+                matches!(target, "A" | "R") || workflows.contains_key(target),
+                "workflow {:?} refers to unknown workflow {:?}",
+                name,
+                target
+            );
+        }
     }
+
+    Ok(())
 }
 
 fn is_accepted(part: &Part, workflows: &HashMap<String, Vec<Rule>>) -> bool {
@@ -72,105 +109,65 @@ fn is_accepted(part: &Part, workflows: &HashMap<String, Vec<Rule>>) -> bool {
         let r = workflows[current]
             .iter()
             .find(|rule| i64::cmp(&part.xmas[rule.property], &rule.value) == rule.ordering)
-            .unwrap();
+            .expect("the last rule always matches");
 
         current = &r.target;
     }
 }
 
-#[derive(Clone, Debug)]
-struct PartRanges {
-    xmas: [RangeInclusive<i64>; 4],
-}
-
-impl PartRanges {
-    fn new() -> Self {
-        Self {
-            xmas: array::from_fn(|_| 1..=4000),
-        }
-    }
-
-    fn count_combinations(&self) -> i64 {
-        self.xmas.iter().map(|r| r.end() - r.start() + 1).product()
-    }
-}
-
-fn collect_accepted_ranges(
-    current: &str,
-    workflows: &HashMap<String, Vec<Rule>>,
-) -> Vec<PartRanges> {
+fn collect_accepted_ranges(current: &str, workflows: &HashMap<String, Vec<Rule>>) -> BoxSet<4> {
     fn recur(
         current: &str,
-        mut ranges: PartRanges,
+        mut ranges: Cuboid<4>,
         workflows: &HashMap<String, Vec<Rule>>,
-        results: &mut Vec<PartRanges>,
+        results: &mut BoxSet<4>,
     ) {
         if current == "R" {
             return;
         }
 
         if current == "A" {
-            results.push(ranges);
+            results.insert(ranges);
             return;
         }
 
         let rules = &workflows[current];
 
         for rule in rules {
-            let i = rule.property;
-            let (&start, &end) = (ranges.xmas[i].start(), ranges.xmas[i].end());
-
-            let (ar, br) = if rule.ordering == Ordering::Less {
-                (
-                    start..=i64::min(end, rule.value - 1),
-                    i64::max(start, rule.value)..=end,
-                )
-            } else if rule.ordering == Ordering::Greater {
-                (
-                    i64::max(start, rule.value + 1)..=end,
-                    start..=i64::min(end, rule.value),
-                )
-            } else {
-                unreachable!()
+            let (matching, rest) = match rule.ordering {
+                Ordering::Less => ranges.split(rule.property, rule.value),
+                Ordering::Greater => {
+                    let (lower, upper) = ranges.split(rule.property, rule.value + 1);
+                    (upper, lower)
+                }
+                Ordering::Equal => unreachable!(),
             };
 
-            if ar.start() <= ar.end() {
-                let mut a = ranges.clone();
-                a.xmas[i] = ar;
+            if let Some(a) = matching {
                 recur(&rule.target, a, workflows, results);
             }
 
-            if br.start() < br.end() {
-                ranges.xmas[i] = br;
-                continue;
+            match rest {
+                Some(b) => ranges = b,
+                None => return,
             }
-
-            return;
         }
 
-        panic!();
+        unreachable!("the last rule always matches");
     }
 
-    let mut results = vec![];
-    recur(current, PartRanges::new(), workflows, &mut results);
+    let mut results = BoxSet::new();
+    let all_parts = Cuboid::new(array::from_fn(|_| 1..4001));
+    recur(current, all_parts, workflows, &mut results);
     results
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let mut workflows = HashMap::default();
-    let mut parts = vec![];
-    let mut parsing_workflows = true;
-
-    for line in lines {
-        if line.is_empty() {
-            parsing_workflows = false;
-        } else if parsing_workflows {
-            let (name, rules) = parse_workflow(line);
-            workflows.insert(name, rules);
-        } else {
-            parts.push(parse_part(line));
-        }
-    }
+    let [workflows, parts] = Sections::new(lines).blocks()?;
+    let workflows: HashMap<_, _> = workflows.parse_lines(parse_workflow)?.into_iter().collect();
+    let parts = parts.parse_lines(parse_part)?;
+
+    check_workflows(&workflows)?;
 
     let total: i64 = parts
         .iter()
@@ -180,10 +177,7 @@ pub(crate) fn run(lines: Lines) -> Result {
 
     println!("part A: {}", total);
 
-    let count: i64 = collect_accepted_ranges("in", &workflows)
-        .iter()
-        .map(|r| r.count_combinations())
-        .sum();
+    let count = collect_accepted_ranges("in", &workflows).volume();
 
     println!("part B: {}", count);
 
@@ -193,20 +187,29 @@ pub(crate) fn run(lines: Lines) -> Result {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_common::parse::parse_line;
+
+    fn workflow(line: &str) -> (String, Vec<Rule>) {
+        parse_line(1, line, parse_workflow).unwrap()
+    }
+
+    fn part(line: &str) -> Part {
+        parse_line(1, line, parse_part).unwrap()
+    }
 
     fn workflows() -> HashMap<String, Vec<Rule>> {
         HashMap::from_iter([
-            parse_workflow("px{a<2006:qkq,m>2090:A,rfg}"),
-            parse_workflow("pv{a>1716:R,A}"),
-            parse_workflow("lnx{m>1548:A,A}"),
-            parse_workflow("rfg{s<537:gd,x>2440:R,A}"),
-            parse_workflow("qs{s>3448:A,lnx}"),
-            parse_workflow("qkq{x<1416:A,crn}"),
-            parse_workflow("crn{x>2662:A,R}"),
-            parse_workflow("in{s<1351:px,qqz}"),
-            parse_workflow("qqz{s>2770:qs,m<1801:hdj,R}"),
-            parse_workflow("gd{a>3333:R,R}"),
-            parse_workflow("hdj{m>838:A,pv}"),
+            workflow("px{a<2006:qkq,m>2090:A,rfg}"),
+            workflow("pv{a>1716:R,A}"),
+            workflow("lnx{m>1548:A,A}"),
+            workflow("rfg{s<537:gd,x>2440:R,A}"),
+            workflow("qs{s>3448:A,lnx}"),
+            workflow("qkq{x<1416:A,crn}"),
+            workflow("crn{x>2662:A,R}"),
+            workflow("in{s<1351:px,qqz}"),
+            workflow("qqz{s>2770:qs,m<1801:hdj,R}"),
+            workflow("gd{a>3333:R,R}"),
+            workflow("hdj{m>838:A,pv}"),
         ])
     }
 
@@ -215,23 +218,23 @@ mod tests {
         let workflows = workflows();
 
         assert_eq!(
-            is_accepted(&parse_part("{x=787,m=2655,a=1222,s=2876}"), &workflows),
+            is_accepted(&part("{x=787,m=2655,a=1222,s=2876}"), &workflows),
             true
         );
         assert_eq!(
-            is_accepted(&parse_part("{x=1679,m=44,a=2067,s=496}"), &workflows),
+            is_accepted(&part("{x=1679,m=44,a=2067,s=496}"), &workflows),
             false
         );
         assert_eq!(
-            is_accepted(&parse_part("{x=2036,m=264,a=79,s=2244}"), &workflows),
+            is_accepted(&part("{x=2036,m=264,a=79,s=2244}"), &workflows),
             true
         );
         assert_eq!(
-            is_accepted(&parse_part("{x=2461,m=1339,a=466,s=291}"), &workflows),
+            is_accepted(&part("{x=2461,m=1339,a=466,s=291}"), &workflows),
             false
         );
         assert_eq!(
-            is_accepted(&parse_part("{x=2127,m=1623,a=2188,s=1013}"), &workflows),
+            is_accepted(&part("{x=2127,m=1623,a=2188,s=1013}"), &workflows),
             true
         );
 
@@ -243,10 +246,7 @@ mod tests {
         let workflows = workflows();
 
         assert_eq!(
-            collect_accepted_ranges("in", &workflows)
-                .iter()
-                .map(|r| r.count_combinations())
-                .sum::<i64>(),
+            collect_accepted_ranges("in", &workflows).volume(),
             167409079868000
         );
     }
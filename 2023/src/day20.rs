@@ -1,6 +1,10 @@
 use crate::common::*;
-use num::integer::lcm;
+use advent_common::assume::Assumptions;
+use advent_common::math::lcm;
+use advent_common::parse::{Block, Cursor, ParseResult};
+use itertools::chain;
 use std::collections::VecDeque;
+use std::env;
 
 #[derive(Debug, Clone)]
 enum ModuleKind<'a> {
@@ -19,32 +23,40 @@ struct Module<'a> {
 
 type Modules<'a> = HashMap<&'a str, Module<'a>>;
 
-fn parse_modules(lines: Lines) -> Modules<'_> {
-    let mut modules = HashMap::default();
+fn parse_module<'a>(c: &mut Cursor<'a>) -> ParseResult<Module<'a>> {
+    let kind = if c.eat("%") {
+        ModuleKind::FlipFlop { state: false }
+    } else if c.eat("&") {
+        ModuleKind::Conjunction { memory: default() }
+    } else {
+        ModuleKind::Broadcaster
+    };
 
-    for line in lines {
-        let (a, b) = line.split_once(" -> ").unwrap();
+    let start = *c;
+    let name = c.word()?;
+    if matches!(kind, ModuleKind::Broadcaster) && name != "broadcaster" {
+        return Err(start.error("expected %, & or broadcaster"));
+    }
 
-        let (name, kind) = if a == "broadcaster" {
-            ("broadcaster", ModuleKind::Broadcaster)
-        } else if a.starts_with("%") {
-            (&a[1..], ModuleKind::FlipFlop { state: false })
-        } else if a.starts_with("&") {
-            (&a[1..], ModuleKind::Conjunction { memory: default() })
-        } else {
-            unreachable!()
-        };
+    c.tag(" -> ")?;
+    let outgoing = c.list(", ", Cursor::word)?;
 
-        modules.insert(
-            name,
-            Module {
-                name,
-                kind,
-                outgoing: b.split(", ").collect(),
-            },
-        );
+    Ok(Module {
+        name,
+        kind,
+        outgoing,
+    })
+}
+
+fn parse_modules(lines: Lines) -> Result<Modules<'_>> {
+    let mut modules = HashMap::default();
+
+    for module in Block::new(lines).parse_lines(parse_module)? {
+        modules.insert(module.name, module);
     }
 
+    ensure!(modules.contains_key("broadcaster"), "no broadcaster module");
+
     for (name, module) in modules.clone() {
         for out in module.outgoing {
             let target = modules.entry(out).or_insert(Module {
@@ -59,7 +71,7 @@ fn parse_modules(lines: Lines) -> Modules<'_> {
         }
     }
 
-    modules
+    Ok(modules)
 }
 
 fn simulate_pulse<'a>(
@@ -110,47 +122,327 @@ fn count_pulses(presses: usize, modules: &mut Modules) -> [usize; 2] {
     counts
 }
 
-fn find_fewest_presses(modules: &mut Modules) -> usize {
-    let (&target, _) = modules
-        .iter()
-        .find(|(name, module)| module.outgoing == &["rx"])
-        .unwrap();
-    let mut remaining: HashSet<_> = match &modules[target].kind {
-        ModuleKind::Conjunction { memory } => memory.keys().copied().collect(),
-        _ => unreachable!(),
+// Give up on an input of the conjunction feeding rx after this many presses.
+const MAX_PRESSES: usize = 1_000_000;
+
+fn find_fewest_presses(modules: &mut Modules) -> Result<usize> {
+    let mut assumptions = Assumptions::new();
+    let parents = modules
+        .values()
+        .filter(|module| module.outgoing.contains(&"rx"))
+        .collect_vec();
+
+    assumptions.check(
+        parents.len() == 1,
+        format_args!("rx has a single input, found {}", parents.len()),
+    );
+    assumptions.verify()?;
+
+    let target = parents[0].name;
+    let mut hits: HashMap<&str, Vec<usize>> = match &parents[0].kind {
+        ModuleKind::Conjunction { memory } => memory.keys().map(|&k| (k, vec![])).collect(),
+        _ => default(),
     };
 
+    assumptions.check(!hits.is_empty(), "the input of rx is a conjunction");
+    assumptions.verify()?;
+
     let mut pulses = VecDeque::new();
     let mut presses = 0;
-    let mut result = 1;
 
-    loop {
+    // Record the first two presses that send a high pulse from every input
+    // of the conjunction.
+    while presses <= MAX_PRESSES && hits.values().any(|h| h.len() < 2) {
         let Some((src, dst, pulse)) = pulses.pop_front() else {
             presses += 1;
             pulses.push_back(("button", "broadcaster", false));
             continue;
         };
 
-        if dst == target && pulse && remaining.remove(src) {
-            result = lcm(result, presses);
-
-            if remaining.is_empty() {
-                return result;
+        if dst == target && pulse {
+            if let Some(h) = hits.get_mut(src) {
+                if h.len() < 2 && h.last() != Some(&presses) {
+                    h.push(presses);
+                }
             }
         }
 
         simulate_pulse(src, dst, pulse, modules, &mut pulses);
     }
+
+    // The conjunction sends a low pulse to rx once all its inputs are high
+    // during the same press, which happens at the lcm of their periods if
+    // every input is high exactly at the multiples of its first press.
+    let mut result = 1;
+
+    for (name, h) in hits.iter().sorted() {
+        let holds = assumptions.check(
+            h.len() == 2 && h[1] == 2 * h[0],
+            format_args!("{} sends a high pulse to {} periodically", name, target),
+        );
+
+        if holds {
+            result = lcm(result, h[0] as i64);
+        }
+    }
+
+    assumptions.verify()?;
+    Ok(result as usize)
+}
+
+/// Counter made of a chain of flip-flops, where `chain[i]` holds bit `i`.
+/// The conjunction `hub` reads the bits that are set in `period`, so once
+/// the counter reaches that value the hub resets it to zero and `output`
+/// sends a high pulse to the input of rx.
+#[derive(Debug)]
+struct Counter<'a> {
+    chain: Vec<&'a str>,
+    hub: &'a str,
+    output: &'a str,
+    period: usize,
+}
+
+fn is_flip_flop(modules: &Modules, name: &str) -> bool {
+    matches!(modules[name].kind, ModuleKind::FlipFlop { .. })
+}
+
+fn inputs<'a>(modules: &Modules<'a>, name: &str) -> Vec<&'a str> {
+    match &modules[name].kind {
+        ModuleKind::Conjunction { memory } => memory.keys().copied().sorted().collect(),
+        _ => vec![],
+    }
+}
+
+fn analyze_counter<'a>(modules: &Modules<'a>, head: &'a str, sink: &str) -> Result<Counter<'a>> {
+    let mut assumptions = Assumptions::new();
+    let mut chain = vec![head];
+
+    assumptions.check(
+        is_flip_flop(modules, head),
+        format_args!("{} is a flip-flop", head),
+    );
+    assumptions.verify()?;
+
+    loop {
+        let last = chain[chain.len() - 1];
+        let next = modules[last]
+            .outgoing
+            .iter()
+            .filter(|n| is_flip_flop(modules, n))
+            .collect_vec();
+
+        let holds = assumptions.check(
+            next.len() <= 1 && next.iter().all(|n| !chain.contains(n)),
+            format_args!("{} is followed by at most one new flip-flop", last),
+        );
+
+        match next.first() {
+            Some(&&n) if holds => chain.push(n),
+            _ => break,
+        }
+    }
+
+    let hubs = chain
+        .iter()
+        .flat_map(|n| &modules[n].outgoing)
+        .filter(|n| !is_flip_flop(modules, n))
+        .unique()
+        .collect_vec();
+
+    assumptions.check(
+        hubs.len() == 1 && !inputs(modules, hubs[0]).is_empty(),
+        format_args!("the chain starting at {} feeds a single conjunction", head),
+    );
+    assumptions.verify()?;
+
+    // The hub resets the first bit and every bit that is not one of its
+    // inputs, which brings the counter from `period` back to zero.
+    let hub = *hubs[0];
+    let resets = &modules[hub].outgoing;
+    let mut period = 0;
+    let mut holds = true;
+
+    for (i, &bit) in enumerate(&chain) {
+        let is_input = modules[bit].outgoing.contains(&hub);
+        let is_reset = resets.contains(&bit);
+
+        if is_input {
+            period |= 1 << i;
+        }
+
+        holds &= if i == 0 {
+            is_input && is_reset
+        } else {
+            is_input != is_reset
+        };
+    }
+
+    assumptions.check(
+        holds,
+        format_args!(
+            "{} resets exactly the bits of {} it does not read",
+            hub, head
+        ),
+    );
+
+    let outputs = resets.iter().filter(|n| !chain.contains(n)).collect_vec();
+
+    assumptions.check(
+        outputs.len() == 1
+            && inputs(modules, outputs[0]) == [hub]
+            && modules[*outputs[0]].outgoing == [sink],
+        format_args!("{} reaches {} through a single inverter", hub, sink),
+    );
+    assumptions.verify()?;
+
+    Ok(Counter {
+        chain,
+        hub,
+        output: outputs[0],
+        period,
+    })
+}
+
+/// Decomposes the circuit into independent counters that are started by
+/// the broadcaster and all feed the conjunction in front of rx. Button
+/// press `n` then sends a low pulse to rx if `n` is a multiple of every
+/// period.
+fn analyze_circuit<'a>(modules: &Modules<'a>) -> Result<Vec<Counter<'a>>> {
+    let mut assumptions = Assumptions::new();
+    let parents = modules
+        .values()
+        .filter(|module| module.outgoing.contains(&"rx"))
+        .collect_vec();
+
+    assumptions.check(
+        modules.contains_key("broadcaster")
+            && parents.len() == 1
+            && !inputs(modules, parents[0].name).is_empty(),
+        "rx has a single conjunction as input",
+    );
+    assumptions.verify()?;
+
+    let sink = parents[0].name;
+    let counters = modules["broadcaster"]
+        .outgoing
+        .iter()
+        .map(|head| analyze_counter(modules, head, sink))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut seen = HashSet::default();
+    let mut outputs = vec![];
+
+    for counter in &counters {
+        outputs.push(counter.output);
+
+        for &name in chain!(&counter.chain, [&counter.hub, &counter.output]) {
+            assumptions.check(
+                seen.insert(name),
+                format_args!("{} belongs to a single counter", name),
+            );
+        }
+    }
+
+    assumptions.check(
+        outputs.into_iter().sorted().collect_vec() == inputs(modules, sink),
+        format_args!("every input of {} is the output of a counter", sink),
+    );
+    assumptions.verify()?;
+
+    Ok(counters)
+}
+
+/// Writes the circuit in the DOT format of Graphviz, with every counter in
+/// a cluster of its own.
+fn write_dot(modules: &Modules, counters: &[Counter]) -> Result {
+    use std::fmt::Write;
+    let mut out = String::new();
+    writeln!(out, "digraph {{")?;
+
+    for (i, counter) in enumerate(counters) {
+        writeln!(out, "  subgraph cluster_{} {{", i)?;
+        writeln!(out, "    label=\"period {}\";", counter.period)?;
+
+        for name in chain!(&counter.chain, [&counter.hub, &counter.output]) {
+            writeln!(out, "    {};", name)?;
+        }
+
+        writeln!(out, "  }}")?;
+    }
+
+    for module in modules.values().sorted_by_key(|m| m.name) {
+        let shape = match module.kind {
+            ModuleKind::Broadcaster => "doublecircle",
+            ModuleKind::Noop => "circle",
+            ModuleKind::FlipFlop { .. } => "box",
+            ModuleKind::Conjunction { .. } => "diamond",
+        };
+
+        writeln!(out, "  {} [shape={}];", module.name, shape)?;
+
+        for dst in &module.outgoing {
+            writeln!(out, "  {} -> {};", module.name, dst)?;
+        }
+    }
+
+    writeln!(out, "}}")?;
+
+    let filename = "day20.dot";
+    std::fs::write(filename, out)?;
+    println!("saved graph as {:?}", filename);
+
+    Ok(())
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let mut modules = parse_modules(lines);
+    let modules = parse_modules(lines)?;
 
     let counts = count_pulses(1000, &mut modules.clone());
     println!("part A: {:}", counts[0] * counts[1]);
 
-    let count = find_fewest_presses(&mut modules.clone());
+    let counters = analyze_circuit(&modules);
+
+    if env::var_os("VISUALIZE").is_some() {
+        write_dot(&modules, counters.as_deref().unwrap_or_default())?;
+    }
+
+    // Simulate the button presses if the circuit is not made of counters.
+    let count = match &counters {
+        Ok(counters) => counters.iter().map(|c| c.period as i64).fold(1, lcm) as usize,
+        Err(_) => find_fewest_presses(&mut modules.clone())?,
+    };
     println!("part B: {:}", count);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_b() {
+        // Two counters with periods 0b101 and 0b11.
+        let lines = [
+            "broadcaster -> a0, b0",
+            "%a0 -> a1, ha",
+            "%a1 -> a2",
+            "%a2 -> ha",
+            "&ha -> a0, a1, ia",
+            "&ia -> out",
+            "%b0 -> b1, hb",
+            "%b1 -> hb",
+            "&hb -> b0, ib",
+            "&ib -> out",
+            "&out -> rx",
+        ];
+        let modules = parse_modules(&lines).unwrap();
+
+        let counters = analyze_circuit(&modules).unwrap();
+        let periods = counters.iter().map(|c| c.period).sorted().collect_vec();
+        assert_eq!(periods, [3, 5]);
+
+        let count = find_fewest_presses(&mut modules.clone()).unwrap();
+        assert_eq!(count, 15);
+    }
+}
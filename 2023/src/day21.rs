@@ -1,41 +1,27 @@
 use crate::common::*;
-use ndarray::Array2;
-use num::traits::Euclid;
+use advent_common::grid::{Grid, NEIGHBORS4};
 
 type Pos = [i64; 2];
-type Map = Array2<char>;
+type Map = Grid<char>;
 type State = HashSet<Pos>;
 
-fn parse_map(lines: Lines) -> (Map, Pos) {
-    let (width, height) = (lines[0].chars().count(), lines.len());
-    let mut start = [0, 0];
-    let mut map = Map::default((width, height));
+fn parse_map(lines: Lines) -> Result<(Map, Pos)> {
+    let mut map = Grid::parse(lines)?;
+    let [x, y] = map.find(|&c| c == 'S').context("no start position")?;
+    map[[x, y]] = '.';
 
-    for (y, line) in enumerate(lines) {
-        for (x, c) in enumerate(line.chars()) {
-            map[[x, y]] = c;
-
-            if c == 'S' {
-                map[[x, y]] = '.';
-                start = [x as i64, y as i64];
-            }
-        }
-    }
-
-    (map, start)
+    Ok((map, [x as i64, y as i64]))
 }
 
 fn simulate_step(map: &Map, state: &State) -> State {
-    let (width, height) = map.dim();
     let mut new_state = State::default();
 
     for [x, y] in state {
-        for [dx, dy] in [[0, 1], [0, -1], [1, 0], [-1, 0]] {
-            let ix = (x + dx).rem_euclid(width as i64);
-            let iy = (y + dy).rem_euclid(height as i64);
+        for [dx, dy] in NEIGHBORS4 {
+            let next = [x + dx as i64, y + dy as i64];
 
-            if map[[ix as usize, iy as usize]] == '.' {
-                new_state.insert([x + dx, y + dy]);
+            if *map.get_wrapped(next) == '.' {
+                new_state.insert(next);
             }
         }
     }
@@ -54,11 +40,11 @@ fn count_plots(map: &Map, start: Pos, nsteps: usize) -> usize {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let (map, start) = parse_map(lines);
+    let (map, start) = parse_map(lines)?;
 
     println!("part A: {:?}", count_plots(&map, start, 64));
 
-    let size = map.dim().0;
+    let size = map.width();
 
     let x = 26501365;
     let nrounds = (x / size) as i64;
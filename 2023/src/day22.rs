@@ -1,4 +1,5 @@
 use crate::common::*;
+use advent_common::parse::{Block, Cursor, ParseResult};
 use std::collections::VecDeque;
 use std::iter::zip;
 
@@ -30,17 +31,27 @@ impl Cube {
     }
 }
 
-fn parse_cubes(lines: Lines) -> Vec<Cube> {
-    lines
-        .iter()
-        .map(|line| {
-            let (start, end) = line.split_once("~").unwrap();
-            let begin = parse_list(start, ',').unwrap().try_into().unwrap();
-            let end = parse_list(end, ',').unwrap().try_into().unwrap();
-
-            Cube { begin, end }
-        })
-        .collect()
+fn parse_pos(c: &mut Cursor) -> ParseResult<Pos> {
+    let x = c.int()?;
+    c.tag(",")?;
+    let y = c.int()?;
+    c.tag(",")?;
+    let z = c.int()?;
+
+    Ok([x, y, z])
+}
+
+fn parse_cube(c: &mut Cursor) -> ParseResult<Cube> {
+    let start = *c;
+    let begin = parse_pos(c)?;
+    c.tag("~")?;
+    let end = parse_pos(c)?;
+
+    if zip(begin, end).any(|(a, b)| a > b) {
+        return Err(start.error("cube ends before it begins"));
+    }
+
+    Ok(Cube { begin, end })
 }
 
 fn drop_cubes(mut cubes: Vec<Cube>) -> Vec<Cube> {
@@ -115,7 +126,7 @@ fn count_falling_if_removed(index: usize, cubes: &[Cube]) -> usize {
 }
 
 pub(crate) fn run(lines: Lines) -> Result {
-    let cubes = parse_cubes(lines);
+    let cubes = Block::new(lines).parse_lines(parse_cube)?;
 
     let cubes = drop_cubes(cubes);
 
@@ -0,0 +1,101 @@
+//! Checking the hidden structure of puzzle input. Some solutions only work
+//! because the real inputs have properties that the puzzle text never
+//! promises. Such a solution states these properties up front in an
+//! `Assumptions`, so that an input without them results in a diagnostic
+//! (or a slower general algorithm) instead of a wrong answer or a panic.
+
+use std::error::Error;
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssumptionError {
+    pub violations: Vec<String>,
+}
+
+impl Display for AssumptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "input violates assumption: ")?;
+
+        for (i, violation) in self.violations.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+
+            write!(f, "{}", violation)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for AssumptionError {}
+
+/// Collects the assumptions a solution makes about its input together with
+/// the ones that turned out not to hold.
+#[derive(Debug, Clone, Default)]
+pub struct Assumptions {
+    violations: Vec<String>,
+}
+
+impl Assumptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that the input should have the property described by
+    /// `description`, which only gets formatted if `holds` is false.
+    /// Returns `holds`.
+    pub fn check(&mut self, holds: bool, description: impl Display) -> bool {
+        if !holds {
+            self.violations.push(description.to_string());
+        }
+
+        holds
+    }
+
+    /// Whether every assumption checked so far holds.
+    pub fn hold(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn violations(&self) -> &[String] {
+        &self.violations
+    }
+
+    /// Fails with all violated assumptions if there are any. Can be called
+    /// several times when later assumptions only make sense if the earlier
+    /// ones hold.
+    pub fn verify(&self) -> Result<(), AssumptionError> {
+        if self.hold() {
+            Ok(())
+        } else {
+            Err(AssumptionError {
+                violations: self.violations.clone(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assumptions() {
+        let mut assumptions = Assumptions::new();
+        assert!(assumptions.check(true, "never shown"));
+        assert!(assumptions.hold());
+        assert_eq!(assumptions.verify(), Ok(()));
+
+        assert!(!assumptions.check(1 > 2, format_args!("{} exceeds {}", 1, 2)));
+        assert!(!assumptions.check(false, "input is sorted"));
+        assert!(!assumptions.hold());
+        assert_eq!(assumptions.violations(), ["1 exceeds 2", "input is sorted"]);
+
+        let err = assumptions.verify().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "input violates assumption: 1 exceeds 2; input is sorted"
+        );
+    }
+}
@@ -0,0 +1,588 @@
+//! Cellular automata, in which every cell changes its state at the same time
+//! based on the states of its neighbors.
+//!
+//! A `DenseAutomaton` stores every cell of a rectangular window of the plane
+//! and lets the rule inspect the neighborhood freely. A `SparseAutomaton`
+//! only stores the live cells of an arbitrary `Space`, such as a lattice in
+//! any number of dimensions, a hex grid or a recursive grid, and decides the
+//! next state from the number of live neighbors.
+
+use crate::grid::{Grid, NEIGHBORS4, NEIGHBORS8};
+use crate::hex::Hex;
+use crate::sparse::Point;
+use fnv::{FnvHashMap, FnvHashSet};
+use std::hash::Hash;
+use std::thread;
+
+/// Number of threads used by the parallel stepping functions.
+fn num_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Dense two-dimensional automaton. Cells outside the stored window either
+/// do not exist (bounded) or all share the same background state
+/// (unbounded), in which case the window grows by one cell in every
+/// direction on each step.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DenseAutomaton<T> {
+    cells: Grid<T>,
+    origin: Point,
+    background: Option<T>,
+}
+
+/// View of the automaton around the cell that is being updated.
+pub struct Neighborhood<'a, T> {
+    automaton: &'a DenseAutomaton<T>,
+    pos: Point,
+}
+
+impl<'a, T> Neighborhood<'a, T> {
+    pub fn pos(&self) -> Point {
+        self.pos
+    }
+
+    pub fn center(&self) -> &'a T {
+        self.get([0, 0]).unwrap()
+    }
+
+    /// State of the cell at the given offset from the center, or `None` if
+    /// it lies outside a bounded automaton.
+    pub fn get(&self, [dx, dy]: Point) -> Option<&'a T> {
+        self.automaton.get([self.pos[0] + dx, self.pos[1] + dy])
+    }
+
+    /// States of the existing orthogonal neighbors.
+    pub fn neighbors4(&self) -> impl Iterator<Item = &'a T> + '_ {
+        NEIGHBORS4
+            .iter()
+            .filter_map(|&[dx, dy]| self.get([dx as i64, dy as i64]))
+    }
+
+    /// States of the existing orthogonal and diagonal neighbors.
+    pub fn neighbors8(&self) -> impl Iterator<Item = &'a T> + '_ {
+        NEIGHBORS8
+            .iter()
+            .filter_map(|&[dx, dy]| self.get([dx as i64, dy as i64]))
+    }
+}
+
+impl<T> DenseAutomaton<T> {
+    /// Automaton in which only the given cells exist.
+    pub fn bounded(cells: Grid<T>) -> Self {
+        Self {
+            cells,
+            origin: [0, 0],
+            background: None,
+        }
+    }
+
+    /// Automaton on the infinite plane, where every cell outside the grid
+    /// is in state `background`.
+    pub fn unbounded(cells: Grid<T>, background: T) -> Self {
+        Self {
+            cells,
+            origin: [0, 0],
+            background: Some(background),
+        }
+    }
+
+    pub fn cells(&self) -> &Grid<T> {
+        &self.cells
+    }
+
+    pub fn into_cells(self) -> Grid<T> {
+        self.cells
+    }
+
+    /// Position of the top-left cell of `cells`. The initial grid starts
+    /// at `[0, 0]`.
+    pub fn origin(&self) -> Point {
+        self.origin
+    }
+
+    /// State of all cells outside the window, or `None` if it is bounded.
+    pub fn background(&self) -> Option<&T> {
+        self.background.as_ref()
+    }
+
+    pub fn get(&self, [x, y]: Point) -> Option<&T> {
+        let (x, y) = (x - self.origin[0], y - self.origin[1]);
+
+        match self.cells.checked_pos([x, y]) {
+            Some(p) => Some(&self.cells[p]),
+            None => self.background.as_ref(),
+        }
+    }
+
+    /// Number of cells inside the window for which `pred` holds.
+    pub fn count<F>(&self, mut pred: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.cells.iter().filter(|c| pred(c)).count()
+    }
+}
+
+impl<T: Clone> DenseAutomaton<T> {
+    /// Window after the next step and the position of its top-left cell.
+    fn next_window(&self) -> ([usize; 2], Point) {
+        let [w, h] = self.cells.dim();
+        let [x, y] = self.origin;
+
+        match self.background {
+            None => ([w, h], [x, y]),
+            Some(_) => ([w + 2, h + 2], [x - 1, y - 1]),
+        }
+    }
+
+    /// Evaluates the rule far away from the window, where every neighbor is
+    /// in the background state.
+    fn next_background<F>(&self, rule: &F) -> Option<T>
+    where
+        F: Fn(&Neighborhood<T>) -> T,
+    {
+        self.background.as_ref()?;
+        let pos = [self.origin[0] - 2, self.origin[1] - 2];
+
+        Some(rule(&Neighborhood {
+            automaton: self,
+            pos,
+        }))
+    }
+
+    /// Applies `rule` to every cell at once. For unbounded automata, the
+    /// rule should only look at the direct neighbors of a cell.
+    pub fn step<F>(&self, rule: F) -> Self
+    where
+        F: Fn(&Neighborhood<T>) -> T,
+    {
+        let ([w, h], origin) = self.next_window();
+        let cells = Grid::from_fn(w, h, |[x, y]| {
+            rule(&Neighborhood {
+                automaton: self,
+                pos: [origin[0] + x as i64, origin[1] + y as i64],
+            })
+        });
+
+        Self {
+            cells,
+            origin,
+            background: self.next_background(&rule),
+        }
+    }
+
+    /// Same as `step`, but divides the rows over multiple threads.
+    pub fn par_step<F>(&self, rule: F) -> Self
+    where
+        T: Send + Sync,
+        F: Fn(&Neighborhood<T>) -> T + Sync,
+    {
+        let ([w, h], origin) = self.next_window();
+        let chunk = h.div_ceil(num_threads()).max(1);
+        let rule = &rule;
+
+        let rows = thread::scope(|s| {
+            let handles = (0..h)
+                .step_by(chunk)
+                .map(|start| {
+                    s.spawn(move || {
+                        (start..h.min(start + chunk))
+                            .map(|y| {
+                                (0..w)
+                                    .map(|x| {
+                                        rule(&Neighborhood {
+                                            automaton: self,
+                                            pos: [origin[0] + x as i64, origin[1] + y as i64],
+                                        })
+                                    })
+                                    .collect::<Vec<_>>()
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        Self {
+            cells: Grid::from_rows(rows).unwrap(),
+            origin,
+            background: self.next_background(rule),
+        }
+    }
+
+    /// Applies `rule` until the cells stop changing. Returns the final
+    /// state and the number of steps that changed something. Only bounded
+    /// automata can settle, since unbounded ones keep growing.
+    pub fn stabilize<F>(&self, rule: F) -> (Self, usize)
+    where
+        T: Eq,
+        F: Fn(&Neighborhood<T>) -> T,
+    {
+        let mut current = self.clone();
+        let mut steps = 0;
+
+        loop {
+            let next = current.step(&rule);
+
+            if next == current {
+                break (current, steps);
+            }
+
+            current = next;
+            steps += 1;
+        }
+    }
+}
+
+/// Set of cells together with the neighbors of each cell.
+pub trait Space {
+    type Cell: Copy + Eq + Hash;
+
+    fn for_each_neighbor<F: FnMut(Self::Cell)>(&self, cell: Self::Cell, fun: F);
+}
+
+/// N-dimensional lattice where every cell touches the `3^N - 1` cells that
+/// differ by at most one in each coordinate.
+#[derive(Clone, Debug)]
+pub struct Moore<const N: usize> {
+    offsets: Vec<[i64; N]>,
+}
+
+impl<const N: usize> Moore<N> {
+    pub fn new() -> Self {
+        let mut offsets = vec![[0; N]];
+
+        for axis in 0..N {
+            offsets = offsets
+                .into_iter()
+                .flat_map(|o| {
+                    [-1, 0, 1].map(|d| {
+                        let mut o = o;
+                        o[axis] = d;
+                        o
+                    })
+                })
+                .collect();
+        }
+
+        offsets.retain(|o| o.iter().any(|&d| d != 0));
+        Self { offsets }
+    }
+}
+
+impl<const N: usize> Default for Moore<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Space for Moore<N> {
+    type Cell = [i64; N];
+
+    fn for_each_neighbor<F: FnMut(Self::Cell)>(&self, cell: Self::Cell, mut fun: F) {
+        for offset in &self.offsets {
+            fun(std::array::from_fn(|i| cell[i] + offset[i]));
+        }
+    }
+}
+
+/// N-dimensional lattice where every cell touches the `2N` cells that
+/// differ by one in a single coordinate.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct VonNeumann<const N: usize>;
+
+impl<const N: usize> Space for VonNeumann<N> {
+    type Cell = [i64; N];
+
+    fn for_each_neighbor<F: FnMut(Self::Cell)>(&self, cell: Self::Cell, mut fun: F) {
+        for axis in 0..N {
+            for d in [-1, 1] {
+                let mut neighbor = cell;
+                neighbor[axis] += d;
+                fun(neighbor);
+            }
+        }
+    }
+}
+
+/// Infinite grid of hexagons.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct HexSpace;
+
+impl Space for HexSpace {
+    type Cell = Hex;
+
+    fn for_each_neighbor<F: FnMut(Self::Cell)>(&self, cell: Self::Cell, fun: F) {
+        cell.neighbors().into_iter().for_each(fun);
+    }
+}
+
+/// Square grid of odd `size` whose center cell is replaced by another copy
+/// of the grid, one level deeper, and so on in both directions. Cells are
+/// `[x, y, level]`.
+#[derive(Copy, Clone, Debug)]
+pub struct RecursiveGrid {
+    size: i64,
+}
+
+impl RecursiveGrid {
+    pub fn new(size: i64) -> Self {
+        assert!(size % 2 == 1, "size must be odd");
+        Self { size }
+    }
+}
+
+impl Space for RecursiveGrid {
+    type Cell = [i64; 3];
+
+    fn for_each_neighbor<F: FnMut(Self::Cell)>(&self, [x, y, level]: Self::Cell, mut fun: F) {
+        let (n, mid) = (self.size, self.size / 2);
+
+        for [dx, dy] in NEIGHBORS4 {
+            let (p, q) = (x + dx as i64, y + dy as i64);
+
+            if p < 0 || q < 0 || p >= n || q >= n {
+                // Leaving the grid ends up next to the center of the
+                // enclosing grid.
+                fun([mid + dx as i64, mid + dy as i64, level - 1]);
+            } else if [p, q] != [mid, mid] {
+                fun([p, q, level]);
+            } else {
+                // Entering the center touches a whole edge of the inner grid.
+                for r in 0..n {
+                    let inner = match [dx, dy] {
+                        [1, 0] => [0, r],
+                        [-1, 0] => [n - 1, r],
+                        [0, 1] => [r, 0],
+                        _ => [r, n - 1],
+                    };
+
+                    fun([inner[0], inner[1], level + 1]);
+                }
+            }
+        }
+    }
+}
+
+/// Automaton that stores the set of live cells of a `Space`, assuming all
+/// other cells are dead.
+#[derive(Clone, Debug)]
+pub struct SparseAutomaton<S: Space> {
+    space: S,
+    alive: FnvHashSet<S::Cell>,
+}
+
+impl<S: Space> SparseAutomaton<S> {
+    pub fn new<I>(space: S, alive: I) -> Self
+    where
+        I: IntoIterator<Item = S::Cell>,
+    {
+        Self {
+            space,
+            alive: alive.into_iter().collect(),
+        }
+    }
+
+    pub fn space(&self) -> &S {
+        &self.space
+    }
+
+    pub fn alive(&self) -> &FnvHashSet<S::Cell> {
+        &self.alive
+    }
+
+    pub fn len(&self) -> usize {
+        self.alive.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alive.is_empty()
+    }
+
+    fn count_neighbors<'a, I>(&self, cells: I) -> FnvHashMap<S::Cell, usize>
+    where
+        I: IntoIterator<Item = &'a S::Cell>,
+        S::Cell: 'a,
+    {
+        let mut counts = FnvHashMap::default();
+
+        for &cell in cells {
+            counts.entry(cell).or_insert(0);
+            self.space
+                .for_each_neighbor(cell, |n| *counts.entry(n).or_insert(0) += 1);
+        }
+
+        counts
+    }
+
+    fn apply<F>(&self, counts: FnvHashMap<S::Cell, usize>, rule: F) -> Self
+    where
+        S: Clone,
+        F: Fn(bool, usize) -> bool,
+    {
+        // Dead cells without live neighbors are not visited, so they must
+        // stay dead.
+        assert!(!rule(false, 0), "rule must keep empty space dead");
+
+        let alive = counts
+            .into_iter()
+            .filter(|&(cell, n)| rule(self.alive.contains(&cell), n))
+            .map(|(cell, _)| cell)
+            .collect();
+
+        Self {
+            space: self.space.clone(),
+            alive,
+        }
+    }
+
+    /// Applies `rule` to every cell at once. The rule receives whether the
+    /// cell is alive and how many of its neighbors are. Panics if the rule
+    /// brings a dead cell without live neighbors to life, since that would
+    /// fill the infinite empty space. Use an unbounded `DenseAutomaton` for
+    /// such rules, which tracks the state of the background.
+    pub fn step<F>(&self, rule: F) -> Self
+    where
+        S: Clone,
+        F: Fn(bool, usize) -> bool,
+    {
+        self.apply(self.count_neighbors(&self.alive), rule)
+    }
+
+    /// Same as `step`, but counts the neighbors on multiple threads. Panics
+    /// under the same condition.
+    pub fn par_step<F>(&self, rule: F) -> Self
+    where
+        S: Clone + Sync,
+        S::Cell: Send + Sync,
+        F: Fn(bool, usize) -> bool,
+    {
+        let cells = self.alive.iter().collect::<Vec<_>>();
+        let chunk = cells.len().div_ceil(num_threads()).max(1);
+
+        let partial = thread::scope(|s| {
+            let handles = cells
+                .chunks(chunk)
+                .map(|part| s.spawn(move || self.count_neighbors(part.iter().copied())))
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        let mut counts = FnvHashMap::default();
+        for map in partial {
+            for (cell, n) in map {
+                *counts.entry(cell).or_insert(0) += n;
+            }
+        }
+
+        self.apply(counts, rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(alive: bool, n: usize) -> bool {
+        n == 3 || (alive && n == 2)
+    }
+
+    #[test]
+    fn test_dense() {
+        let glider = Grid::parse_with(&[".#.", "..#", "###"], |c| c == '#').unwrap();
+        let rule =
+            |n: &Neighborhood<bool>| life(*n.center(), n.neighbors8().filter(|&&b| b).count());
+
+        // A glider moves one cell diagonally every four steps.
+        let mut a = DenseAutomaton::unbounded(glider.clone(), false);
+        for _ in 0..4 {
+            a = a.step(rule);
+        }
+
+        assert_eq!(a.count(|&b| b), 5);
+        assert_eq!(a.origin(), [-4, -4]);
+        assert!((0..3)
+            .all(|y| (0..3)
+                .all(|x| a.get([x + 1, y + 1]) == Some(&glider[[x as usize, y as usize]]))));
+        assert_eq!(a.par_step(rule), a.step(rule));
+
+        // In a box, the glider ends up as a block.
+        let boxed = Grid::from_fn(5, 5, |[x, y]| x < 3 && y < 3 && glider[[x, y]]);
+        let (settled, steps) = DenseAutomaton::bounded(boxed).stabilize(rule);
+        assert_eq!(settled.count(|&b| b), 4);
+        assert_eq!(settled.par_step(rule), settled);
+        assert!(steps > 0);
+        assert_eq!(settled.get([5, 0]), None);
+
+        // Rules that turn on empty space flip the background.
+        let invert = DenseAutomaton::unbounded(glider, false).step(|n| !*n.center());
+        assert_eq!(invert.background(), Some(&true));
+        assert_eq!(invert.get([-10, 7]), Some(&true));
+        assert_eq!(invert.count(|&b| b), 20);
+    }
+
+    #[test]
+    fn test_sparse() {
+        let glider = [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]];
+        let mut a = SparseAutomaton::new(Moore::<2>::new(), glider);
+
+        for _ in 0..4 {
+            a = a.step(life);
+        }
+
+        let moved = glider
+            .iter()
+            .map(|&[x, y]| [x + 1, y + 1])
+            .collect::<FnvHashSet<_>>();
+        assert_eq!(a.alive(), &moved);
+        assert_eq!(a.par_step(life).alive(), a.step(life).alive());
+
+        let mut count = 0;
+        Moore::<4>::new().for_each_neighbor([0; 4], |_| count += 1);
+        assert_eq!(count, 80);
+
+        let mut count = 0;
+        VonNeumann::<3>.for_each_neighbor([0; 3], |_| count += 1);
+        assert_eq!(count, 6);
+    }
+
+    #[test]
+    fn test_recursive() {
+        let space = RecursiveGrid::new(5);
+        let neighbors = |cell| {
+            let mut result = vec![];
+            space.for_each_neighbor(cell, |n| result.push(n));
+            result.sort();
+            result
+        };
+
+        // Examples from 2019 day 24: cell 19 has four neighbors on its own
+        // level, cell 14 has eight, and corner cell A touches two outer cells.
+        assert_eq!(neighbors([3, 3, 0]).len(), 4);
+        assert_eq!(neighbors([3, 2, 0]).len(), 8);
+        assert_eq!(
+            neighbors([0, 0, 1]),
+            vec![[0, 1, 1], [1, 0, 1], [1, 2, 0], [2, 1, 0]]
+        );
+
+        // Adjacency is symmetric.
+        for x in 0..5 {
+            for y in 0..5 {
+                if [x, y] != [2, 2] {
+                    for n in neighbors([x, y, 0]) {
+                        assert!(neighbors(n).contains(&[x, y, 0]));
+                    }
+                }
+            }
+        }
+    }
+}
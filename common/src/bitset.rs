@@ -0,0 +1,332 @@
+//! Fixed-capacity sets of small integers stored as bitmasks. A `BitSet<W>`
+//! holds the elements `0..64 * W` in `W` words and is `Copy`, so it can be
+//! used directly as (part of) a search state or a hash key.
+
+use std::fmt::{self, Debug, Display};
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BitSet<const W: usize = 1> {
+    words: [u64; W],
+}
+
+/// Set of the elements `0..64`.
+pub type SmallSet = BitSet<1>;
+
+impl<const W: usize> BitSet<W> {
+    pub const CAPACITY: usize = 64 * W;
+
+    pub const fn new() -> Self {
+        Self { words: [0; W] }
+    }
+
+    /// Set of all elements below `n`.
+    pub fn below(n: usize) -> Self {
+        assert!(n <= Self::CAPACITY, "{} exceeds capacity of bitset", n);
+        let mut result = Self::new();
+
+        for (i, word) in result.words.iter_mut().enumerate() {
+            *word = match n.saturating_sub(64 * i) {
+                0 => 0,
+                k if k >= 64 => !0,
+                k => (1 << k) - 1,
+            };
+        }
+
+        result
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        index < Self::CAPACITY && (self.words[index / 64] >> (index % 64)) & 1 == 1
+    }
+
+    /// Adds `index` to the set. Returns false if it was already present.
+    pub fn insert(&mut self, index: usize) -> bool {
+        assert!(
+            index < Self::CAPACITY,
+            "{} exceeds capacity of bitset",
+            index
+        );
+        let present = self.contains(index);
+        self.words[index / 64] |= 1 << (index % 64);
+        !present
+    }
+
+    /// Removes `index` from the set. Returns false if it was not present.
+    pub fn remove(&mut self, index: usize) -> bool {
+        let present = self.contains(index);
+
+        if present {
+            self.words[index / 64] &= !(1 << (index % 64));
+        }
+
+        present
+    }
+
+    /// Copy of this set that also contains `index`.
+    pub fn with(mut self, index: usize) -> Self {
+        self.insert(index);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    pub fn difference(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(*other).is_empty()
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(*other).is_empty()
+    }
+
+    /// Elements in increasing order.
+    pub fn iter(&self) -> Iter<W> {
+        Iter { remaining: *self }
+    }
+
+    /// All subsets of this set, starting with the empty set and ending with
+    /// the set itself.
+    pub fn subsets(&self) -> Subsets<W> {
+        Subsets {
+            set: *self,
+            next: Some(Self::new()),
+        }
+    }
+
+    fn zip_with(mut self, other: Self, fun: impl Fn(u64, u64) -> u64) -> Self {
+        for (a, b) in self.words.iter_mut().zip(other.words) {
+            *a = fun(*a, b);
+        }
+
+        self
+    }
+}
+
+impl<const W: usize> Default for BitSet<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize> Debug for BitSet<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const W: usize> Display for BitSet<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{")?;
+
+        for (i, index) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{}", index)?;
+        }
+
+        write!(f, "}}")
+    }
+}
+
+impl<const W: usize> FromIterator<usize> for BitSet<W> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut result = Self::new();
+        result.extend(iter);
+        result
+    }
+}
+
+impl<const W: usize> Extend<usize> for BitSet<W> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for index in iter {
+            self.insert(index);
+        }
+    }
+}
+
+impl<const W: usize> IntoIterator for BitSet<W> {
+    type Item = usize;
+    type IntoIter = Iter<W>;
+
+    fn into_iter(self) -> Iter<W> {
+        self.iter()
+    }
+}
+
+impl<const W: usize> IntoIterator for &BitSet<W> {
+    type Item = usize;
+    type IntoIter = Iter<W>;
+
+    fn into_iter(self) -> Iter<W> {
+        self.iter()
+    }
+}
+
+macro_rules! impl_operator {
+    ($op:ident, $fun:ident, $op_assign:ident, $fun_assign:ident, $method:ident) => {
+        impl<const W: usize> $op for BitSet<W> {
+            type Output = Self;
+
+            fn $fun(self, other: Self) -> Self {
+                self.$method(other)
+            }
+        }
+
+        impl<const W: usize> $op_assign for BitSet<W> {
+            fn $fun_assign(&mut self, other: Self) {
+                *self = self.$method(other);
+            }
+        }
+    };
+}
+
+impl_operator!(BitOr, bitor, BitOrAssign, bitor_assign, union);
+impl_operator!(BitAnd, bitand, BitAndAssign, bitand_assign, intersection);
+impl_operator!(Sub, sub, SubAssign, sub_assign, difference);
+
+#[derive(Clone, Debug)]
+pub struct Iter<const W: usize> {
+    remaining: BitSet<W>,
+}
+
+impl<const W: usize> Iterator for Iter<W> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let (i, word) = self
+            .remaining
+            .words
+            .iter_mut()
+            .enumerate()
+            .find(|(_, w)| **w != 0)?;
+
+        let bit = word.trailing_zeros() as usize;
+        *word &= *word - 1;
+        Some(64 * i + bit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.remaining.len();
+        (n, Some(n))
+    }
+}
+
+impl<const W: usize> ExactSizeIterator for Iter<W> {}
+
+#[derive(Clone, Debug)]
+pub struct Subsets<const W: usize> {
+    set: BitSet<W>,
+    next: Option<BitSet<W>>,
+}
+
+impl<const W: usize> Iterator for Subsets<W> {
+    type Item = BitSet<W>;
+
+    fn next(&mut self) -> Option<BitSet<W>> {
+        let current = self.next?;
+
+        // Count upwards in the bits of the set: setting every bit outside of
+        // the set makes the carry skip over them.
+        let mut next = current;
+        let mut carry = true;
+
+        for (word, mask) in next.words.iter_mut().zip(self.set.words) {
+            let (sum, overflow) = (*word | !mask).overflowing_add(carry as u64);
+            *word = sum & mask;
+            carry = overflow;
+        }
+
+        // A carry out of the last word means that all subsets were visited.
+        self.next = if carry { None } else { Some(next) };
+        Some(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitset() {
+        let mut a = SmallSet::new();
+        assert!(a.is_empty());
+        assert!(a.insert(3));
+        assert!(!a.insert(3));
+        assert!(a.insert(63));
+        assert_eq!(a.len(), 2);
+        assert!(a.contains(3) && a.contains(63) && !a.contains(4) && !a.contains(64));
+        assert!(a.remove(3));
+        assert!(!a.remove(3));
+
+        let b = [1, 2, 5].into_iter().collect::<SmallSet>();
+        let c = SmallSet::below(3);
+        assert_eq!(c.iter().collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(b | c, [0, 1, 2, 5].into_iter().collect());
+        assert_eq!(b & c, [1, 2].into_iter().collect());
+        assert_eq!(b - c, SmallSet::new().with(5));
+        assert!(b.is_disjoint(&SmallSet::new().with(0)));
+        assert!((b & c).is_subset(&b) && !b.is_subset(&c));
+
+        assert_eq!(b.to_string(), "{1, 2, 5}");
+        assert_eq!(format!("{:?}", SmallSet::new()), "{}");
+        assert_eq!(SmallSet::below(64).len(), 64);
+    }
+
+    #[test]
+    fn test_wide() {
+        let mut a = BitSet::<3>::below(130);
+        assert_eq!(a.len(), 130);
+        assert!(a.contains(129) && !a.contains(130));
+
+        a -= BitSet::below(64);
+        assert_eq!(a.iter().next(), Some(64));
+        assert_eq!(a.iter().len(), 66);
+        assert_eq!(a.iter().last(), Some(129));
+    }
+
+    #[test]
+    fn test_subsets() {
+        let set = [1, 4, 6].into_iter().collect::<SmallSet>();
+        let subsets = set.subsets().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            subsets,
+            [
+                "{}",
+                "{1}",
+                "{4}",
+                "{1, 4}",
+                "{6}",
+                "{1, 6}",
+                "{4, 6}",
+                "{1, 4, 6}"
+            ]
+        );
+
+        // The carry crosses word boundaries.
+        let set = [63, 64, 190].into_iter().collect::<BitSet<3>>();
+        assert_eq!(set.subsets().count(), 8);
+        assert!(set.subsets().all(|s| s.is_subset(&set)));
+        assert_eq!(SmallSet::new().subsets().count(), 1);
+    }
+}
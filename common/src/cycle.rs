@@ -0,0 +1,269 @@
+//! Detecting cycles in sequences of states `x0, x1 = f(x0), x2 = f(x1), ...`
+//! and using them to skip ahead to a far away step.
+
+use fnv::FnvHashMap;
+use std::hash::Hash;
+use std::ops::{Add, Mul, Sub};
+
+/// Cycle in a sequence of states: the state after `start + len` steps
+/// equals the state after `start` steps.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// Smallest step that has the same state as step `n`. The result is
+    /// always less than `start + len`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+
+    /// Extrapolates a quantity that is accumulated over the sequence, such
+    /// as a total height or a count, to step `n`. Here `values[i]` is the
+    /// quantity after `i` steps and must be known up to `start + len`. The
+    /// quantity is assumed to grow by the same amount during every cycle.
+    pub fn extrapolate<T>(&self, values: &[T], n: usize) -> T
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + TryFrom<usize>,
+    {
+        if n < values.len() {
+            return values[n];
+        }
+
+        let end = self.start + self.len;
+        let growth = values[end] - values[self.start];
+        let cycles = (n - self.start) / self.len;
+        let cycles = T::try_from(cycles)
+            .ok()
+            .expect("number of cycles is too large");
+
+        values[self.reduce(n)] + cycles * growth
+    }
+}
+
+/// Detects a cycle in a sequence of states that are presented one at a
+/// time. Useful when the states themselves are expensive to store and a
+/// smaller key captures everything that determines the future.
+#[derive(Clone, Debug)]
+pub struct CycleDetector<K> {
+    seen: FnvHashMap<K, usize>,
+}
+
+impl<K: Hash + Eq> CycleDetector<K> {
+    pub fn new() -> Self {
+        Self {
+            seen: FnvHashMap::default(),
+        }
+    }
+
+    /// Number of states that have been recorded so far.
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    /// Records the key of the next state. Returns the cycle as soon as a key
+    /// is seen for the second time, after which the detector should not be
+    /// used anymore.
+    pub fn push(&mut self, key: K) -> Option<Cycle> {
+        let step = self.seen.len();
+
+        let start = self.seen.insert(key, step)?;
+        Some(Cycle {
+            start,
+            len: step - start,
+        })
+    }
+}
+
+impl<K: Hash + Eq> Default for CycleDetector<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Finds the cycle starting from `initial` by remembering every state.
+/// Returns the cycle together with all states before the state repeats.
+pub fn find_cycle<S, F>(initial: S, mut step: F) -> (Cycle, Vec<S>)
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut detector = CycleDetector::new();
+    let mut history = vec![];
+    let mut state = initial;
+
+    loop {
+        if let Some(cycle) = detector.push(state.clone()) {
+            break (cycle, history);
+        }
+
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+}
+
+/// State after `n` steps, skipping over whole cycles once a state repeats.
+pub fn nth_state<S, F>(initial: S, mut step: F, n: usize) -> S
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut detector = CycleDetector::new();
+    let mut history = vec![];
+    let mut state = initial;
+
+    for _ in 0..n {
+        if let Some(cycle) = detector.push(state.clone()) {
+            return history.swap_remove(cycle.reduce(n));
+        }
+
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+
+    state
+}
+
+/// Floyd's tortoise and hare algorithm. Only needs to compare states, but
+/// takes about three times as many steps as strictly necessary.
+pub fn floyd<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: PartialEq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut slow = step(&initial);
+    let mut fast = step(&slow);
+    while slow != fast {
+        slow = step(&slow);
+        let half = step(&fast);
+        fast = step(&half);
+    }
+
+    let mut start = 0;
+    slow = initial;
+    while slow != fast {
+        slow = step(&slow);
+        fast = step(&fast);
+        start += 1;
+    }
+
+    let mut len = 1;
+    fast = step(&slow);
+    while slow != fast {
+        fast = step(&fast);
+        len += 1;
+    }
+
+    Cycle { start, len }
+}
+
+/// Brent's algorithm. Only needs to compare states and usually requires
+/// fewer steps than Floyd's algorithm.
+pub fn brent<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: PartialEq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+
+        hare = step(&hare);
+        len += 1;
+    }
+
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..len {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, len }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, 2, 5, 26, 2, 5, 26, ...
+    fn next(&x: &u32) -> u32 {
+        (x * x + 1) % 27
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let (cycle, history) = find_cycle(0, next);
+        assert_eq!(cycle, Cycle { start: 2, len: 3 });
+        assert_eq!(history, [0, 1, 2, 5, 26]);
+
+        assert_eq!(brent(0, next), cycle);
+        assert_eq!(floyd(0, next), cycle);
+        assert_eq!(brent(5, next), Cycle { start: 0, len: 3 });
+        assert_eq!(floyd(5, next), Cycle { start: 0, len: 3 });
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        let cycle = Cycle { start: 2, len: 3 };
+        assert_eq!(cycle.reduce(1), 1);
+        assert_eq!(cycle.reduce(5), 2);
+        assert_eq!(cycle.reduce(1_000_000), 4);
+
+        for n in 0..20 {
+            let expected = (0..n).fold(0, |x, _| next(&x));
+            assert_eq!(nth_state(0, next, n), expected);
+        }
+
+        assert_eq!(nth_state(0, next, 1_000_000_000), 26);
+    }
+
+    #[test]
+    fn test_detector() {
+        // Running total of the sequence above.
+        let mut detector = CycleDetector::new();
+        let mut totals = vec![0u64];
+        let mut state = 0;
+
+        let cycle = loop {
+            if let Some(cycle) = detector.push(state) {
+                break cycle;
+            }
+
+            totals.push(totals.last().unwrap() + state as u64);
+            state = next(&state);
+        };
+
+        assert_eq!(cycle, Cycle { start: 2, len: 3 });
+        assert_eq!(detector.len(), 5);
+        assert_eq!(cycle.extrapolate(&totals, 4), 8);
+        assert_eq!(cycle.extrapolate(&totals, 8), 1 + 2 * 33);
+        assert_eq!(cycle.extrapolate(&totals, 1_000_001), 1 + 33 * 333_333);
+    }
+}
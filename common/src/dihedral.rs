@@ -0,0 +1,201 @@
+//! The eight symmetries of a square (the dihedral group D4) acting on grids.
+
+use crate::grid::{Grid, Pos};
+
+/// Symmetry of a square: an optional left-to-right mirror followed by a
+/// number of clockwise quarter turns.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct D4 {
+    flip: bool,
+    turns: u8,
+}
+
+impl D4 {
+    pub const IDENTITY: Self = Self {
+        flip: false,
+        turns: 0,
+    };
+
+    pub fn new(flip: bool, turns: u8) -> Self {
+        Self {
+            flip,
+            turns: turns % 4,
+        }
+    }
+
+    /// All eight symmetries, starting with the identity.
+    pub fn all() -> [Self; 8] {
+        [false, true]
+            .map(|flip| [0, 1, 2, 3].map(|turns| Self::new(flip, turns)))
+            .concat()
+            .try_into()
+            .unwrap()
+    }
+
+    pub fn is_flipped(&self) -> bool {
+        self.flip
+    }
+
+    pub fn turns(&self) -> u8 {
+        self.turns
+    }
+
+    /// The symmetry that applies `other` first and then `self`.
+    pub fn compose(&self, other: &Self) -> Self {
+        // Mirroring reverses the direction of the turns that come before it.
+        let turns = if self.flip {
+            self.turns + 4 - other.turns
+        } else {
+            self.turns + other.turns
+        };
+
+        Self::new(self.flip ^ other.flip, turns)
+    }
+
+    pub fn inverse(&self) -> Self {
+        if self.flip {
+            *self
+        } else {
+            Self::new(false, 4 - self.turns)
+        }
+    }
+
+    /// Size `[width, height]` of a grid of size `dim` after the transform.
+    pub fn apply_dim(&self, [w, h]: [usize; 2]) -> [usize; 2] {
+        if self.turns % 2 == 0 {
+            [w, h]
+        } else {
+            [h, w]
+        }
+    }
+
+    /// Where position `pos` in a grid of size `dim` ends up after the
+    /// transform.
+    pub fn apply_pos(&self, [mut x, mut y]: Pos, [mut w, mut h]: [usize; 2]) -> Pos {
+        if self.flip {
+            x = w - 1 - x;
+        }
+
+        for _ in 0..self.turns {
+            (x, y, w, h) = (h - 1 - y, x, h, w);
+        }
+
+        [x, y]
+    }
+
+    pub fn apply<T: Clone>(&self, grid: &Grid<T>) -> Grid<T> {
+        let [w, h] = self.apply_dim(grid.dim());
+        let inverse = self.inverse();
+
+        Grid::from_fn(w, h, |p| grid[inverse.apply_pos(p, [w, h])].clone())
+    }
+}
+
+/// Finds every place where `mask` fits onto `grid` in any of its eight
+/// orientations, such that `matches` holds for every grid cell under a
+/// `true` cell of the mask. Returns the orientation of the mask and the
+/// position of its top-left corner.
+pub fn find_pattern<T, F>(grid: &Grid<T>, mask: &Grid<bool>, mut matches: F) -> Vec<(D4, Pos)>
+where
+    F: FnMut(&T) -> bool,
+{
+    let mut found = vec![];
+    let mut seen = vec![];
+
+    for sym in D4::all() {
+        let oriented = sym.apply(mask);
+
+        // Symmetric masks look the same under several orientations.
+        if seen.contains(&oriented) {
+            continue;
+        }
+
+        let cells = oriented
+            .positions()
+            .filter(|&p| oriented[p])
+            .collect::<Vec<_>>();
+        let [w, h] = oriented.dim();
+
+        if w <= grid.width() && h <= grid.height() {
+            for y in 0..=(grid.height() - h) {
+                for x in 0..=(grid.width() - w) {
+                    if cells
+                        .iter()
+                        .all(|&[dx, dy]| matches(&grid[[x + dx, y + dy]]))
+                    {
+                        found.push((sym, [x, y]));
+                    }
+                }
+            }
+        }
+
+        seen.push(oriented);
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse(&["abc", "def"]).unwrap()
+    }
+
+    #[test]
+    fn test_grid() {
+        let g = grid();
+        assert_eq!(D4::IDENTITY.apply(&g), g);
+        assert_eq!(D4::new(false, 1).apply(&g), g.rotate_cw());
+        assert_eq!(D4::new(false, 3).apply(&g), g.rotate_ccw());
+        assert_eq!(D4::new(true, 0).apply(&g), g.flip_horizontal());
+        assert_eq!(D4::new(true, 2).apply(&g), g.flip_vertical());
+        assert_eq!(D4::new(true, 3).apply(&g), g.transpose());
+
+        let all = D4::all();
+        let images = all.map(|s| s.apply(&g));
+        for (i, a) in images.iter().enumerate() {
+            assert!(images[..i].iter().all(|b| a != b));
+        }
+    }
+
+    #[test]
+    fn test_group() {
+        let g = grid();
+
+        for a in D4::all() {
+            assert_eq!(a.compose(&a.inverse()), D4::IDENTITY);
+            assert_eq!(a.inverse().apply(&a.apply(&g)), g);
+
+            for b in D4::all() {
+                assert_eq!(a.compose(&b).apply(&g), a.apply(&b.apply(&g)));
+            }
+
+            for p in g.positions() {
+                let q = a.apply_pos(p, g.dim());
+                assert_eq!(a.apply(&g)[q], g[p]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let grid = Grid::parse(&[
+            "..#....", //
+            ".###...", "......#", ".....##", "......#",
+        ])
+        .unwrap();
+
+        // A "T" shape, found once pointing up and once pointing left
+        let mask = Grid::parse_with(&[".#.", "###"], |c| c == '#').unwrap();
+        let mut found = find_pattern(&grid, &mask, |&c| c == '#');
+        found.sort_by_key(|&(_, p)| p);
+
+        assert_eq!(found, [(D4::IDENTITY, [1, 0]), (D4::new(false, 3), [5, 2])]);
+
+        // Symmetric masks are only reported once
+        let dot = Grid::from_elem(1, 1, true);
+        assert_eq!(find_pattern(&grid, &dot, |&c| c == '#').len(), 8);
+    }
+}
@@ -0,0 +1,481 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+/// Position in a grid as `[x, y]`, where `x` is the column and `y` the row.
+pub type Pos = [usize; 2];
+
+pub const NEIGHBORS4: [[isize; 2]; 4] = [[0, -1], [1, 0], [0, 1], [-1, 0]];
+
+pub const NEIGHBORS8: [[isize; 2]; 8] = [
+    [-1, -1],
+    [0, -1],
+    [1, -1],
+    [-1, 0],
+    [1, 0],
+    [-1, 1],
+    [0, 1],
+    [1, 1],
+];
+
+/// How a grid treats positions beyond its edges.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Topology {
+    /// Positions outside of the grid do not exist.
+    Bounded,
+    /// The grid wraps around at its edges, i.e., it is a torus.
+    Toroidal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseGridError {
+    pub line: usize,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected {} columns, found {}",
+            self.line, self.expected, self.found
+        )
+    }
+}
+
+impl Error for ParseGridError {}
+
+/// Dense two-dimensional grid stored in row-major order.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    data: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn<F>(width: usize, height: usize, mut fun: F) -> Self
+    where
+        F: FnMut(Pos) -> T,
+    {
+        let data = (0..height)
+            .flat_map(|y| (0..width).map(move |x| [x, y]))
+            .map(&mut fun)
+            .collect();
+
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    pub fn from_elem(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            data: vec![value; width * height],
+        }
+    }
+
+    /// Builds a grid from its rows. Returns an error if the rows do not all
+    /// have the same length.
+    pub fn from_rows<R>(rows: impl IntoIterator<Item = R>) -> Result<Self, ParseGridError>
+    where
+        R: IntoIterator<Item = T>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut data = vec![];
+
+        for row in rows {
+            let before = data.len();
+            data.extend(row);
+            let found = data.len() - before;
+            let expected = *width.get_or_insert(found);
+
+            if found != expected {
+                return Err(ParseGridError {
+                    line: height + 1,
+                    expected,
+                    found,
+                });
+            }
+
+            height += 1;
+        }
+
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            data,
+        })
+    }
+
+    /// Parses each character of the given lines using `fun`.
+    pub fn parse_with<S, F>(lines: &[S], mut fun: F) -> Result<Self, ParseGridError>
+    where
+        S: AsRef<str>,
+        F: FnMut(char) -> T,
+    {
+        let rows = lines
+            .iter()
+            .map(|line| line.as_ref().chars().map(&mut fun).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `[width, height]`.
+    pub fn dim(&self) -> [usize; 2] {
+        [self.width, self.height]
+    }
+
+    pub fn contains(&self, [x, y]: Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Converts a signed position into a position on the grid, if it lies
+    /// within the bounds of the grid.
+    pub fn checked_pos(&self, [x, y]: [i64; 2]) -> Option<Pos> {
+        let pos = [usize::try_from(x).ok()?, usize::try_from(y).ok()?];
+        self.contains(pos).then_some(pos)
+    }
+
+    /// Moves `pos` by the given offset, if the result lies within the grid.
+    pub fn offset(&self, [x, y]: Pos, [dx, dy]: [isize; 2]) -> Option<Pos> {
+        let pos = [x.checked_add_signed(dx)?, y.checked_add_signed(dy)?];
+        self.contains(pos).then_some(pos)
+    }
+
+    /// Maps a signed position onto the grid by wrapping around its edges.
+    pub fn wrap(&self, [x, y]: [i64; 2]) -> Pos {
+        [
+            x.rem_euclid(self.width as i64) as usize,
+            y.rem_euclid(self.height as i64) as usize,
+        ]
+    }
+
+    /// Moves `pos` by the given offset, wrapping around the edges.
+    pub fn wrapping_offset(&self, [x, y]: Pos, [dx, dy]: [isize; 2]) -> Pos {
+        self.wrap([x as i64 + dx as i64, y as i64 + dy as i64])
+    }
+
+    /// Moves `pos` by the given offset using the given topology.
+    pub fn offset_with(&self, pos: Pos, delta: [isize; 2], topology: Topology) -> Option<Pos> {
+        match topology {
+            Topology::Bounded => self.offset(pos, delta),
+            Topology::Toroidal => Some(self.wrapping_offset(pos, delta)),
+        }
+    }
+
+    fn index_of(&self, pos: Pos) -> Option<usize> {
+        let [x, y] = pos;
+        self.contains(pos).then(|| y * self.width + x)
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.data[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.data[i])
+    }
+
+    /// Looks up a signed position as if the grid is tiled infinitely in
+    /// all directions.
+    pub fn get_wrapped(&self, pos: [i64; 2]) -> &T {
+        &self[self.wrap(pos)]
+    }
+
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS4
+            .iter()
+            .filter_map(move |&delta| self.offset(pos, delta))
+    }
+
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS8
+            .iter()
+            .filter_map(move |&delta| self.offset(pos, delta))
+    }
+
+    pub fn neighbors4_with(&self, pos: Pos, topology: Topology) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS4
+            .iter()
+            .filter_map(move |&delta| self.offset_with(pos, delta, topology))
+    }
+
+    pub fn neighbors8_with(&self, pos: Pos, topology: Topology) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS8
+            .iter()
+            .filter_map(move |&delta| self.offset_with(pos, delta, topology))
+    }
+
+    /// All positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| [x, y]))
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.data.iter_mut()
+    }
+
+    /// Iterates over all positions and values in row-major order.
+    pub fn enumerate(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.data)
+    }
+
+    pub fn find<F>(&self, mut fun: F) -> Option<Pos>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.enumerate().find(|(_, v)| fun(v)).map(|(p, _)| p)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} out of bounds", x);
+        self.data.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    pub fn map<U, F>(&self, fun: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            data: self.data.iter().map(fun).collect(),
+        }
+    }
+
+    /// Builds a new grid of the given size where every cell is taken from
+    /// this grid at the position given by `fun`.
+    fn remap<F>(&self, width: usize, height: usize, fun: F) -> Self
+    where
+        F: Fn(Pos) -> Pos,
+        T: Clone,
+    {
+        Self::from_fn(width, height, |p| self[fun(p)].clone())
+    }
+
+    /// Mirrors the grid along its main diagonal.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |[x, y]| [y, x])
+    }
+
+    /// Rotates the grid by 90 degrees clockwise.
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let h = self.height;
+        self.remap(h, self.width, |[x, y]| [y, h - 1 - x])
+    }
+
+    /// Rotates the grid by 90 degrees counterclockwise.
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let w = self.width;
+        self.remap(self.height, w, |[x, y]| [w - 1 - y, x])
+    }
+
+    /// Mirrors the grid left to right.
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        let w = self.width;
+        self.remap(w, self.height, |[x, y]| [w - 1 - x, y])
+    }
+
+    /// Mirrors the grid top to bottom.
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        let h = self.height;
+        self.remap(self.width, h, |[x, y]| [x, h - 1 - y])
+    }
+}
+
+impl Grid<char> {
+    /// Parses a character grid. All lines must have the same length.
+    pub fn parse<S: AsRef<str>>(lines: &[S]) -> Result<Self, ParseGridError> {
+        Self::parse_with(lines, |c| c)
+    }
+
+    /// Parses a character grid, padding lines that are shorter than the
+    /// longest line with `fill`.
+    pub fn parse_padded<S: AsRef<str>>(lines: &[S], fill: char) -> Self {
+        let width = lines
+            .iter()
+            .map(|l| l.as_ref().chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut grid = Self::from_elem(width, lines.len(), fill);
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.as_ref().chars().enumerate() {
+                grid[[x, y]] = c;
+            }
+        }
+
+        grid
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.index_of(pos) {
+            Some(i) => &self.data[i],
+            None => panic!("position {:?} out of bounds for grid {:?}", pos, self.dim()),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        match self.index_of(pos) {
+            Some(i) => &mut self.data[i],
+            None => panic!("position {:?} out of bounds for grid {:?}", pos, self.dim()),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Grid<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        Grid::parse(&["abc", "def"]).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = example();
+        assert_eq!(grid.dim(), [3, 2]);
+        assert_eq!(grid[[2, 0]], 'c');
+        assert_eq!(grid[[0, 1]], 'd');
+        assert_eq!(grid.get([3, 0]), None);
+        assert_eq!(grid.to_string(), "abc\ndef");
+
+        let err = Grid::parse(&["abc", "de"]).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: expected 3 columns, found 2");
+
+        let grid = Grid::parse_padded(&["abc", "d"], ' ');
+        assert_eq!(grid.to_string(), "abc\nd  ");
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = example();
+        assert_eq!(
+            grid.neighbors4([0, 0]).collect::<Vec<_>>(),
+            [[1, 0], [0, 1]]
+        );
+        assert_eq!(grid.neighbors8([1, 0]).count(), 5);
+        assert_eq!(grid.offset([2, 1], [-2, -1]), Some([0, 0]));
+        assert_eq!(grid.offset([2, 1], [1, 0]), None);
+        assert_eq!(grid.checked_pos([-1, 0]), None);
+    }
+
+    #[test]
+    fn test_toroidal() {
+        let grid = example();
+        assert_eq!(grid.wrap([-1, 2]), [2, 0]);
+        assert_eq!(*grid.get_wrapped([4, -1]), 'e');
+        assert_eq!(grid.wrapping_offset([2, 1], [1, 1]), [0, 0]);
+        assert_eq!(grid.offset_with([0, 0], [-1, 0], Topology::Bounded), None);
+        assert_eq!(
+            grid.offset_with([0, 0], [-1, 0], Topology::Toroidal),
+            Some([2, 0])
+        );
+        assert_eq!(grid.neighbors8_with([0, 0], Topology::Toroidal).count(), 8);
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = example();
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.find(|&c| c == 'e'), Some([1, 1]));
+    }
+
+    #[test]
+    fn test_transform() {
+        let grid = example();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+    }
+}
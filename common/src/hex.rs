@@ -0,0 +1,321 @@
+//! Coordinates on a grid of hexagons, using the axial coordinates `q` and
+//! `r`. The third cube coordinate `s = -q - r` is implied.
+
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::iter::Sum;
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Hex {
+    pub q: i64,
+    pub r: i64,
+}
+
+/// The six unit steps in counterclockwise order, starting with the step
+/// that increases `q`.
+pub const DIRECTIONS: [Hex; 6] = [
+    Hex::new(1, 0),
+    Hex::new(1, -1),
+    Hex::new(0, -1),
+    Hex::new(-1, 0),
+    Hex::new(-1, 1),
+    Hex::new(0, 1),
+];
+
+impl Hex {
+    pub const ORIGIN: Self = Self::new(0, 0);
+
+    pub const fn new(q: i64, r: i64) -> Self {
+        Self { q, r }
+    }
+
+    /// Returns `None` if the coordinates do not sum to zero.
+    pub fn from_cube([q, r, s]: [i64; 3]) -> Option<Self> {
+        (q + r + s == 0).then_some(Self::new(q, r))
+    }
+
+    pub fn s(&self) -> i64 {
+        -self.q - self.r
+    }
+
+    pub fn cube(&self) -> [i64; 3] {
+        [self.q, self.r, self.s()]
+    }
+
+    pub fn neighbors(&self) -> [Self; 6] {
+        DIRECTIONS.map(|d| *self + d)
+    }
+
+    /// Number of steps between both hexes.
+    pub fn distance(&self, other: &Self) -> i64 {
+        let d = *self - *other;
+        (d.q.abs() + d.r.abs() + d.s().abs()) / 2
+    }
+
+    /// All hexes at exactly `radius` steps, going counterclockwise.
+    pub fn ring(&self, radius: i64) -> Vec<Self> {
+        if radius == 0 {
+            return vec![*self];
+        }
+
+        let mut hex = *self + DIRECTIONS[4] * radius;
+        let mut result = vec![];
+
+        for d in DIRECTIONS {
+            for _ in 0..radius {
+                result.push(hex);
+                hex = hex + d;
+            }
+        }
+
+        result
+    }
+
+    /// All hexes within `radius` steps, ring by ring from the center.
+    pub fn spiral(&self, radius: i64) -> Vec<Self> {
+        (0..=radius).flat_map(|k| self.ring(k)).collect()
+    }
+
+    /// Hexes crossed by a straight line from `self` to `other`, including
+    /// both ends. Every hex is a neighbor of the previous one.
+    pub fn line(&self, other: &Self) -> Vec<Self> {
+        let n = self.distance(other);
+
+        // Nudge the line slightly so that it never passes exactly between
+        // two hexes, which would make rounding ambiguous.
+        let [aq, ar, as_] = self.cube().map(|v| v as f64);
+        let [bq, br, bs] = other.cube().map(|v| v as f64);
+        let (aq, ar, as_) = (aq + 1e-6, ar + 2e-6, as_ - 3e-6);
+        let (bq, br, bs) = (bq + 1e-6, br + 2e-6, bs - 3e-6);
+
+        (0..=n)
+            .map(|i| {
+                let t = if n == 0 { 0.0 } else { i as f64 / n as f64 };
+                round_cube([aq + (bq - aq) * t, ar + (br - ar) * t, as_ + (bs - as_) * t])
+            })
+            .collect()
+    }
+}
+
+/// Rounds fractional cube coordinates to the nearest hex by fixing the
+/// coordinate with the largest rounding error.
+fn round_cube([q, r, s]: [f64; 3]) -> Hex {
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+
+    Hex::new(rq as i64, rr as i64)
+}
+
+impl Add for Hex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.q, -self.r)
+    }
+}
+
+impl Mul<i64> for Hex {
+    type Output = Self;
+
+    fn mul(self, k: i64) -> Self {
+        Self::new(self.q * k, self.r * k)
+    }
+}
+
+impl Sum for Hex {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ORIGIN, |a, b| a + b)
+    }
+}
+
+/// How the hexagons are drawn, which determines the names of the six
+/// directions and where each hex ends up on the screen.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Orientation {
+    /// Rows of hexes, with neighbors to the east and west.
+    PointyTop,
+    /// Columns of hexes, with neighbors to the north and south.
+    FlatTop,
+}
+
+impl Orientation {
+    /// Names of the entries of `DIRECTIONS`.
+    pub fn direction_names(&self) -> [&'static str; 6] {
+        match self {
+            Orientation::PointyTop => ["e", "ne", "nw", "w", "sw", "se"],
+            Orientation::FlatTop => ["se", "ne", "n", "nw", "sw", "s"],
+        }
+    }
+
+    pub fn direction(&self, name: &str) -> Option<Hex> {
+        let index = self.direction_names().iter().position(|&n| n == name)?;
+        Some(DIRECTIONS[index])
+    }
+
+    /// Parses a list of directions that are either written back to back,
+    /// like `"nwwswee"`, or separated by commas or whitespace, like
+    /// `"ne,ne,s"`. Sum the result to find where the path ends.
+    pub fn parse_directions(&self, line: &str) -> Result<Vec<Hex>, ParseHexError> {
+        let mut result = vec![];
+        let mut rest = line;
+
+        while let Some(c) = rest.chars().next() {
+            if c == ',' || c.is_whitespace() {
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+
+            // Prefer two-letter names, since "n" is a prefix of "ne".
+            let (hex, len) = [2, 1]
+                .into_iter()
+                .filter_map(|len| Some((self.direction(rest.get(..len)?)?, len)))
+                .next()
+                .ok_or(ParseHexError {
+                    column: line.len() - rest.len(),
+                })?;
+
+            result.push(hex);
+            rest = &rest[len..];
+        }
+
+        Ok(result)
+    }
+
+    /// Center of the hex on the screen, for hexes with a radius of one and
+    /// the y axis pointing down.
+    pub fn to_pixel(&self, hex: Hex) -> [f64; 2] {
+        let sqrt3 = 3f64.sqrt();
+        let (q, r) = (hex.q as f64, hex.r as f64);
+
+        match self {
+            Orientation::PointyTop => [sqrt3 * (q + r / 2.0), 1.5 * r],
+            Orientation::FlatTop => [1.5 * q, sqrt3 * (r + q / 2.0)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHexError {
+    pub column: usize,
+}
+
+impl Display for ParseHexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "column {}: invalid direction", self.column)
+    }
+}
+
+impl Error for ParseHexError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geometry() {
+        let center = Hex::new(2, -5);
+
+        for (i, n) in center.neighbors().into_iter().enumerate() {
+            assert_eq!(center.distance(&n), 1);
+            assert_eq!(n.cube().iter().sum::<i64>(), 0);
+            assert_eq!(Hex::from_cube(n.cube()), Some(n));
+            assert_eq!(DIRECTIONS[i] + DIRECTIONS[(i + 3) % 6], Hex::ORIGIN);
+        }
+
+        for radius in 0..5 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), (6 * radius).max(1) as usize);
+            assert!(ring.iter().all(|h| h.distance(&center) == radius));
+        }
+
+        assert_eq!(center.spiral(3).len(), 37);
+        assert_eq!(Hex::from_cube([1, 1, 1]), None);
+    }
+
+    #[test]
+    fn test_line() {
+        let a = Hex::new(-3, 1);
+
+        for b in Hex::ORIGIN.spiral(6) {
+            let line = a.line(&b);
+            assert_eq!(line.len() as i64, a.distance(&b) + 1);
+            assert_eq!((line[0], line[line.len() - 1]), (a, b));
+            assert!(line.windows(2).all(|w| w[0].distance(&w[1]) == 1));
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let pointy = Orientation::PointyTop;
+        let flat = Orientation::FlatTop;
+
+        let path = pointy.parse_directions("nwwswee").unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.into_iter().sum::<Hex>(), Hex::ORIGIN);
+        assert_eq!(pointy.parse_directions("esew").unwrap().len(), 3);
+
+        let end: Hex = flat
+            .parse_directions("se,sw,se,sw,sw")
+            .unwrap()
+            .into_iter()
+            .sum();
+        assert_eq!(end.distance(&Hex::ORIGIN), 3);
+        assert_eq!(flat.parse_directions("ne, ne,s,s").unwrap().len(), 4);
+
+        assert_eq!(
+            pointy.parse_directions("ene"),
+            Ok(vec![DIRECTIONS[0], DIRECTIONS[1]])
+        );
+        assert_eq!(
+            pointy.parse_directions("en"),
+            Err(ParseHexError { column: 1 })
+        );
+        assert_eq!(
+            flat.parse_directions("n,e"),
+            Err(ParseHexError { column: 2 })
+        );
+    }
+
+    #[test]
+    fn test_pixel() {
+        for orientation in [Orientation::PointyTop, Orientation::FlatTop] {
+            for d in DIRECTIONS {
+                let [x, y] = orientation.to_pixel(d);
+                assert!(((x * x + y * y).sqrt() - 3f64.sqrt()).abs() < 1e-9);
+            }
+
+            // North is up and east is right, with the y axis pointing down.
+            let names = orientation.direction_names();
+            for (name, d) in names.iter().zip(DIRECTIONS) {
+                let [x, y] = orientation.to_pixel(d);
+                assert_eq!(name.contains('n'), y < 0.0);
+                assert_eq!(name.contains('s'), y > 0.0);
+                assert_eq!(name.contains('e'), x > 0.0);
+                assert_eq!(name.contains('w'), x < 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,537 @@
+//! Sets of integers represented as half-open ranges, and their
+//! N-dimensional counterpart represented as axis-aligned boxes.
+
+use std::array;
+use std::ops::Range;
+
+fn normalize(mut ranges: Vec<Range<i64>>) -> Vec<Range<i64>> {
+    ranges.retain(|r| !r.is_empty());
+    ranges.sort_by_key(|r| r.start);
+
+    let mut output: Vec<Range<i64>> = Vec::with_capacity(ranges.len());
+    for r in ranges {
+        match output.last_mut() {
+            Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+            _ => output.push(r),
+        }
+    }
+
+    output
+}
+
+/// Set of integers stored as sorted, disjoint and non-adjacent half-open
+/// ranges.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct IntervalSet {
+    ranges: Vec<Range<i64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_ranges<I>(ranges: I) -> Self
+    where
+        I: IntoIterator<Item = Range<i64>>,
+    {
+        Self {
+            ranges: normalize(ranges.into_iter().collect()),
+        }
+    }
+
+    /// The ranges making up this set in ascending order.
+    pub fn ranges(&self) -> &[Range<i64>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of integers in the set.
+    pub fn len(&self) -> i64 {
+        self.ranges.iter().map(|r| r.end - r.start).sum()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.ranges.last().map(|r| r.end - 1)
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        let index = self.ranges.partition_point(|r| r.end <= x);
+        self.ranges.get(index).is_some_and(|r| r.contains(&x))
+    }
+
+    pub fn insert(&mut self, range: Range<i64>) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push(range);
+        self.ranges = normalize(ranges);
+    }
+
+    pub fn remove(&mut self, range: Range<i64>) {
+        *self = self.difference(&Self::from(range));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_ranges(self.ranges.iter().chain(&other.ranges).cloned())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut output = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+
+            if start < end {
+                output.push(start..end);
+            }
+
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges: output }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut output = vec![];
+        let mut j = 0;
+
+        for a in &self.ranges {
+            let mut start = a.start;
+
+            // Skip the ranges of `other` that end before this range
+            while other.ranges.get(j).is_some_and(|b| b.end <= start) {
+                j += 1;
+            }
+
+            let mut k = j;
+            while let Some(b) = other.ranges.get(k) {
+                if b.start >= a.end {
+                    break;
+                }
+
+                if b.start > start {
+                    output.push(start..b.start);
+                }
+
+                start = start.max(b.end);
+                k += 1;
+            }
+
+            if start < a.end {
+                output.push(start..a.end);
+            }
+        }
+
+        Self { ranges: output }
+    }
+}
+
+impl From<Range<i64>> for IntervalSet {
+    fn from(range: Range<i64>) -> Self {
+        Self::from_ranges([range])
+    }
+}
+
+impl FromIterator<Range<i64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Range<i64>>>(iter: I) -> Self {
+        Self::from_ranges(iter)
+    }
+}
+
+/// Piecewise-linear function on the integers that shifts every piece by a
+/// constant offset. Values outside of all pieces are mapped onto themselves.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct IntervalMap {
+    pieces: Vec<(Range<i64>, i64)>,
+}
+
+impl IntervalMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps every `x` in `range` onto `x + offset`. Panics if the range
+    /// overlaps with a previously inserted range.
+    pub fn insert(&mut self, range: Range<i64>, offset: i64) {
+        if range.is_empty() {
+            return;
+        }
+
+        let index = self.pieces.partition_point(|(r, _)| r.start < range.start);
+        let overlaps_prev = index > 0 && self.pieces[index - 1].0.end > range.start;
+        let overlaps_next = self
+            .pieces
+            .get(index)
+            .is_some_and(|(r, _)| r.start < range.end);
+        assert!(
+            !overlaps_prev && !overlaps_next,
+            "range {:?} overlaps with existing piece",
+            range
+        );
+
+        self.pieces.insert(index, (range, offset));
+    }
+
+    pub fn apply(&self, x: i64) -> i64 {
+        let index = self.pieces.partition_point(|(r, _)| r.end <= x);
+
+        match self.pieces.get(index) {
+            Some((r, offset)) if r.contains(&x) => x + offset,
+            _ => x,
+        }
+    }
+
+    /// The image of all values in `set`.
+    pub fn apply_set(&self, set: &IntervalSet) -> IntervalSet {
+        let mut output = vec![];
+
+        for range in set.ranges() {
+            let mut pos = range.start;
+            let first = self.pieces.partition_point(|(r, _)| r.end <= pos);
+
+            for (r, offset) in &self.pieces[first..] {
+                if r.start >= range.end {
+                    break;
+                }
+
+                if r.start > pos {
+                    output.push(pos..r.start);
+                }
+
+                let end = r.end.min(range.end);
+                let start = r.start.max(pos);
+                output.push((start + offset)..(end + offset));
+                pos = end;
+            }
+
+            if pos < range.end {
+                output.push(pos..range.end);
+            }
+        }
+
+        IntervalSet::from_ranges(output)
+    }
+}
+
+impl FromIterator<(Range<i64>, i64)> for IntervalMap {
+    fn from_iter<I: IntoIterator<Item = (Range<i64>, i64)>>(iter: I) -> Self {
+        let mut map = Self::new();
+
+        for (range, offset) in iter {
+            map.insert(range, offset);
+        }
+
+        map
+    }
+}
+
+/// Axis-aligned box in N dimensions: the product of N half-open ranges.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Cuboid<const N: usize> {
+    pub ranges: [Range<i64>; N],
+}
+
+impl<const N: usize> Cuboid<N> {
+    pub fn new(ranges: [Range<i64>; N]) -> Self {
+        Self { ranges }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.iter().any(|r| r.is_empty())
+    }
+
+    /// Number of integer points inside the box.
+    pub fn volume(&self) -> i64 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        self.ranges.iter().map(|r| r.end - r.start).product()
+    }
+
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        (0..N).all(|i| self.ranges[i].contains(&point[i]))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let result = Self::new(array::from_fn(|i| {
+            let (a, b) = (&self.ranges[i], &other.ranges[i]);
+            a.start.max(b.start)..a.end.min(b.end)
+        }));
+
+        (!result.is_empty()).then_some(result)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Splits the box along `axis` into the part below `value` and the part
+    /// at or above `value`. Empty parts are returned as `None`.
+    pub fn split(&self, axis: usize, value: i64) -> (Option<Self>, Option<Self>) {
+        let range = &self.ranges[axis];
+        let mid = value.clamp(range.start, range.end);
+
+        let mut lower = self.clone();
+        lower.ranges[axis] = range.start..mid;
+
+        let mut upper = self.clone();
+        upper.ranges[axis] = mid..range.end;
+
+        (
+            (!lower.is_empty()).then_some(lower),
+            (!upper.is_empty()).then_some(upper),
+        )
+    }
+
+    /// The part of this box outside `other` as at most `2 * N` disjoint
+    /// boxes.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        let overlap = match self.intersection(other) {
+            Some(overlap) => overlap,
+            None => return vec![self.clone()],
+        };
+
+        let mut output = vec![];
+        let mut rest = self.clone();
+
+        for axis in 0..N {
+            let (below, mid) = rest.split(axis, overlap.ranges[axis].start);
+            output.extend(below);
+
+            let (mid, above) = mid.unwrap().split(axis, overlap.ranges[axis].end);
+            output.extend(above);
+
+            rest = mid.unwrap();
+        }
+
+        output
+    }
+}
+
+/// Set of integer points in N dimensions, stored as disjoint boxes.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct BoxSet<const N: usize> {
+    boxes: Vec<Cuboid<N>>,
+}
+
+impl<const N: usize> BoxSet<N> {
+    pub fn new() -> Self {
+        Self { boxes: vec![] }
+    }
+
+    /// The disjoint boxes making up this set, in arbitrary order.
+    pub fn boxes(&self) -> &[Cuboid<N>] {
+        &self.boxes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    /// Number of integer points in the set.
+    pub fn volume(&self) -> i64 {
+        self.boxes.iter().map(|b| b.volume()).sum()
+    }
+
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        self.boxes.iter().any(|b| b.contains(point))
+    }
+
+    pub fn insert(&mut self, cuboid: Cuboid<N>) {
+        if !cuboid.is_empty() {
+            self.remove(&cuboid);
+            self.boxes.push(cuboid);
+        }
+    }
+
+    pub fn remove(&mut self, cuboid: &Cuboid<N>) {
+        let mut output = Vec::with_capacity(self.boxes.len());
+
+        for b in &self.boxes {
+            if b.intersects(cuboid) {
+                output.extend(b.difference(cuboid));
+            } else {
+                output.push(b.clone());
+            }
+        }
+
+        self.boxes = output;
+    }
+
+    /// The part of this set inside `cuboid`.
+    pub fn intersection(&self, cuboid: &Cuboid<N>) -> Self {
+        Self {
+            boxes: self
+                .boxes
+                .iter()
+                .filter_map(|b| b.intersection(cuboid))
+                .collect(),
+        }
+    }
+}
+
+impl<const N: usize> FromIterator<Cuboid<N>> for BoxSet<N> {
+    fn from_iter<I: IntoIterator<Item = Cuboid<N>>>(iter: I) -> Self {
+        let mut set = Self::new();
+
+        for cuboid in iter {
+            set.insert(cuboid);
+        }
+
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn to_bits(set: &IntervalSet) -> u64 {
+        (0..64).filter(|&i| set.contains(i)).map(|i| 1 << i).sum()
+    }
+
+    fn random_set(rng: &mut StdRng) -> IntervalSet {
+        let n = rng.gen_range(0..5);
+        (0..n)
+            .map(|_| {
+                let start = rng.gen_range(0..64);
+                start..rng.gen_range(start..=64)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_interval_set() {
+        let set = IntervalSet::from_ranges([5..8, 1..3, 3..4, 10..10, 7..9]);
+        assert_eq!(set.ranges(), [1..4, 5..9]);
+        assert_eq!(set.len(), 7);
+        assert_eq!((set.min(), set.max()), (Some(1), Some(8)));
+        assert!(set.contains(3) && !set.contains(4) && !set.contains(9));
+
+        let mut set = set;
+        set.remove(2..6);
+        set.insert(20..22);
+        assert_eq!(set.ranges(), [1..2, 6..9, 20..22]);
+
+        // Compare the set operations against bitmasks
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..1000 {
+            let a = random_set(&mut rng);
+            let b = random_set(&mut rng);
+            let (x, y) = (to_bits(&a), to_bits(&b));
+
+            assert_eq!(to_bits(&a.union(&b)), x | y);
+            assert_eq!(to_bits(&a.intersection(&b)), x & y);
+            assert_eq!(to_bits(&a.difference(&b)), x & !y);
+            assert_eq!(a.len(), x.count_ones() as i64);
+
+            // Results are normalized, so equal sets compare equal
+            assert_eq!(a.union(&b), b.union(&a));
+            assert_eq!(a.difference(&b).union(&a.intersection(&b)), a);
+        }
+    }
+
+    #[test]
+    fn test_interval_map() {
+        let map = IntervalMap::from_iter([(10..20, 100), (0..5, -5)]);
+        assert_eq!(map.apply(-1), -1);
+        assert_eq!(map.apply(0), -5);
+        assert_eq!(map.apply(7), 7);
+        assert_eq!(map.apply(19), 119);
+        assert_eq!(map.apply(20), 20);
+
+        let image = map.apply_set(&IntervalSet::from(3..15));
+        assert_eq!(image.ranges(), [-2..0, 5..10, 110..115]);
+
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..100 {
+            let set = random_set(&mut rng);
+            let image = map.apply_set(&set);
+            let expected: IntervalSet = (0..64)
+                .filter(|&x| set.contains(x))
+                .map(|x| map.apply(x))
+                .map(|x| x..x + 1)
+                .collect();
+
+            assert_eq!(image, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_interval_map_overlap() {
+        IntervalMap::from_iter([(0..10, 1), (5..15, 2)]);
+    }
+
+    #[test]
+    fn test_cuboid() {
+        let a = Cuboid::new([0..4, 0..4, 0..4]);
+        let b = Cuboid::new([2..6, 1..2, -3..3]);
+
+        assert_eq!(a.volume(), 64);
+        assert_eq!(a.intersection(&b), Some(Cuboid::new([2..4, 1..2, 0..3])));
+        assert_eq!(Cuboid::new([0..0, 0..4]).volume(), 0);
+
+        let parts = a.difference(&b);
+        assert!(parts.len() <= 6);
+        assert_eq!(parts.iter().map(|p| p.volume()).sum::<i64>(), 64 - 6);
+
+        let (lower, upper) = a.split(0, 1);
+        assert_eq!(lower, Some(Cuboid::new([0..1, 0..4, 0..4])));
+        assert_eq!(upper, Some(Cuboid::new([1..4, 0..4, 0..4])));
+        assert_eq!(a.split(1, 10), (Some(a.clone()), None));
+    }
+
+    #[test]
+    fn test_box_set() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut set = BoxSet::<2>::new();
+        let mut grid = [[false; 16]; 16];
+
+        for _ in 0..200 {
+            let ranges = array::from_fn(|_| {
+                let start = rng.gen_range(0..16);
+                start..rng.gen_range(start..=16)
+            });
+            let cuboid = Cuboid::new(ranges);
+            let on = rng.gen_bool(0.5);
+
+            for x in cuboid.ranges[0].clone() {
+                for y in cuboid.ranges[1].clone() {
+                    grid[x as usize][y as usize] = on;
+                }
+            }
+
+            if on {
+                set.insert(cuboid);
+            } else {
+                set.remove(&cuboid);
+            }
+
+            let expected = grid.iter().flatten().filter(|&&b| b).count();
+            assert_eq!(set.volume(), expected as i64);
+            assert!(set.contains([3, 5]) == grid[3][5]);
+        }
+
+        let window = Cuboid::new([0..8, 4..12]);
+        let expected = (0..8)
+            .flat_map(|x| (4..12).map(move |y| (x, y)))
+            .filter(|&(x, y)| grid[x][y])
+            .count();
+        assert_eq!(set.intersection(&window).volume(), expected as i64);
+    }
+}
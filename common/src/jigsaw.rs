@@ -0,0 +1,286 @@
+//! Assembling square tiles into a rectangle such that the borders of
+//! neighboring tiles match.
+
+use crate::dihedral::D4;
+use crate::grid::Grid;
+use fnv::FnvHashMap;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::hash::Hash;
+
+/// Tile together with the orientation in which it is placed.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Placement {
+    pub tile: usize,
+    pub orientation: D4,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JigsawError {
+    NoSolution,
+    Ambiguous,
+}
+
+impl Display for JigsawError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JigsawError::NoSolution => write!(f, "tiles cannot be assembled"),
+            JigsawError::Ambiguous => write!(f, "tiles can be assembled in more than one way"),
+        }
+    }
+}
+
+impl Error for JigsawError {}
+
+const TOP: usize = 0;
+const RIGHT: usize = 1;
+const BOTTOM: usize = 2;
+const LEFT: usize = 3;
+
+/// Borders of a tile in some orientation as `[top, right, bottom, left]`,
+/// each read from left to right or from top to bottom.
+fn borders<T: Clone>(tile: &Grid<T>) -> [Vec<T>; 4] {
+    let (w, h) = (tile.width(), tile.height());
+
+    [
+        tile.row(0).to_vec(),
+        tile.column(w - 1).cloned().collect(),
+        tile.row(h - 1).to_vec(),
+        tile.column(0).cloned().collect(),
+    ]
+}
+
+/// The whole layout turned and mirrored by `sym`.
+fn transform(layout: &Grid<Placement>, sym: D4) -> Grid<Placement> {
+    sym.apply(layout).map(|p| Placement {
+        tile: p.tile,
+        orientation: sym.compose(&p.orientation),
+    })
+}
+
+struct Solver<'a, T> {
+    width: usize,
+    size: usize,
+    options: &'a [(Placement, [Vec<T>; 4])],
+    by_left: &'a FnvHashMap<Vec<T>, Vec<usize>>,
+    by_top: &'a FnvHashMap<Vec<T>, Vec<usize>>,
+    starts: &'a [usize],
+    used: Vec<bool>,
+    current: Vec<usize>,
+    solutions: Vec<Grid<Placement>>,
+}
+
+impl<'a, T: Eq + Hash> Solver<'a, T> {
+    fn search(&mut self) {
+        // Two solutions that are not turned or mirrored versions of each
+        // other are enough to know the puzzle is ambiguous.
+        if self.solutions.len() > 1 {
+            return;
+        }
+
+        let index = self.current.len();
+        if index == self.size {
+            self.add_solution();
+            return;
+        }
+
+        let matching = |map: &'a FnvHashMap<Vec<T>, Vec<usize>>, border: &Vec<T>| {
+            map.get(border).map_or(&[][..], |v| &v[..])
+        };
+
+        let above = (index >= self.width).then(|| &self.options[self.current[index - self.width]]);
+        let candidates = if index == 0 {
+            self.starts
+        } else if index % self.width != 0 {
+            let left = &self.options[self.current[index - 1]];
+            matching(self.by_left, &left.1[RIGHT])
+        } else {
+            matching(self.by_top, &above.unwrap().1[BOTTOM])
+        };
+
+        for &option in candidates {
+            let (placement, borders) = &self.options[option];
+
+            if self.used[placement.tile] || above.is_some_and(|a| a.1[BOTTOM] != borders[TOP]) {
+                continue;
+            }
+
+            self.used[placement.tile] = true;
+            self.current.push(option);
+            self.search();
+            self.current.pop();
+            self.used[placement.tile] = false;
+        }
+    }
+
+    fn add_solution(&mut self) {
+        let layout = Grid::from_fn(self.width, self.size / self.width, |[x, y]| {
+            self.options[self.current[y * self.width + x]].0
+        });
+
+        // Every solution can be turned and mirrored as a whole, so the
+        // search finds it once for every corner that it starts from.
+        let is_variant = |other: &Grid<Placement>| {
+            D4::all()
+                .into_iter()
+                .any(|sym| transform(other, sym) == layout)
+        };
+
+        if !self.solutions.iter().any(is_variant) {
+            self.solutions.push(layout);
+        }
+    }
+}
+
+/// Arranges the tiles into a rectangle that is `width` tiles wide, turning
+/// and mirroring them as needed, such that the borders of adjacent tiles
+/// are equal. All tiles must be squares of the same size. The solution is
+/// only unique up to turning and mirroring the whole picture, and any of
+/// these variants may be returned. Returns an error if there is no solution
+/// or more than one.
+///
+/// The search starts from the tiles that have two borders that match no
+/// other tile, since those must be the corners if the borders on the
+/// outside of the picture are unique. Only if that fails is every tile
+/// tried as the top-left corner. A single solution found from the corners
+/// is returned without trying the other tiles, so a second layout is only
+/// reported as ambiguous if it also has those tiles in its corners. When
+/// the outer borders are not unique, this may return one of several
+/// solutions instead of an error.
+pub fn solve_jigsaw<T>(tiles: &[Grid<T>], width: usize) -> Result<Grid<Placement>, JigsawError>
+where
+    T: Clone + Eq + Hash,
+{
+    let n = tiles.len();
+    let dim = tiles.first().ok_or(JigsawError::NoSolution)?.dim();
+    assert!(
+        dim[0] == dim[1] && tiles.iter().all(|t| t.dim() == dim),
+        "tiles must be squares of the same size"
+    );
+
+    if width == 0 || n % width != 0 {
+        return Err(JigsawError::NoSolution);
+    }
+
+    let mut options = vec![];
+    let mut by_left = FnvHashMap::<_, Vec<_>>::default();
+    let mut by_top = FnvHashMap::<_, Vec<_>>::default();
+
+    for (tile, grid) in tiles.iter().enumerate() {
+        for orientation in D4::all() {
+            let borders = borders(&orientation.apply(grid));
+            by_left
+                .entry(borders[LEFT].clone())
+                .or_default()
+                .push(options.len());
+            by_top
+                .entry(borders[TOP].clone())
+                .or_default()
+                .push(options.len());
+            options.push((Placement { tile, orientation }, borders));
+        }
+    }
+
+    // Every border of every tile is the left border in some orientation.
+    let is_unmatched =
+        |tile: usize, border: &Vec<T>| by_left[border].iter().all(|&i| options[i].0.tile == tile);
+
+    let corners = (0..options.len())
+        .filter(|&i| {
+            let (placement, borders) = &options[i];
+            is_unmatched(placement.tile, &borders[TOP])
+                && is_unmatched(placement.tile, &borders[LEFT])
+        })
+        .collect::<Vec<_>>();
+    let everything = (0..options.len()).collect::<Vec<_>>();
+
+    for starts in [&corners, &everything] {
+        let mut solver = Solver {
+            width,
+            size: n,
+            options: &options,
+            by_left: &by_left,
+            by_top: &by_top,
+            starts,
+            used: vec![false; n],
+            current: vec![],
+            solutions: vec![],
+        };
+
+        solver.search();
+
+        match solver.solutions.len() {
+            0 => continue,
+            1 => return Ok(solver.solutions.pop().unwrap()),
+            _ => return Err(JigsawError::Ambiguous),
+        }
+    }
+
+    Err(JigsawError::NoSolution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    /// Cuts a random picture into `cols` by `rows` tiles of size 4 that
+    /// share their borders, like the tiles of 2020 day 20.
+    fn cut_picture(rng: &mut StdRng, cols: usize, rows: usize) -> (Grid<u32>, Vec<Grid<u32>>) {
+        let picture = Grid::from_fn(3 * cols + 1, 3 * rows + 1, |_| rng.gen_range(0..1000));
+        let mut tiles = vec![];
+
+        for ty in 0..rows {
+            for tx in 0..cols {
+                tiles.push(Grid::from_fn(4, 4, |[x, y]| {
+                    picture[[3 * tx + x, 3 * ty + y]]
+                }));
+            }
+        }
+
+        (picture, tiles)
+    }
+
+    #[test]
+    fn test_solve() {
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for (cols, rows) in [(3, 3), (4, 2), (1, 5), (5, 3), (12, 12)] {
+            let (picture, mut tiles) = cut_picture(&mut rng, cols, rows);
+
+            tiles.shuffle(&mut rng);
+            for tile in &mut tiles {
+                *tile = D4::all()[rng.gen_range(0..8)].apply(tile);
+            }
+
+            let layout = solve_jigsaw(&tiles, cols).unwrap();
+            assert_eq!(layout.dim(), [cols, rows]);
+
+            // Put the tiles back together, the picture may come out turned
+            // or mirrored.
+            let placed = layout.map(|p| p.orientation.apply(&tiles[p.tile]));
+            let result = Grid::from_fn(3 * cols + 1, 3 * rows + 1, |[x, y]| {
+                let [tx, ty] = [usize::min(x / 3, cols - 1), usize::min(y / 3, rows - 1)];
+                placed[[tx, ty]][[x - 3 * tx, y - 3 * ty]]
+            });
+
+            assert!(D4::all().iter().any(|sym| sym.apply(&picture) == result));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let (_, mut tiles) = cut_picture(&mut rng, 2, 2);
+        assert!(solve_jigsaw(&tiles, 2).is_ok());
+        assert_eq!(solve_jigsaw(&tiles, 3), Err(JigsawError::NoSolution));
+
+        // Tiles with a constant border fit together in many ways
+        let blank = vec![Grid::from_elem(3, 3, 0); 4];
+        assert_eq!(solve_jigsaw(&blank, 2), Err(JigsawError::Ambiguous));
+
+        tiles[3] = Grid::from_elem(4, 4, 1000);
+        assert_eq!(solve_jigsaw(&tiles, 2), Err(JigsawError::NoSolution));
+        assert_eq!(solve_jigsaw::<u32>(&[], 1), Err(JigsawError::NoSolution));
+    }
+}
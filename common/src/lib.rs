@@ -0,0 +1,20 @@
+//! Data structures and algorithms shared between the solutions of the
+//! different years.
+
+pub mod assume;
+pub mod automaton;
+pub mod bitset;
+pub mod cycle;
+pub mod dihedral;
+pub mod grid;
+pub mod hex;
+pub mod interval;
+pub mod jigsaw;
+pub mod manhattan;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod polygon;
+pub mod rotation;
+pub mod search;
+pub mod sparse;
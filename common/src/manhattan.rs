@@ -0,0 +1,364 @@
+//! Balls under the Manhattan (L1) metric, which are diamonds in two and
+//! octahedra in three dimensions.
+
+use crate::interval::{BoxSet, Cuboid};
+use std::array;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+pub fn distance<const N: usize>(a: [i64; N], b: [i64; N]) -> i64 {
+    (0..N).map(|i| (a[i] - b[i]).abs()).sum()
+}
+
+/// All points within Manhattan distance `radius` of `center`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Ball<const N: usize> {
+    pub center: [i64; N],
+    pub radius: i64,
+}
+
+impl<const N: usize> Ball<N> {
+    pub fn new(center: [i64; N], radius: i64) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn contains(&self, p: [i64; N]) -> bool {
+        distance(self.center, p) <= self.radius
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        distance(self.center, other.center) <= self.radius + other.radius
+    }
+
+    /// Smallest box containing the ball.
+    pub fn bounding_box(&self) -> Cuboid<N> {
+        Cuboid::new(array::from_fn(|i| {
+            (self.center[i] - self.radius)..(self.center[i] + self.radius + 1)
+        }))
+    }
+
+    /// Distance from the center to the nearest point in `cuboid`, which must
+    /// not be empty.
+    pub fn distance_to_box(&self, cuboid: &Cuboid<N>) -> i64 {
+        (0..N)
+            .map(|i| {
+                let r = &cuboid.ranges[i];
+                (r.start - self.center[i]).max(0) + (self.center[i] - (r.end - 1)).max(0)
+            })
+            .sum()
+    }
+
+    pub fn intersects_box(&self, cuboid: &Cuboid<N>) -> bool {
+        !cuboid.is_empty() && self.distance_to_box(cuboid) <= self.radius
+    }
+
+    /// Returns `true` if every point of `cuboid` is inside the ball. Since
+    /// balls are convex, it suffices to check the farthest corner.
+    pub fn contains_box(&self, cuboid: &Cuboid<N>) -> bool {
+        let farthest: i64 = (0..N)
+            .map(|i| {
+                let r = &cuboid.ranges[i];
+                i64::max(
+                    (r.start - self.center[i]).abs(),
+                    (r.end - 1 - self.center[i]).abs(),
+                )
+            })
+            .sum();
+
+        cuboid.is_empty() || farthest <= self.radius
+    }
+}
+
+/// Rotates `[x, y]` by 45 degrees into `[x + y, x - y]`. Manhattan distance
+/// in the original basis becomes Chebyshev (L∞) distance in the new one, so
+/// diamonds turn into axis-aligned squares.
+pub fn rotate([x, y]: [i64; 2]) -> [i64; 2] {
+    [x + y, x - y]
+}
+
+/// Inverse of `rotate`. Only points where both coordinates have the same
+/// parity correspond to a lattice point.
+pub fn unrotate([u, v]: [i64; 2]) -> Option<[i64; 2]> {
+    ((u + v) % 2 == 0).then(|| [(u + v) / 2, (u - v) / 2])
+}
+
+impl Ball<2> {
+    /// The ball as a square in the rotated basis.
+    pub fn rotated(&self) -> Cuboid<2> {
+        let [u, v] = rotate(self.center);
+        let r = self.radius;
+        Cuboid::new([(u - r)..(u + r + 1), (v - r)..(v + r + 1)])
+    }
+}
+
+fn count_even(range: &std::ops::Range<i64>) -> i64 {
+    (range.end + 1).div_euclid(2) - (range.start + 1).div_euclid(2)
+}
+
+/// Number of lattice points inside a region given in the rotated basis.
+pub fn count_rotated(region: &BoxSet<2>) -> i64 {
+    region
+        .boxes()
+        .iter()
+        .map(|b| {
+            let [us, vs] = &b.ranges;
+            let (even_u, even_v) = (count_even(us), count_even(vs));
+            let (odd_u, odd_v) = (us.end - us.start - even_u, vs.end - vs.start - even_v);
+            even_u * even_v + odd_u * odd_v
+        })
+        .sum()
+}
+
+/// Union of diamonds in the rotated basis.
+pub fn union_rotated(balls: &[Ball<2>]) -> BoxSet<2> {
+    balls.iter().map(|b| b.rotated()).collect()
+}
+
+/// Splits a box into up to `2^N` parts by halving every axis.
+fn subdivide<const N: usize>(cuboid: &Cuboid<N>) -> Vec<Cuboid<N>> {
+    let mut parts = vec![cuboid.clone()];
+
+    for (axis, r) in cuboid.ranges.iter().enumerate() {
+        if r.end - r.start > 1 {
+            let mid = r.start + (r.end - r.start) / 2;
+            parts = parts
+                .iter()
+                .flat_map(|p| {
+                    let (lower, upper) = p.split(axis, mid);
+                    lower.into_iter().chain(upper)
+                })
+                .collect();
+        }
+    }
+
+    parts
+}
+
+fn corner<const N: usize>(cuboid: &Cuboid<N>) -> [i64; N] {
+    array::from_fn(|i| cuboid.ranges[i].start)
+}
+
+/// Finds a point inside `region` that is not covered by any of the balls,
+/// by recursively subdividing the region and discarding the parts that lie
+/// entirely within a single ball.
+pub fn find_uncovered<const N: usize>(balls: &[Ball<N>], region: &Cuboid<N>) -> Option<[i64; N]> {
+    let mut stack = vec![(region.clone(), balls.to_vec())];
+
+    while let Some((cuboid, candidates)) = stack.pop() {
+        if cuboid.is_empty() || candidates.iter().any(|b| b.contains_box(&cuboid)) {
+            continue;
+        }
+
+        let candidates = candidates
+            .into_iter()
+            .filter(|b| b.intersects_box(&cuboid))
+            .collect::<Vec<_>>();
+
+        // Either a single point that no ball contains, or no ball touches
+        // the box at all.
+        if cuboid.volume() == 1 || candidates.is_empty() {
+            return Some(corner(&cuboid));
+        }
+
+        for part in subdivide(&cuboid) {
+            stack.push((part, candidates.clone()));
+        }
+    }
+
+    None
+}
+
+/// Box in the priority queue of `max_overlap`, ordered by the number of
+/// balls touching it, then by distance to the target and then by size.
+struct Candidate<const N: usize> {
+    count: usize,
+    volume: i64,
+    distance: i64,
+    cuboid: Cuboid<N>,
+}
+
+impl<const N: usize> Candidate<N> {
+    fn key(&self) -> (usize, Reverse<i64>, Reverse<i64>) {
+        (self.count, Reverse(self.distance), Reverse(self.volume))
+    }
+}
+
+impl<const N: usize> PartialEq for Candidate<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<const N: usize> Eq for Candidate<N> {}
+
+impl<const N: usize> PartialOrd for Candidate<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Ord for Candidate<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// Finds the point covered by the largest number of balls, together with
+/// that number. Ties are broken by choosing the point closest to `target`.
+///
+/// This is a best-first search over an octree. The number of balls that
+/// touch a box is an upper bound for every point inside it, and the distance
+/// from the target to a box is a lower bound, so the first single point that
+/// is taken from the queue is optimal.
+pub fn max_overlap<const N: usize>(
+    balls: &[Ball<N>],
+    target: [i64; N],
+) -> Option<([i64; N], usize)> {
+    let origin = Ball::new(target, 0);
+    let region = Cuboid::new(array::from_fn(|i| {
+        let start = balls.iter().map(|b| b.center[i] - b.radius).min();
+        let end = balls.iter().map(|b| b.center[i] + b.radius + 1).max();
+        start.unwrap_or_default()..end.unwrap_or_default()
+    }));
+
+    if region.is_empty() {
+        return None;
+    }
+
+    let mut queue = BinaryHeap::new();
+    queue.push(Candidate {
+        count: balls.len(),
+        volume: region.volume(),
+        distance: 0,
+        cuboid: region,
+    });
+
+    while let Some(candidate) = queue.pop() {
+        if candidate.volume == 1 {
+            return Some((corner(&candidate.cuboid), candidate.count));
+        }
+
+        for part in subdivide(&candidate.cuboid) {
+            queue.push(Candidate {
+                count: balls.iter().filter(|b| b.intersects_box(&part)).count(),
+                volume: part.volume(),
+                distance: origin.distance_to_box(&part),
+                cuboid: part,
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn random_balls<const N: usize>(rng: &mut StdRng, n: usize, size: i64) -> Vec<Ball<N>> {
+        (0..n)
+            .map(|_| {
+                let center = array::from_fn(|_| rng.gen_range(-size..=size));
+                Ball::new(center, rng.gen_range(0..size))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ball() {
+        let ball = Ball::new([0, 0], 2);
+        assert!(ball.contains([1, -1]));
+        assert!(!ball.contains([2, 1]));
+        assert!(ball.intersects(&Ball::new([3, 2], 3)));
+        assert!(!ball.intersects(&Ball::new([3, 2], 2)));
+
+        assert!(ball.contains_box(&Cuboid::new([0..2, -1..1])));
+        assert!(!ball.contains_box(&Cuboid::new([0..2, -1..3])));
+        assert!(ball.intersects_box(&Cuboid::new([2..5, 0..5])));
+        assert!(!ball.intersects_box(&Cuboid::new([2..5, 1..5])));
+        assert_eq!(ball.distance_to_box(&Cuboid::new([4..6, -9..-3])), 8);
+
+        assert_eq!(rotate([3, 1]), [4, 2]);
+        assert_eq!(unrotate([4, 2]), Some([3, 1]));
+        assert_eq!(unrotate([4, 1]), None);
+        assert_eq!(count_rotated(&union_rotated(&[ball])), 13);
+    }
+
+    #[test]
+    fn test_union() {
+        let mut rng = StdRng::seed_from_u64(4);
+
+        for _ in 0..50 {
+            let balls = random_balls::<2>(&mut rng, 5, 10);
+            let bounds = Cuboid::new([-20..21, -20..21]);
+            let expected = (-20..=20)
+                .flat_map(|x| (-20..=20).map(move |y| [x, y]))
+                .filter(|&p| balls.iter().any(|b| b.contains(p)))
+                .count();
+
+            assert_eq!(count_rotated(&union_rotated(&balls)), expected as i64);
+
+            match find_uncovered(&balls, &bounds) {
+                Some(p) => {
+                    assert!(bounds.contains(p));
+                    assert!(balls.iter().all(|b| !b.contains(p)));
+                }
+                None => assert_eq!(expected, 41 * 41),
+            }
+        }
+
+        // Only the center of the box is not covered
+        let mut balls = vec![];
+        for [dx, dy] in [
+            [1, 0],
+            [-1, 0],
+            [0, 1],
+            [0, -1],
+            [1, 1],
+            [1, -1],
+            [-1, 1],
+            [-1, -1],
+        ] {
+            let radius = 5 * i64::abs(dx) + 5 * i64::abs(dy) - 1;
+            balls.push(Ball::new([5 + 5 * dx, 5 + 5 * dy], radius));
+        }
+
+        let bounds = Cuboid::new([0..11, 0..11]);
+        assert_eq!(find_uncovered(&balls, &bounds), Some([5, 5]));
+
+        balls.push(Ball::new([5, 5], 0));
+        assert_eq!(find_uncovered(&balls, &bounds), None);
+    }
+
+    #[test]
+    fn test_max_overlap() {
+        // Example from 2018 day 23
+        let bots = [
+            Ball::new([10, 12, 12], 2),
+            Ball::new([12, 14, 12], 2),
+            Ball::new([16, 12, 12], 4),
+            Ball::new([14, 14, 14], 6),
+            Ball::new([50, 50, 50], 200),
+            Ball::new([10, 10, 10], 5),
+        ];
+        assert_eq!(max_overlap(&bots, [0, 0, 0]), Some(([12, 12, 12], 5)));
+        assert_eq!(max_overlap::<3>(&[], [0, 0, 0]), None);
+
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..20 {
+            let balls = random_balls::<2>(&mut rng, 8, 10);
+            let count = |p| balls.iter().filter(|b| b.contains(p)).count();
+            let (point, n) = max_overlap(&balls, [0, 0]).unwrap();
+
+            let best = (-20..=20)
+                .flat_map(|x| (-20..=20).map(move |y| [x, y]))
+                .max_by_key(|&p| (count(p), Reverse(distance(p, [0, 0]))))
+                .unwrap();
+
+            assert_eq!(n, count(point));
+            assert_eq!(n, count(best));
+            assert_eq!(distance(point, [0, 0]), distance(best, [0, 0]));
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! Number theory on `i64`. Intermediate products are computed using `i128`,
+//! so none of these functions overflow as long as the inputs and outputs
+//! fit in an `i64`.
+
+use fnv::FnvHashMap;
+
+/// Greatest common divisor, which is always non-negative. Note that
+/// `gcd(0, 0) == 0`.
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a as i64
+}
+
+/// Least common multiple, which is always non-negative.
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+
+    (a / gcd(a, b) * b).abs()
+}
+
+/// Extended Euclidean algorithm: returns `(g, x, y)` such that
+/// `a * x + b * y == g` where `g == gcd(a, b)`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut r0, mut r1) = (a as i128, b as i128);
+    let (mut x0, mut x1) = (1i128, 0i128);
+    let (mut y0, mut y1) = (0i128, 1i128);
+
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+
+    if r0 < 0 {
+        (r0, x0, y0) = (-r0, -x0, -y0);
+    }
+
+    (r0 as i64, x0 as i64, y0 as i64)
+}
+
+/// Computes `a * b` modulo `m`, the result is in `0..m`.
+pub fn mod_mul(a: i64, b: i64, m: i64) -> i64 {
+    (a as i128 * b as i128).rem_euclid(m as i128) as i64
+}
+
+/// Computes `base` to the power `exp` modulo `m`, the result is in `0..m`.
+pub fn mod_pow(base: i64, mut exp: u64, m: i64) -> i64 {
+    let mut base = base.rem_euclid(m);
+    let mut result = 1 % m;
+
+    while exp > 0 {
+        if exp % 2 == 1 {
+            result = mod_mul(result, base, m);
+        }
+
+        base = mod_mul(base, base, m);
+        exp /= 2;
+    }
+
+    result
+}
+
+/// The `x` in `0..m` such that `a * x == 1` modulo `m`. Only exists if `a`
+/// and `m` are coprime.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Chinese remainder theorem for a system of congruences `x == r (mod m)`
+/// given as `(r, m)` pairs. The moduli do not need to be coprime. Returns
+/// `(x, l)` where `l` is the least common multiple of the moduli and `x` is
+/// the unique solution in `0..l`, or `None` if a modulus is not positive, the
+/// system has no solution or `l` does not fit in an `i64`.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut x = 0i128;
+    let mut l = 1i128;
+
+    for &(r, m) in congruences {
+        if m <= 0 {
+            return None;
+        }
+
+        let (r, m) = (r as i128, m as i128);
+        let (g, p, _) = extended_gcd(l as i64, m as i64);
+        let g = g as i128;
+
+        // We need x + l * k == r (mod m), so l * k == r - x (mod m)
+        let diff = r - x;
+        if diff % g != 0 {
+            return None;
+        }
+
+        let step = m / g;
+        let k = (diff / g).rem_euclid(step) * (p as i128).rem_euclid(step) % step;
+
+        x += l * k;
+        l *= step;
+        x = x.rem_euclid(l);
+
+        if l > i64::MAX as i128 {
+            return None;
+        }
+    }
+
+    Some((x as i64, l as i64))
+}
+
+/// Discrete logarithm: the smallest `k >= 0` such that `base` to the power
+/// `k` equals `target` modulo `m`, found using baby-step giant-step in
+/// `O(sqrt(m))` time. The base and the modulus do not need to be coprime.
+pub fn discrete_log(base: i64, target: i64, m: i64) -> Option<i64> {
+    let mut m = m;
+    let mut base = base.rem_euclid(m);
+    let mut target = target.rem_euclid(m);
+    let mut factor = 1 % m;
+    let mut offset = 0;
+
+    if m == 1 {
+        return Some(0);
+    }
+
+    // Divide out the factors shared by base and modulus until they are
+    // coprime. Afterwards we solve: factor * base^k == target (mod m)
+    loop {
+        let g = gcd(base, m);
+        if g == 1 {
+            break;
+        }
+
+        if target == factor {
+            return Some(offset);
+        }
+
+        if target % g != 0 {
+            return None;
+        }
+
+        target /= g;
+        m /= g;
+        offset += 1;
+        factor = mod_mul(factor, base / g, m);
+        base %= m;
+        target %= m;
+    }
+
+    if target == factor % m {
+        return Some(offset);
+    }
+
+    let n = (m as f64).sqrt().ceil() as i64;
+
+    // Baby steps: target * base^j for 0 <= j < n. Later insertions overwrite
+    // earlier ones, so the largest j is kept, giving the smallest k below.
+    let mut table = FnvHashMap::default();
+    let mut value = target;
+    for j in 0..n {
+        table.insert(value, j);
+        value = mod_mul(value, base, m);
+    }
+
+    // Giant steps: factor * base^(n * i) for 1 <= i <= n
+    let giant = mod_pow(base, n as u64, m);
+    let mut value = factor;
+    for i in 1..=n {
+        value = mod_mul(value, giant, m);
+
+        if let Some(&j) = table.get(&value) {
+            return Some(i * n - j + offset);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    const NUM_CASES: usize = 2000;
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(0x5eed)
+    }
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, -5), 5);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+
+        let mut rng = rng();
+        for _ in 0..NUM_CASES {
+            let a = rng.gen_range(-1_000_000_000_000..1_000_000_000_000);
+            let b = rng.gen_range(-1_000_000_000_000..1_000_000_000_000);
+
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a as i128 * x as i128 + b as i128 * y as i128, g as i128);
+
+            if g != 0 {
+                assert_eq!(a % g, 0);
+                assert_eq!(b % g, 0);
+                assert_eq!(gcd(a / g, b / g), 1);
+            }
+
+            let (a, b) = (a % 1_000_000, b % 1_000_000);
+            if a != 0 && b != 0 {
+                let l = lcm(a, b);
+                assert_eq!(l % a, 0);
+                assert_eq!(l % b, 0);
+                assert_eq!(l as i128 * gcd(a, b) as i128, (a as i128 * b as i128).abs());
+            }
+        }
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_inverse(3, 7), Some(5));
+        assert_eq!(mod_inverse(4, 8), None);
+
+        let mut rng = rng();
+        for _ in 0..NUM_CASES {
+            let m = rng.gen_range(1..i64::MAX);
+            let a = rng.gen_range(i64::MIN..i64::MAX);
+            let b = rng.gen_range(i64::MIN..i64::MAX);
+
+            let p = mod_mul(a, b, m);
+            assert!((0..m).contains(&p));
+            assert_eq!(p as i128, (a as i128 * b as i128).rem_euclid(m as i128));
+
+            if let Some(inv) = mod_inverse(a, m) {
+                assert_eq!(mod_mul(a, inv, m), 1 % m);
+            } else {
+                assert_ne!(gcd(a, m), 1);
+            }
+
+            let exp = rng.gen_range(0..50);
+            let expected = (0..exp).fold(1 % m, |acc, _| mod_mul(acc, a, m));
+            assert_eq!(mod_pow(a, exp, m), expected);
+        }
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(1, 4), (0, 0)]), None);
+        assert_eq!(crt(&[(1, -4)]), None);
+
+        let mut rng = rng();
+        for _ in 0..NUM_CASES {
+            let n = rng.gen_range(1..5);
+            let moduli: Vec<i64> = (0..n).map(|_| rng.gen_range(1..30)).collect();
+            let l = moduli.iter().fold(1, |l, &m| lcm(l, m));
+
+            // Consistent system derived from a known solution
+            let x = rng.gen_range(0..l);
+            let system: Vec<_> = moduli.iter().map(|&m| (x + m * 3 - m, m)).collect();
+            assert_eq!(crt(&system), Some((x, l)));
+
+            // Arbitrary residues, compared against a brute-force search
+            let system: Vec<_> = moduli.iter().map(|&m| (rng.gen_range(0..m), m)).collect();
+            let expected = (0..l).find(|x| system.iter().all(|&(r, m)| x % m == r));
+            assert_eq!(crt(&system), expected.map(|x| (x, l)));
+        }
+
+        // Large moduli do not overflow
+        let p = 1_000_000_007;
+        let q = 998_244_353;
+        let (x, l) = crt(&[(5, p), (7, q)]).unwrap();
+        assert_eq!(l, p * q);
+        assert_eq!((x % p, x % q), (5, 7));
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(discrete_log(7, 5764801, 20201227), Some(8));
+        assert_eq!(discrete_log(2, 1, 7), Some(0));
+        assert_eq!(discrete_log(2, 3, 7), None);
+        assert_eq!(discrete_log(2, 0, 8), Some(3));
+
+        let mut rng = rng();
+        for _ in 0..NUM_CASES {
+            let m = rng.gen_range(1..200);
+            let base = rng.gen_range(0..m);
+            let target = rng.gen_range(0..m);
+
+            // The powers of base are periodic after at most m steps
+            let expected = (0..2 * m).find(|&k| mod_pow(base, k as u64, m) == target % m);
+            assert_eq!(
+                discrete_log(base, target, m),
+                expected,
+                "{}^k == {} (mod {})",
+                base,
+                target,
+                m
+            );
+        }
+
+        let p = 1_000_000_007;
+        let k = discrete_log(5, 123_456_789, p).unwrap();
+        assert_eq!(mod_pow(5, k as u64, p), 123_456_789);
+    }
+}
@@ -0,0 +1,177 @@
+//! Memoization of recursive functions. The function receives a `recur`
+//! callback that it calls instead of itself, so its body reads as plain
+//! recursion while the result for every key is computed only once. Keys may
+//! borrow from the caller, such as tuples of slices. By default the results
+//! are kept in a hash map, or in a vector if every key can be mapped to an
+//! index.
+
+use fnv::FnvHashMap;
+use std::fmt::{self, Display};
+use std::hash::Hash;
+
+/// Where the results of a memoized function are kept.
+pub trait Store<K, V> {
+    fn lookup(&self, key: &K) -> Option<&V>;
+    fn store(&mut self, key: K, value: V);
+}
+
+impl<K: Hash + Eq, V> Store<K, V> for FnvHashMap<K, V> {
+    fn lookup(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn store(&mut self, key: K, value: V) {
+        self.insert(key, value);
+    }
+}
+
+/// Keeps results in a vector, where `index` maps each key to a distinct
+/// position below the size of the vector.
+#[derive(Clone, Debug)]
+pub struct Dense<F, V> {
+    index: F,
+    values: Vec<Option<V>>,
+}
+
+impl<K, V, F> Store<K, V> for Dense<F, V>
+where
+    F: Fn(&K) -> usize,
+{
+    fn lookup(&self, key: &K) -> Option<&V> {
+        self.values[(self.index)(key)].as_ref()
+    }
+
+    fn store(&mut self, key: K, value: V) {
+        self.values[(self.index)(&key)] = Some(value);
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} hits, {} misses", self.hits, self.misses)
+    }
+}
+
+/// Results of a memoized function together with statistics about how often
+/// a result could be reused.
+#[derive(Clone, Debug)]
+pub struct Memo<S> {
+    store: S,
+    stats: Stats,
+}
+
+impl<K: Hash + Eq, V> Memo<FnvHashMap<K, V>> {
+    pub fn new() -> Self {
+        Self::with_store(FnvHashMap::default())
+    }
+}
+
+impl<K: Hash + Eq, V> Default for Memo<FnvHashMap<K, V>> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F, V> Memo<Dense<F, V>> {
+    /// Memo for keys that `index` maps to distinct indices below `size`.
+    pub fn dense(size: usize, index: F) -> Self {
+        let values = (0..size).map(|_| None).collect();
+        Self::with_store(Dense { index, values })
+    }
+}
+
+impl<S> Memo<S> {
+    pub fn with_store(store: S) -> Self {
+        Self {
+            store,
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Evaluates `fun` at `key`. The function gets a callback for evaluating
+    /// itself at other keys, all of which are memoized as well.
+    pub fn call<K, V, F>(&mut self, key: K, fun: &F) -> V
+    where
+        S: Store<K, V>,
+        V: Clone,
+        F: Fn(&mut dyn FnMut(K) -> V, &K) -> V,
+    {
+        if let Some(value) = self.store.lookup(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+        let value = fun(&mut |k| self.call(k, fun), &key);
+        self.store.store(key, value.clone());
+        value
+    }
+}
+
+/// Evaluates the recursive function `fun` at `key`, see the module
+/// documentation.
+pub fn memoize<K, V, F>(key: K, fun: F) -> V
+where
+    K: Hash + Eq,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, &K) -> V,
+{
+    Memo::new().call(key, &fun)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binomial(recur: &mut dyn FnMut((u64, u64)) -> u64, &(n, k): &(u64, u64)) -> u64 {
+        if k == 0 || k == n {
+            1
+        } else {
+            recur((n - 1, k - 1)) + recur((n - 1, k))
+        }
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(memo.call((4, 2), &binomial), 6);
+        assert_eq!(memo.stats(), Stats { hits: 1, misses: 8 });
+        assert_eq!(memo.call((4, 2), &binomial), 6);
+        assert_eq!(memo.stats().to_string(), "2 hits, 8 misses");
+
+        let mut memo = Memo::new();
+        let mut dense = Memo::dense(61 * 61, |&(n, k): &(u64, u64)| (n * 61 + k) as usize);
+        assert_eq!(memo.call((60, 30), &binomial), 118264581564861424);
+        assert_eq!(dense.call((60, 30), &binomial), 118264581564861424);
+        assert_eq!(memo.stats(), dense.stats());
+    }
+
+    #[test]
+    fn test_borrowed_keys() {
+        // Number of ways to write a word as a concatenation of pieces.
+        let pieces = ["a", "ab", "b", "ba"];
+        let count = memoize("abab", |recur, &word: &&str| {
+            if word.is_empty() {
+                return 1u64;
+            }
+
+            pieces
+                .iter()
+                .filter_map(|p| word.strip_prefix(p))
+                .map(&mut *recur)
+                .sum()
+        });
+
+        assert_eq!(count, 5);
+    }
+}
@@ -0,0 +1,631 @@
+//! Small toolkit for parsing puzzle input. A `Cursor` walks over a single
+//! line, a `Block` holds consecutive lines and `Sections` splits the input
+//! into blocks at empty lines. Every failure reports the line and column
+//! where it happened, and the block if the input was split, all counted
+//! from one.
+
+use crate::grid::Grid;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub block: Option<usize>,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(block) = self.block {
+            write!(f, "block {}, ", block)?;
+        }
+
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// Position within a single line of input.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Cursor<'a> {
+    block: Option<usize>,
+    line: usize,
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// Cursor at the start of `text`, which is line `line` of the input.
+    pub fn new(line: usize, text: &'a str) -> Self {
+        Self {
+            block: None,
+            line,
+            text,
+            pos: 0,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.text[..self.pos].chars().count() + 1
+    }
+
+    /// The part of the line that has not been consumed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest().is_empty()
+    }
+
+    pub fn error(&self, message: impl Display) -> ParseError {
+        ParseError {
+            block: self.block,
+            line: self.line,
+            column: self.column(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    pub fn next_char(&mut self) -> ParseResult<char> {
+        let c = self
+            .peek()
+            .ok_or_else(|| self.error("unexpected end of line"))?;
+        self.pos += c.len_utf8();
+        Ok(c)
+    }
+
+    /// Consumes `tag` if the rest of the line starts with it.
+    pub fn eat(&mut self, tag: &str) -> bool {
+        let found = self.rest().starts_with(tag);
+        if found {
+            self.pos += tag.len();
+        }
+
+        found
+    }
+
+    pub fn tag(&mut self, tag: &str) -> ParseResult<()> {
+        if self.eat(tag) {
+            Ok(())
+        } else {
+            Err(self.error(format_args!("expected {:?}", tag)))
+        }
+    }
+
+    /// Consumes the first of the given tags that matches and returns the
+    /// value associated with it.
+    pub fn one_of<T: Clone>(&mut self, options: &[(&str, T)]) -> ParseResult<T> {
+        for (tag, value) in options {
+            if self.eat(tag) {
+                return Ok(value.clone());
+            }
+        }
+
+        let tags = options.iter().map(|(t, _)| format!("{:?}", t));
+        Err(self.error(format_args!(
+            "expected one of {}",
+            tags.collect::<Vec<_>>().join(", ")
+        )))
+    }
+
+    /// Consumes characters as long as `pred` holds. Might be empty.
+    pub fn take_while<F>(&mut self, mut pred: F) -> &'a str
+    where
+        F: FnMut(char) -> bool,
+    {
+        let rest = self.rest();
+        let len = rest.find(|c| !pred(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// One or more letters or digits.
+    pub fn word(&mut self) -> ParseResult<&'a str> {
+        let start = *self;
+        let word = self.take_while(char::is_alphanumeric);
+
+        if word.is_empty() {
+            return Err(start.error("expected a word"));
+        }
+
+        Ok(word)
+    }
+
+    /// Integer with an optional sign. Does not consume anything on failure.
+    pub fn int<T: FromStr>(&mut self) -> ParseResult<T> {
+        let start = *self;
+        let sign = self.take_while(|c| c == '-' || c == '+').len();
+        let digits = self.take_while(|c| c.is_ascii_digit());
+
+        if sign > 1 || digits.is_empty() {
+            *self = start;
+            return Err(self.error("expected an integer"));
+        }
+
+        let text = &start.text[start.pos..self.pos];
+        text.trim_start_matches('+').parse().map_err(|_| {
+            *self = start;
+
+            // Unsigned types cannot hold any negative number.
+            if text.starts_with('-') && "-1".parse::<T>().is_err() {
+                start.error("expected a non-negative integer")
+            } else {
+                start.error("integer out of range")
+            }
+        })
+    }
+
+    /// Every integer in the rest of the line, ignoring whatever is in
+    /// between. A minus sign right before the digits makes it negative.
+    pub fn ints<T: FromStr>(&mut self) -> ParseResult<Vec<T>> {
+        let mut result = vec![];
+
+        while !self.is_empty() {
+            if let Some(value) = self.attempt(Self::int) {
+                result.push(value);
+            } else {
+                self.next_char()?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// One or more items separated by `sep`.
+    pub fn list<T, F>(&mut self, sep: &str, mut item: F) -> ParseResult<Vec<T>>
+    where
+        F: FnMut(&mut Self) -> ParseResult<T>,
+    {
+        let mut result = vec![item(self)?];
+
+        while self.eat(sep) {
+            result.push(item(self)?);
+        }
+
+        Ok(result)
+    }
+
+    /// Entries of the form `key` `assign` `value`, separated by `sep`, such
+    /// as `x=1,m=2` or `ecl:gry pid:860033327`. Keys are words.
+    pub fn record<V, F>(
+        &mut self,
+        sep: &str,
+        assign: &str,
+        mut value: F,
+    ) -> ParseResult<Vec<(&'a str, V)>>
+    where
+        F: FnMut(&mut Self) -> ParseResult<V>,
+    {
+        self.list(sep, |c| {
+            let key = c.word()?;
+            c.tag(assign)?;
+            Ok((key, value(c)?))
+        })
+    }
+
+    /// Runs `parser` and rewinds the cursor if it fails.
+    pub fn attempt<T, F>(&mut self, parser: F) -> Option<T>
+    where
+        F: FnOnce(&mut Self) -> ParseResult<T>,
+    {
+        let start = *self;
+        let result = parser(self).ok();
+
+        if result.is_none() {
+            *self = start;
+        }
+
+        result
+    }
+
+    pub fn end(&self) -> ParseResult<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(format_args!("unexpected {:?}", self.rest())))
+        }
+    }
+}
+
+/// Runs `parser` on a whole line, which must be consumed completely.
+pub fn parse_line<'a, T, F>(line: usize, text: &'a str, parser: F) -> ParseResult<T>
+where
+    F: FnOnce(&mut Cursor<'a>) -> ParseResult<T>,
+{
+    let mut cursor = Cursor::new(line, text);
+    let result = parser(&mut cursor)?;
+    cursor.end()?;
+
+    Ok(result)
+}
+
+/// Consecutive lines of input together with the number of the first line
+/// and, for blocks that come from `Sections`, the number of the block.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Block<'a, S> {
+    pub block: Option<usize>,
+    pub first_line: usize,
+    pub lines: &'a [S],
+}
+
+// Implemented by hand since deriving would require `S: Copy`.
+impl<S> Clone for Block<'_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for Block<'_, S> {}
+
+impl<'a, S: AsRef<str>> Block<'a, S> {
+    /// Block holding the entire input.
+    pub fn new(lines: &'a [S]) -> Self {
+        Self {
+            block: None,
+            first_line: 1,
+            lines,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Cursor at the start of line `index` of the block.
+    pub fn cursor(&self, index: usize) -> Cursor<'a> {
+        Cursor {
+            block: self.block,
+            ..Cursor::new(self.first_line + index, self.lines[index].as_ref())
+        }
+    }
+
+    /// Error located at the start of line `index`, which may be one past
+    /// the last line.
+    pub fn error(&self, index: usize, message: impl Display) -> ParseError {
+        ParseError {
+            block: self.block,
+            line: self.first_line + index,
+            column: 1,
+            message: message.to_string(),
+        }
+    }
+
+    /// Checks that the first line is exactly `header` and returns the
+    /// remaining lines.
+    pub fn header(&self, header: &str) -> ParseResult<Self> {
+        match self.lines.first() {
+            Some(line) if line.as_ref() == header => Ok(self.slice(1, self.len())),
+            _ => Err(self.error(0, format_args!("expected header {:?}", header))),
+        }
+    }
+
+    /// Cursor at the first line and the remaining lines.
+    pub fn split_first(&self) -> ParseResult<(Cursor<'a>, Self)> {
+        if self.is_empty() {
+            return Err(self.error(0, "unexpected empty block"));
+        }
+
+        Ok((self.cursor(0), self.slice(1, self.len())))
+    }
+
+    /// Parses a block that consists of exactly one line.
+    pub fn parse_single<T, F>(&self, parser: F) -> ParseResult<T>
+    where
+        F: FnOnce(&mut Cursor<'a>) -> ParseResult<T>,
+    {
+        let (_, rest) = self.split_first()?;
+        if !rest.is_empty() {
+            return Err(rest.error(0, "expected a single line"));
+        }
+
+        self.parse_line(0, parser)
+    }
+
+    /// Parses line `index` with `parser`, which must consume it completely.
+    pub fn parse_line<T, F>(&self, index: usize, parser: F) -> ParseResult<T>
+    where
+        F: FnOnce(&mut Cursor<'a>) -> ParseResult<T>,
+    {
+        let mut cursor = self.cursor(index);
+        let result = parser(&mut cursor)?;
+        cursor.end()?;
+
+        Ok(result)
+    }
+
+    /// Parses every line with `parser`.
+    pub fn parse_lines<T, F>(&self, mut parser: F) -> ParseResult<Vec<T>>
+    where
+        F: FnMut(&mut Cursor<'a>) -> ParseResult<T>,
+    {
+        (0..self.len())
+            .map(|i| self.parse_line(i, &mut parser))
+            .collect()
+    }
+
+    /// Parses the lines as a grid of characters, where `fun` returns `None`
+    /// for invalid characters.
+    pub fn parse_grid<T, F>(&self, mut fun: F) -> ParseResult<Grid<T>>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let width = self.lines.first().map_or(0, |l| l.as_ref().chars().count());
+
+        let rows = self.parse_lines(|c| {
+            let mut row = vec![];
+
+            while let Some(ch) = c.peek() {
+                row.push(
+                    fun(ch).ok_or_else(|| c.error(format_args!("invalid character {:?}", ch)))?,
+                );
+                c.next_char()?;
+            }
+
+            if row.len() != width {
+                return Err(c.error(format_args!("expected {} columns", width)));
+            }
+
+            Ok(row)
+        })?;
+
+        Ok(Grid::from_rows(rows).unwrap())
+    }
+
+    /// Splits the block at empty lines.
+    pub fn split(&self) -> Vec<Block<'a, S>> {
+        let mut result = vec![];
+        let mut start = 0;
+
+        for (i, line) in self.lines.iter().enumerate() {
+            if line.as_ref().is_empty() {
+                result.push(self.slice(start, i));
+                start = i + 1;
+            }
+        }
+
+        if start < self.len() {
+            result.push(self.slice(start, self.len()));
+        }
+
+        result
+    }
+
+    fn slice(&self, start: usize, end: usize) -> Block<'a, S> {
+        Block {
+            block: self.block,
+            first_line: self.first_line + start,
+            lines: &self.lines[start..end],
+        }
+    }
+}
+
+/// Input that consists of blocks separated by empty lines, such as a list
+/// of rules followed by a list of messages. The blocks are numbered from
+/// one in error messages, but indexed from zero.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Sections<'a, S> {
+    blocks: Vec<Block<'a, S>>,
+    end_line: usize,
+}
+
+impl<'a, S: AsRef<str>> Sections<'a, S> {
+    pub fn new(lines: &'a [S]) -> Self {
+        let blocks = Block::new(lines)
+            .split()
+            .into_iter()
+            .enumerate()
+            .map(|(i, block)| Block {
+                block: Some(i + 1),
+                ..block
+            })
+            .collect();
+
+        Self {
+            blocks,
+            end_line: lines.len() + 1,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Block<'a, S>> + '_ {
+        self.blocks.iter().copied()
+    }
+
+    pub fn get(&self, index: usize) -> ParseResult<Block<'a, S>> {
+        self.blocks.get(index).copied().ok_or_else(|| ParseError {
+            block: Some(index + 1),
+            line: self.end_line,
+            column: 1,
+            message: "missing block".to_string(),
+        })
+    }
+
+    /// Exactly `N` blocks.
+    pub fn blocks<const N: usize>(&self) -> ParseResult<[Block<'a, S>; N]> {
+        if let Some(extra) = self.blocks.get(N) {
+            return Err(extra.error(0, format_args!("expected {} blocks", N)));
+        }
+
+        let mut result = [Block::new(&[]); N];
+        for (i, block) in result.iter_mut().enumerate() {
+            *block = self.get(i)?;
+        }
+
+        Ok(result)
+    }
+
+    /// The block whose first line is `header`, without that line.
+    pub fn named(&self, header: &str) -> ParseResult<Block<'a, S>> {
+        self.iter()
+            .find_map(|block| block.header(header).ok())
+            .ok_or_else(|| ParseError {
+                block: None,
+                line: self.end_line,
+                column: 1,
+                message: format!("missing block {:?}", header),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor() {
+        let mut c = Cursor::new(
+            3,
+            "Valve AA has flow rate=-13; tunnels lead to valves DD, II",
+        );
+        c.tag("Valve ").unwrap();
+        assert_eq!(c.word(), Ok("AA"));
+        c.tag(" has flow rate=").unwrap();
+        assert_eq!(c.int::<i64>(), Ok(-13));
+        c.one_of(&[
+            ("; tunnel leads to valve ", ()),
+            ("; tunnels lead to valves ", ()),
+        ])
+        .unwrap();
+        assert_eq!(c.list(", ", Cursor::word), Ok(vec!["DD", "II"]));
+        assert!(c.end().is_ok());
+
+        let mut c = Cursor::new(1, "{x=787,m=-2,a=1}");
+        c.tag("{").unwrap();
+        let record = c.record(",", "=", Cursor::int::<i32>).unwrap();
+        assert_eq!(record, [("x", 787), ("m", -2), ("a", 1)]);
+        assert!(c.tag("}").is_ok());
+
+        let mut c = Cursor::new(1, "target area: x=20..-30, y=-10..+5");
+        assert_eq!(c.ints::<i32>(), Ok(vec![20, -30, -10, 5]));
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = parse_line(7, "mem[8] = 11", |c| {
+            c.tag("mem[")?;
+            c.int::<u8>()
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "line 7, column 6: unexpected \"] = 11\"");
+
+        let mut c = Cursor::new(2, "x=300");
+        c.tag("x=").unwrap();
+        assert_eq!(c.int::<u8>().unwrap_err().column, 3);
+        assert_eq!(c.int::<i32>(), Ok(300));
+
+        let mut c = Cursor::new(2, "-3");
+        let err = c.int::<u32>().unwrap_err();
+        assert_eq!(err.message, "expected a non-negative integer");
+        assert_eq!(c.int::<i8>(), Ok(-3));
+
+        let mut c = Cursor::new(2, "--5");
+        assert_eq!(c.int::<i32>().unwrap_err().message, "expected an integer");
+        assert_eq!(c.rest(), "--5");
+        assert_eq!(c.attempt(|c| c.tag("-+")), None);
+        assert_eq!(c.rest(), "--5");
+
+        let err = Cursor::new(1, "a")
+            .one_of(&[("n", 0), ("s", 1)])
+            .unwrap_err();
+        assert_eq!(err.message, "expected one of \"n\", \"s\"");
+    }
+
+    #[test]
+    fn test_block() {
+        let lines = ["#.#", "..#", "", "1,2", "", "", "3"];
+        let blocks = Block::new(&lines).split();
+
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(
+            blocks.iter().map(|b| b.first_line).collect::<Vec<_>>(),
+            [1, 4, 6, 7]
+        );
+
+        let grid = blocks[0].parse_grid(|c| Some(c == '#')).unwrap();
+        assert_eq!(grid.dim(), [3, 2]);
+        assert!(grid[[2, 1]]);
+
+        let numbers = blocks[1]
+            .parse_lines(|c| c.list(",", Cursor::int::<i32>))
+            .unwrap();
+        assert_eq!(numbers, [vec![1, 2]]);
+        assert!(blocks[2].is_empty());
+
+        let grid = blocks[3].parse_grid(|c| Some(c == '#')).unwrap();
+        assert_eq!(grid.dim(), [1, 1]);
+
+        let ragged = ["##", "#"];
+        let err = Block::new(&ragged).parse_grid(|_| Some(())).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+
+        let err = Block::new(&lines[..2])
+            .parse_grid(|c| (c == '#').then_some(1))
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (1, 2));
+    }
+
+    #[test]
+    fn test_sections() {
+        let lines = ["rules:", "0: 1 2", "", "messages:", "ab", "ba", "", "end"];
+        let sections = Sections::new(&lines);
+        assert_eq!(sections.len(), 3);
+
+        let [rules, messages, end] = sections.blocks().unwrap();
+        let rules = rules.header("rules:").unwrap();
+        assert_eq!(rules.first_line, 2);
+        assert_eq!(rules.parse_single(|c| c.ints::<u8>()), Ok(vec![0, 1, 2]));
+        assert_eq!(end.parse_single(Cursor::word), Ok("end"));
+
+        let err = messages.header("rules:").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "block 2, line 4, column 1: expected header \"rules:\""
+        );
+        let err = messages.parse_single(Cursor::word).unwrap_err();
+        assert_eq!((err.block, err.line), (Some(2), 5));
+        let err = messages
+            .header("messages:")
+            .and_then(|b| b.parse_lines(|c| c.tag("ab")))
+            .unwrap_err();
+        assert_eq!((err.block, err.line, err.column), (Some(2), 6, 1));
+
+        let messages = sections.named("messages:").unwrap();
+        assert_eq!(messages.lines, ["ab", "ba"]);
+        assert_eq!(sections.named("nothing").unwrap_err().line, 9);
+
+        let err = sections.blocks::<2>().unwrap_err();
+        assert_eq!((err.block, err.line), (Some(3), 8));
+        let err = sections.blocks::<4>().unwrap_err();
+        assert_eq!(err.to_string(), "block 4, line 9, column 1: missing block");
+    }
+}
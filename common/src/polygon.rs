@@ -0,0 +1,207 @@
+//! Simple polygons whose vertices lie on the integer lattice.
+
+use crate::math::gcd;
+use crate::sparse::Point;
+
+/// Closed polygon given by its vertices in order. The edge from the last
+/// vertex back to the first one is implicit.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+fn cross(a: Point, b: Point, p: Point) -> i64 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+impl Polygon {
+    /// Creates a polygon from its vertices. A final vertex that repeats the
+    /// first one is dropped.
+    pub fn new(mut vertices: Vec<Point>) -> Self {
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+
+        Self { vertices }
+    }
+
+    /// Traces a polygon by walking from `start`. Every step is given as a
+    /// unit vector and the number of times to move along it.
+    pub fn from_steps<I>(start: Point, steps: I) -> Self
+    where
+        I: IntoIterator<Item = ([i64; 2], i64)>,
+    {
+        let mut vertices = vec![start];
+        let [mut x, mut y] = start;
+
+        for ([dx, dy], length) in steps {
+            x += dx * length;
+            y += dy * length;
+            vertices.push([x, y]);
+        }
+
+        Self::new(vertices)
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// All edges as pairs of consecutive vertices, including the closing
+    /// edge.
+    pub fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// Twice the signed area according to the shoelace formula. Positive if
+    /// the vertices go counterclockwise in a coordinate system where `y`
+    /// points up, which is clockwise when `y` points down.
+    pub fn signed_double_area(&self) -> i64 {
+        self.edges().map(|(p, q)| p[0] * q[1] - q[0] * p[1]).sum()
+    }
+
+    /// The enclosed area, which is a multiple of one half.
+    pub fn area(&self) -> f64 {
+        self.signed_double_area().abs() as f64 / 2.0
+    }
+
+    /// Number of lattice points on the edges.
+    pub fn boundary_points(&self) -> i64 {
+        self.edges()
+            .map(|(p, q)| gcd(q[0] - p[0], q[1] - p[1]))
+            .sum()
+    }
+
+    /// Number of lattice points strictly inside, using Pick's theorem.
+    pub fn interior_points(&self) -> i64 {
+        (self.signed_double_area().abs() - self.boundary_points() + 2) / 2
+    }
+
+    /// Number of lattice points inside or on the boundary.
+    pub fn lattice_points(&self) -> i64 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    pub fn on_boundary(&self, p: Point) -> bool {
+        self.edges().any(|(a, b)| {
+            cross(a, b, p) == 0
+                && (a[0].min(b[0])..=a[0].max(b[0])).contains(&p[0])
+                && (a[1].min(b[1])..=a[1].max(b[1])).contains(&p[1])
+        })
+    }
+
+    /// Number of times the boundary winds around `p`, with the same sign as
+    /// the signed area. Only meaningful for points not on the boundary.
+    pub fn winding_number(&self, p: Point) -> i64 {
+        let mut winding = 0;
+
+        for (a, b) in self.edges() {
+            if a[1] <= p[1] {
+                if b[1] > p[1] && cross(a, b, p) > 0 {
+                    winding += 1;
+                }
+            } else if b[1] <= p[1] && cross(a, b, p) < 0 {
+                winding -= 1;
+            }
+        }
+
+        winding
+    }
+
+    /// Returns `true` if `p` is strictly inside the polygon.
+    pub fn contains(&self, p: Point) -> bool {
+        !self.on_boundary(p) && self.winding_number(p) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sparse::Bounds;
+
+    fn brute_force(polygon: &Polygon) -> (i64, i64) {
+        let bounds = Bounds::from_points(polygon.vertices().iter().copied()).unwrap();
+        let interior = bounds.points().filter(|&p| polygon.contains(p)).count();
+        let boundary = bounds.points().filter(|&p| polygon.on_boundary(p)).count();
+        (interior as i64, boundary as i64)
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let rect = Polygon::new(vec![[0, 0], [4, 0], [4, 3], [0, 3], [0, 0]]);
+        assert_eq!(rect.vertices().len(), 4);
+        assert_eq!(rect.signed_double_area(), 24);
+        assert_eq!(rect.area(), 12.0);
+        assert_eq!(rect.boundary_points(), 14);
+        assert_eq!(rect.interior_points(), 6);
+        assert_eq!(rect.lattice_points(), 20);
+
+        assert!(rect.contains([1, 1]));
+        assert!(!rect.contains([4, 1]));
+        assert!(rect.on_boundary([4, 1]));
+        assert!(!rect.contains([5, 1]));
+        assert_eq!(rect.winding_number([2, 2]), 1);
+
+        let reversed = Polygon::new(rect.vertices().iter().rev().copied().collect());
+        assert_eq!(reversed.signed_double_area(), -24);
+        assert_eq!(reversed.winding_number([2, 2]), -1);
+        assert_eq!(reversed.interior_points(), 6);
+    }
+
+    #[test]
+    fn test_general() {
+        let polygons = [
+            vec![[0, 0], [5, 1], [2, 4]],
+            vec![[0, 0], [6, 0], [6, 6], [3, 2], [0, 6]],
+            vec![[-3, -1], [4, -2], [7, 5], [1, 3], [-2, 8]],
+        ];
+
+        for vertices in polygons {
+            let polygon = Polygon::new(vertices);
+            assert_eq!(
+                (polygon.interior_points(), polygon.boundary_points()),
+                brute_force(&polygon)
+            );
+        }
+    }
+
+    #[test]
+    fn test_steps() {
+        // The dig plan from 2023 day 18, which encloses 62 cubic meters
+        let plan = [
+            ('R', 6),
+            ('D', 5),
+            ('L', 2),
+            ('D', 2),
+            ('R', 2),
+            ('D', 2),
+            ('L', 5),
+            ('U', 2),
+            ('L', 1),
+            ('U', 2),
+            ('R', 2),
+            ('U', 3),
+            ('L', 2),
+            ('U', 2),
+        ];
+
+        let polygon = Polygon::from_steps(
+            [0, 0],
+            plan.iter().map(|&(d, n)| {
+                let dir = match d {
+                    'R' => [1, 0],
+                    'L' => [-1, 0],
+                    'D' => [0, 1],
+                    _ => [0, -1],
+                };
+                (dir, n)
+            }),
+        );
+
+        assert_eq!(polygon.vertices().len(), 14);
+        assert_eq!(polygon.boundary_points(), 38);
+        assert_eq!(polygon.lattice_points(), 62);
+        assert_eq!(brute_force(&polygon), (24, 38));
+    }
+}
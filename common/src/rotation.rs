@@ -0,0 +1,350 @@
+//! The 24 rotations of a cube and rigid alignment of 3D point clouds.
+
+use fnv::FnvHashMap;
+use std::collections::VecDeque;
+
+/// Point or vector in three dimensions.
+pub type Vec3 = [i64; 3];
+
+fn dot(a: Vec3, b: Vec3) -> i64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+/// Rotation that maps the coordinate axes onto each other, stored as a
+/// matrix whose rows are signed unit vectors.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Rotation {
+    rows: [Vec3; 3],
+}
+
+impl Rotation {
+    pub const IDENTITY: Self = Self {
+        rows: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    };
+
+    /// All 24 rotations, starting with the identity.
+    pub fn all() -> Vec<Self> {
+        const UNITS: [Vec3; 6] = [
+            [1, 0, 0],
+            [-1, 0, 0],
+            [0, 1, 0],
+            [0, -1, 0],
+            [0, 0, 1],
+            [0, 0, -1],
+        ];
+
+        let mut result = vec![];
+
+        for x in UNITS {
+            for y in UNITS {
+                if dot(x, y) == 0 {
+                    result.push(Self {
+                        rows: [x, y, cross(x, y)],
+                    });
+                }
+            }
+        }
+
+        result
+    }
+
+    pub fn matrix(&self) -> [Vec3; 3] {
+        self.rows
+    }
+
+    pub fn apply(&self, v: Vec3) -> Vec3 {
+        self.rows.map(|row| dot(row, v))
+    }
+
+    /// The rotation that applies `other` first and then `self`.
+    pub fn compose(&self, other: &Self) -> Self {
+        let columns = other.transpose().rows;
+        Self {
+            rows: self.rows.map(|row| columns.map(|col| dot(row, col))),
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        self.transpose()
+    }
+
+    fn transpose(&self) -> Self {
+        let m = self.rows;
+        Self {
+            rows: [0, 1, 2].map(|i| [m[0][i], m[1][i], m[2][i]]),
+        }
+    }
+}
+
+/// Rotation followed by a translation.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Transform {
+    pub rotation: Rotation,
+    pub translation: Vec3,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        rotation: Rotation::IDENTITY,
+        translation: [0, 0, 0],
+    };
+
+    pub fn new(rotation: Rotation, translation: Vec3) -> Self {
+        Self {
+            rotation,
+            translation,
+        }
+    }
+
+    pub fn apply(&self, v: Vec3) -> Vec3 {
+        add(self.rotation.apply(v), self.translation)
+    }
+
+    /// The transform that applies `other` first and then `self`.
+    pub fn compose(&self, other: &Self) -> Self {
+        Self {
+            rotation: self.rotation.compose(&other.rotation),
+            translation: self.apply(other.translation),
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        let rotation = self.rotation.inverse();
+        let [x, y, z] = rotation.apply(self.translation);
+        Self::new(rotation, [-x, -y, -z])
+    }
+}
+
+/// Sorted squared distances between all pairs of points in a cloud. These
+/// do not change under rotation and translation, so two clouds that share
+/// `k` points share at least `k * (k - 1) / 2` distances.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Fingerprint {
+    distances: Vec<i64>,
+}
+
+impl Fingerprint {
+    pub fn new(points: &[Vec3]) -> Self {
+        let mut distances = vec![];
+
+        for (i, &p) in points.iter().enumerate() {
+            for &q in &points[i + 1..] {
+                let d = sub(p, q);
+                distances.push(dot(d, d));
+            }
+        }
+
+        distances.sort_unstable();
+        Self { distances }
+    }
+
+    /// Size of the intersection of both distance multisets.
+    pub fn shared(&self, other: &Self) -> usize {
+        let (a, b) = (&self.distances, &other.distances);
+        let (mut i, mut j, mut count) = (0, 0, 0);
+
+        while i < a.len() && j < b.len() {
+            if a[i] < b[j] {
+                i += 1;
+            } else if a[i] > b[j] {
+                j += 1;
+            } else {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Returns `true` if the clouds could have at least `min_overlap` points
+    /// in common.
+    pub fn may_overlap(&self, other: &Self, min_overlap: usize) -> bool {
+        self.shared(other) >= min_overlap * min_overlap.saturating_sub(1) / 2
+    }
+}
+
+/// Finds a transform that maps at least `min_overlap` points of `b` onto
+/// points of `a`, by trying every rotation and counting how often each
+/// translation occurs.
+pub fn align(a: &[Vec3], b: &[Vec3], min_overlap: usize) -> Option<Transform> {
+    let mut counts = FnvHashMap::default();
+
+    for rotation in Rotation::all() {
+        counts.clear();
+
+        for &q in b {
+            let q = rotation.apply(q);
+
+            for &p in a {
+                let count = counts.entry(sub(p, q)).or_insert(0);
+                *count += 1;
+
+                if *count >= min_overlap {
+                    return Some(Transform::new(rotation, sub(p, q)));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Edge in the spanning tree built by `align_all`: `transform` maps the
+/// points of cloud `child` into the frame of cloud `parent`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Link {
+    pub parent: usize,
+    pub child: usize,
+    pub transform: Transform,
+}
+
+/// Result of aligning a collection of point clouds to the first one.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Alignment {
+    /// Edges of the spanning tree in the order they were discovered.
+    pub links: Vec<Link>,
+    /// For every cloud, the transform into the frame of the first cloud, or
+    /// `None` if it does not overlap with any aligned cloud.
+    pub transforms: Vec<Option<Transform>>,
+}
+
+/// Aligns all clouds to the first one by growing a spanning tree outward
+/// from it. Pairs of clouds are only compared point by point if their
+/// fingerprints indicate enough overlap.
+pub fn align_all(clouds: &[Vec<Vec3>], min_overlap: usize) -> Alignment {
+    let fingerprints: Vec<_> = clouds.iter().map(|c| Fingerprint::new(c)).collect();
+    let mut transforms = vec![None; clouds.len()];
+    let mut links = vec![];
+    let mut queue = VecDeque::new();
+
+    if !clouds.is_empty() {
+        transforms[0] = Some(Transform::IDENTITY);
+        queue.push_back(0);
+    }
+
+    while let Some(parent) = queue.pop_front() {
+        let base = transforms[parent].unwrap();
+
+        for child in 0..clouds.len() {
+            if transforms[child].is_some()
+                || !fingerprints[parent].may_overlap(&fingerprints[child], min_overlap)
+            {
+                continue;
+            }
+
+            if let Some(transform) = align(&clouds[parent], &clouds[child], min_overlap) {
+                transforms[child] = Some(base.compose(&transform));
+                links.push(Link {
+                    parent,
+                    child,
+                    transform,
+                });
+                queue.push_back(child);
+            }
+        }
+    }
+
+    Alignment { links, transforms }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_rotations() {
+        let all = Rotation::all();
+        assert_eq!(all.len(), 24);
+        assert_eq!(all[0], Rotation::IDENTITY);
+
+        // Closed under composition and inverse, and every rotation maps a
+        // generic vector somewhere different.
+        for a in &all {
+            assert!(all.contains(&a.inverse()));
+            assert_eq!(a.compose(&a.inverse()), Rotation::IDENTITY);
+
+            for b in &all {
+                let c = a.compose(b);
+                assert!(all.contains(&c));
+                assert_eq!(c.apply([1, 2, 3]), a.apply(b.apply([1, 2, 3])));
+            }
+        }
+
+        let images: Vec<_> = all.iter().map(|r| r.apply([1, 2, 3])).collect();
+        assert!(images
+            .iter()
+            .all(|v| images.iter().filter(|&w| w == v).count() == 1));
+    }
+
+    #[test]
+    fn test_transform() {
+        let all = Rotation::all();
+        let a = Transform::new(all[5], [1, -2, 3]);
+        let b = Transform::new(all[17], [-4, 0, 7]);
+        let p = [5, 6, -7];
+
+        assert_eq!(a.compose(&b).apply(p), a.apply(b.apply(p)));
+        assert_eq!(a.inverse().apply(a.apply(p)), p);
+        assert_eq!(a.compose(&a.inverse()), Transform::IDENTITY);
+    }
+
+    #[test]
+    fn test_align() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let all = Rotation::all();
+        let world: Vec<Vec3> = (0..60)
+            .map(|_| [(); 3].map(|_| rng.gen_range(-1000..=1000)))
+            .collect();
+
+        // Every cloud sees an overlapping window of the world in its own
+        // frame. Cloud 3 shares nothing with the others.
+        let windows = [0..20, 8..28, 16..36, 40..52];
+        let truth: Vec<_> = (0..windows.len())
+            .map(|i| {
+                let translation = [(); 3].map(|_| rng.gen_range(-500..=500));
+                Transform::new(all[(i * 7) % 24], translation)
+            })
+            .collect();
+
+        let clouds: Vec<Vec<Vec3>> = windows
+            .iter()
+            .zip(&truth)
+            .map(|(w, t)| {
+                let inverse = t.inverse();
+                world[w.clone()].iter().map(|&p| inverse.apply(p)).collect()
+            })
+            .collect();
+
+        let fingerprints: Vec<_> = clouds.iter().map(|c| Fingerprint::new(c)).collect();
+        assert!(fingerprints[0].may_overlap(&fingerprints[1], 12));
+        assert!(!fingerprints[0].may_overlap(&fingerprints[2], 12));
+
+        let alignment = align_all(&clouds, 12);
+        assert_eq!(alignment.links.len(), 2);
+        assert_eq!(alignment.transforms[3], None);
+
+        for i in 0..3 {
+            let expected = truth[0].inverse().compose(&truth[i]);
+            assert_eq!(alignment.transforms[i], Some(expected));
+        }
+    }
+}
@@ -0,0 +1,447 @@
+//! Shortest-path searches over implicit graphs.
+//!
+//! Every search takes one or more start states, a successor function and a
+//! goal predicate. The search stops as soon as a goal state is reached; pass
+//! `|_| false` to explore everything that is reachable. The result records
+//! the distance and predecessor of each state that was discovered, which
+//! can be used to reconstruct paths. The `_distance` variants skip the
+//! predecessors and only return the distance to the goal, which is faster
+//! for searches over many states.
+
+use fnv::FnvHashMap;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Outcome of a search.
+#[derive(Clone, Debug)]
+pub struct Paths<S, C> {
+    dists: FnvHashMap<S, C>,
+    preds: FnvHashMap<S, S>,
+    track_preds: bool,
+    goal: Option<S>,
+}
+
+impl<S, C> Paths<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy,
+{
+    fn new(track_preds: bool) -> Self {
+        Self {
+            dists: FnvHashMap::default(),
+            preds: FnvHashMap::default(),
+            track_preds,
+            goal: None,
+        }
+    }
+
+    /// Records that `state` can be reached at `dist` via `pred`, unless a
+    /// path that is at least as short is already known.
+    fn relax(&mut self, state: &S, dist: C, pred: &S) -> bool
+    where
+        C: Ord,
+    {
+        if self.dists.get(state).is_some_and(|&d| d <= dist) {
+            return false;
+        }
+
+        self.dists.insert(state.clone(), dist);
+
+        if self.track_preds {
+            self.preds.insert(state.clone(), pred.clone());
+        }
+
+        true
+    }
+
+    /// The goal state that stopped the search, if any.
+    pub fn goal(&self) -> Option<&S> {
+        self.goal.as_ref()
+    }
+
+    /// Distance from the nearest start to the goal state, if one was found.
+    pub fn goal_distance(&self) -> Option<C> {
+        self.distance(self.goal.as_ref()?)
+    }
+
+    /// Distance from the nearest start to `state`. If the search stopped
+    /// early at a goal, this is only an upper bound for states that are
+    /// further away than the goal.
+    pub fn distance(&self, state: &S) -> Option<C> {
+        self.dists.get(state).copied()
+    }
+
+    /// Distances of all discovered states, in arbitrary order.
+    pub fn distances(&self) -> impl Iterator<Item = (&S, C)> {
+        self.dists.iter().map(|(s, &d)| (s, d))
+    }
+
+    /// The state preceding `state` on its shortest path. Returns `None` for
+    /// start states and states that were not discovered.
+    pub fn predecessor(&self, state: &S) -> Option<&S> {
+        self.preds.get(state)
+    }
+
+    /// Shortest path from a start to `state`, including both endpoints.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.dists.contains_key(state) {
+            return None;
+        }
+
+        let mut path = vec![state.clone()];
+        while let Some(prev) = self.preds.get(path.last().unwrap()) {
+            path.push(prev.clone());
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    /// Shortest path from a start to the goal state, if one was found.
+    pub fn goal_path(&self) -> Option<Vec<S>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+}
+
+/// Breadth-first search where every step costs one.
+pub fn bfs<S, I, F, G>(
+    starts: impl IntoIterator<Item = S>,
+    successors: F,
+    is_goal: G,
+) -> Paths<S, usize>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    bfs_with(starts, successors, is_goal, true)
+}
+
+/// Distance from the nearest start to a goal, using `bfs`.
+pub fn bfs_distance<S, I, F, G>(
+    starts: impl IntoIterator<Item = S>,
+    successors: F,
+    is_goal: G,
+) -> Option<usize>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    bfs_with(starts, successors, is_goal, false).goal_distance()
+}
+
+fn bfs_with<S, I, F, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: F,
+    mut is_goal: G,
+    track_preds: bool,
+) -> Paths<S, usize>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    let mut paths = Paths::new(track_preds);
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if !paths.dists.contains_key(&start) {
+            paths.dists.insert(start.clone(), 0);
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((state, dist)) = queue.pop_front() {
+        if is_goal(&state) {
+            paths.goal = Some(state);
+            break;
+        }
+
+        for next in successors(&state) {
+            if paths.relax(&next, dist + 1, &state) {
+                queue.push_back((next, dist + 1));
+            }
+        }
+    }
+
+    paths
+}
+
+/// Breadth-first search where every step costs either zero or one.
+pub fn bfs01<S, I, F, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: F,
+    mut is_goal: G,
+) -> Paths<S, usize>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = (S, usize)>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    let mut paths = Paths::new(true);
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        paths.dists.insert(start.clone(), 0);
+        queue.push_back((start, 0));
+    }
+
+    while let Some((state, dist)) = queue.pop_front() {
+        if paths.dists[&state] < dist {
+            continue;
+        }
+
+        if is_goal(&state) {
+            paths.goal = Some(state);
+            break;
+        }
+
+        for (next, cost) in successors(&state) {
+            assert!(cost <= 1, "step cost must be zero or one, got {}", cost);
+            let next_dist = dist + cost;
+
+            if paths.relax(&next, next_dist, &state) {
+                if cost == 0 {
+                    queue.push_front((next, next_dist));
+                } else {
+                    queue.push_back((next, next_dist));
+                }
+            }
+        }
+    }
+
+    paths
+}
+
+/// Dijkstra's algorithm for non-negative step costs.
+pub fn dijkstra<S, C, I, F, G>(
+    starts: impl IntoIterator<Item = S>,
+    successors: F,
+    is_goal: G,
+) -> Paths<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    astar(starts, successors, |_| C::default(), is_goal)
+}
+
+/// Distance from the nearest start to a goal, using `dijkstra`.
+pub fn dijkstra_distance<S, C, I, F, G>(
+    starts: impl IntoIterator<Item = S>,
+    successors: F,
+    is_goal: G,
+) -> Option<C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    astar_with(starts, successors, |_| C::default(), is_goal, false).goal_distance()
+}
+
+/// A* search guided by `heuristic`, which must never overestimate the
+/// remaining cost to a goal and must be consistent for the result to be
+/// optimal.
+pub fn astar<S, C, I, F, H, G>(
+    starts: impl IntoIterator<Item = S>,
+    successors: F,
+    heuristic: H,
+    is_goal: G,
+) -> Paths<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    astar_with(starts, successors, heuristic, is_goal, true)
+}
+
+fn astar_with<S, C, I, F, H, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: F,
+    mut heuristic: H,
+    mut is_goal: G,
+    track_preds: bool,
+) -> Paths<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let mut paths = Paths::new(track_preds);
+    let mut queue = BinaryHeap::new();
+
+    for start in starts {
+        let estimate = heuristic(&start);
+        paths.dists.insert(start.clone(), C::default());
+        queue.push(Reverse(Entry {
+            estimate,
+            dist: C::default(),
+            state: start,
+        }));
+    }
+
+    while let Some(Reverse(Entry { dist, state, .. })) = queue.pop() {
+        if paths.dists[&state] < dist {
+            continue;
+        }
+
+        if is_goal(&state) {
+            paths.goal = Some(state);
+            break;
+        }
+
+        for (next, cost) in successors(&state) {
+            let next_dist = dist + cost;
+
+            if paths.relax(&next, next_dist, &state) {
+                queue.push(Reverse(Entry {
+                    estimate: next_dist + heuristic(&next),
+                    dist: next_dist,
+                    state: next,
+                }));
+            }
+        }
+    }
+
+    paths
+}
+
+/// Priority queue entry that is ordered by its estimated total cost only.
+struct Entry<S, C> {
+    estimate: C,
+    dist: C,
+    state: S,
+}
+
+impl<S, C: Ord> PartialEq for Entry<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S, C: Ord> Eq for Entry<S, C> {}
+
+impl<S, C: Ord> PartialOrd for Entry<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> Ord for Entry<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.estimate.cmp(&other.estimate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Grid, Pos};
+
+    fn maze() -> Grid<char> {
+        Grid::parse(&[
+            "S..#....", //
+            ".#.#.##.", ".#...#..", ".####.#.", "......#E",
+        ])
+        .unwrap()
+    }
+
+    fn open_neighbors(grid: &Grid<char>, pos: Pos) -> Vec<Pos> {
+        grid.neighbors4(pos).filter(|&p| grid[p] != '#').collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = maze();
+        let start = grid.find(|&c| c == 'S').unwrap();
+        let end = grid.find(|&c| c == 'E').unwrap();
+
+        let paths = bfs([start], |&p| open_neighbors(&grid, p), |&p| p == end);
+        assert_eq!(paths.goal(), Some(&end));
+        assert_eq!(paths.goal_distance(), Some(15));
+        assert_eq!(
+            bfs_distance([start], |&p| open_neighbors(&grid, p), |&p| p == end),
+            Some(15)
+        );
+
+        let path = paths.goal_path().unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!(path[0], start);
+        assert!(path
+            .windows(2)
+            .all(|w| open_neighbors(&grid, w[0]).contains(&w[1])));
+
+        let all = bfs([start], |&p| open_neighbors(&grid, p), |_| false);
+        assert_eq!(all.goal(), None);
+        assert_eq!(all.distance(&[4, 0]), Some(8));
+        assert_eq!(
+            all.distances().count(),
+            grid.iter().filter(|&&c| c != '#').count()
+        );
+        assert_eq!(all.predecessor(&start), None);
+    }
+
+    #[test]
+    fn test_weighted() {
+        // Moving into a cell costs its digit, which makes the direct route
+        // along the top more expensive than the detour below it.
+        let grid = Grid::parse(&["1911", "1119"])
+            .unwrap()
+            .map(|c| c.to_digit(10).unwrap());
+        let successors = |&p: &Pos| grid.neighbors4(p).map(|q| (q, grid[q])).collect::<Vec<_>>();
+        let end = [3, 0];
+
+        let a = dijkstra([[0, 0]], successors, |&p| p == end);
+        assert_eq!(a.goal_distance(), Some(5));
+        assert_eq!(
+            dijkstra_distance([[0, 0]], successors, |&p| p == end),
+            Some(5)
+        );
+        assert_eq!(dijkstra_distance([[0, 0]], successors, |_| false), None);
+        assert_eq!(
+            a.goal_path().unwrap(),
+            [[0, 0], [0, 1], [1, 1], [2, 1], [2, 0], [3, 0]]
+        );
+
+        let manhattan = |&[x, y]: &Pos| (x.abs_diff(end[0]) + y.abs_diff(end[1])) as u32;
+        let b = astar([[0, 0]], successors, manhattan, |&p| p == end);
+        assert_eq!(b.goal_distance(), Some(5));
+    }
+
+    #[test]
+    fn test_bfs01() {
+        // Doubling is free, incrementing or decrementing costs one. States
+        // are limited to a small range to keep the graph finite.
+        let successors = |&x: &i32| {
+            [(x + 1, 1), (x - 1, 1), (x * 2, 0)]
+                .into_iter()
+                .filter(|&(y, _)| y.abs() <= 64)
+        };
+        let paths = bfs01([0], successors, |&x| x == -3);
+        assert_eq!(paths.goal_distance(), Some(2));
+
+        let paths = bfs01([1], successors, |&x| x == 20);
+        assert_eq!(paths.goal_distance(), Some(1));
+        assert_eq!(paths.goal_path().unwrap(), [1, 2, 4, 5, 10, 20]);
+    }
+}
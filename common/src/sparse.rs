@@ -0,0 +1,245 @@
+use fnv::FnvHashMap;
+use std::fmt::{self, Display};
+
+/// Signed position `[x, y]` on an unbounded plane.
+pub type Point = [i64; 2];
+
+/// Inclusive axis-aligned bounding box of a set of points.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    /// Smallest box containing all the given points, or `None` if there
+    /// are no points.
+    pub fn from_points<I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = Point>,
+    {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Self::new(first, first);
+
+        for p in points {
+            bounds.include(p);
+        }
+
+        Some(bounds)
+    }
+
+    /// Grows the box such that it contains `p`.
+    pub fn include(&mut self, [x, y]: Point) {
+        self.min = [self.min[0].min(x), self.min[1].min(y)];
+        self.max = [self.max[0].max(x), self.max[1].max(y)];
+    }
+
+    /// Grows the box by `n` cells in every direction.
+    pub fn expand(&self, n: i64) -> Self {
+        Self::new(
+            [self.min[0] - n, self.min[1] - n],
+            [self.max[0] + n, self.max[1] + n],
+        )
+    }
+
+    pub fn contains(&self, [x, y]: Point) -> bool {
+        (self.min[0]..=self.max[0]).contains(&x) && (self.min[1]..=self.max[1]).contains(&y)
+    }
+
+    pub fn width(&self) -> i64 {
+        self.max[0] - self.min[0] + 1
+    }
+
+    pub fn height(&self) -> i64 {
+        self.max[1] - self.min[1] + 1
+    }
+
+    pub fn area(&self) -> i64 {
+        self.width() * self.height()
+    }
+
+    /// All points inside the box in row-major order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let [x0, y0] = self.min;
+        let [x1, y1] = self.max;
+        (y0..=y1).flat_map(move |y| (x0..=x1).map(move |x| [x, y]))
+    }
+
+    /// Renders the box as text, one line per row, by calling `fun` for
+    /// every point.
+    pub fn render<F>(&self, mut fun: F) -> String
+    where
+        F: FnMut(Point) -> char,
+    {
+        let mut out = String::new();
+
+        for y in self.min[1]..=self.max[1] {
+            if y > self.min[1] {
+                out.push('\n');
+            }
+
+            for x in self.min[0]..=self.max[0] {
+                out.push(fun([x, y]));
+            }
+        }
+
+        out
+    }
+}
+
+/// Grid without bounds that only stores the cells differing from a
+/// background value. Every position that has not been set explicitly holds
+/// the background.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SparseGrid<T> {
+    cells: FnvHashMap<Point, T>,
+    background: T,
+}
+
+impl<T: PartialEq> SparseGrid<T> {
+    pub fn new(background: T) -> Self {
+        Self {
+            cells: FnvHashMap::default(),
+            background,
+        }
+    }
+
+    /// Parses a grid from lines of text, where `(x, y)` is the column and
+    /// line index of a character. Characters equal to `background` are
+    /// not stored.
+    pub fn parse<S, F>(lines: &[S], background: T, mut fun: F) -> Self
+    where
+        S: AsRef<str>,
+        F: FnMut(char) -> T,
+    {
+        let mut grid = Self::new(background);
+
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.as_ref().chars().enumerate() {
+                grid.insert([x as i64, y as i64], fun(c));
+            }
+        }
+
+        grid
+    }
+
+    pub fn background(&self) -> &T {
+        &self.background
+    }
+
+    /// Sets the cell at `p`, returning its old value if it was not the
+    /// background. Setting a cell to the background removes it.
+    pub fn insert(&mut self, p: Point, value: T) -> Option<T> {
+        if value == self.background {
+            self.cells.remove(&p)
+        } else {
+            self.cells.insert(p, value)
+        }
+    }
+
+    pub fn remove(&mut self, p: Point) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    pub fn get(&self, p: Point) -> &T {
+        self.cells.get(&p).unwrap_or(&self.background)
+    }
+
+    /// Returns `true` if the cell at `p` is not the background.
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    /// Number of cells that are not the background.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// All cells that are not the background, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(&p, v)| (p, v))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// Bounding box of all cells that are not the background.
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(self.points())
+    }
+
+    /// Renders the cells within `bounds` using `fun`.
+    pub fn render<F>(&self, bounds: Bounds, mut fun: F) -> String
+    where
+        F: FnMut(&T) -> char,
+    {
+        bounds.render(|p| fun(self.get(p)))
+    }
+}
+
+impl<T: PartialEq + Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bounds = match self.bounds() {
+            Some(b) => b,
+            None => return Ok(()),
+        };
+
+        for y in bounds.min[1]..=bounds.max[1] {
+            if y > bounds.min[1] {
+                writeln!(f)?;
+            }
+
+            for x in bounds.min[0]..=bounds.max[0] {
+                write!(f, "{}", self.get([x, y]))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds() {
+        let bounds = Bounds::from_points([[1, 2], [-1, 5], [3, 3]]).unwrap();
+        assert_eq!(bounds, Bounds::new([-1, 2], [3, 5]));
+        assert_eq!((bounds.width(), bounds.height(), bounds.area()), (5, 4, 20));
+        assert!(bounds.contains([0, 4]));
+        assert!(!bounds.contains([0, 6]));
+        assert_eq!(bounds.points().count(), 20);
+        assert_eq!(Bounds::from_points(None), None);
+
+        let small = Bounds::new([0, 0], [1, 0]);
+        let text = small
+            .expand(1)
+            .render(|p| if small.contains(p) { '#' } else { '.' });
+        assert_eq!(text, "....\n.##.\n....");
+    }
+
+    #[test]
+    fn test_sparse() {
+        let mut grid = SparseGrid::parse(&[".#.", "..#"], '.', |c| c);
+        assert_eq!(grid.len(), 2);
+        assert_eq!(*grid.get([1, 0]), '#');
+        assert_eq!(*grid.get([-100, 100]), '.');
+        assert_eq!(grid.bounds(), Some(Bounds::new([1, 0], [2, 1])));
+
+        grid.insert([-1, -1], '#');
+        grid.insert([1, 0], '.');
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.to_string(), "#...\n....\n...#");
+    }
+}